@@ -2,7 +2,7 @@ use crate::{ChainSpec, DepositContract};
 use alloc::{boxed::Box, vec::Vec};
 use alloy_chains::Chain;
 use alloy_consensus::Header;
-use alloy_eips::{eip1559::BaseFeeParams, eip7840::BlobParams};
+use alloy_eips::{eip1559::BaseFeeParams, eip7840::BlobParams, merge::BEACON_NONCE};
 use alloy_genesis::Genesis;
 use alloy_primitives::B256;
 use core::fmt::{Debug, Display};
@@ -53,6 +53,13 @@ pub trait EthChainSpec: Send + Sync + Unpin + Debug {
     /// The bootnodes for the chain, if any.
     fn bootnodes(&self) -> Option<Vec<NodeRecord>>;
 
+    /// Returns the nonce that is set in the headers of post-merge blocks.
+    ///
+    /// Defaults to [`BEACON_NONCE`].
+    fn block_nonce(&self) -> u64 {
+        BEACON_NONCE
+    }
+
     /// Returns `true` if this chain contains Optimism configuration.
     fn is_optimism(&self) -> bool {
         self.chain().is_optimism()
@@ -121,3 +128,79 @@ impl EthChainSpec for ChainSpec {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MAINNET;
+    use alloy_primitives::B64;
+
+    /// Chain spec that uses a custom header nonce.
+    #[derive(Debug)]
+    struct CustomNonceSpec(ChainSpec);
+
+    impl EthChainSpec for CustomNonceSpec {
+        type Header = Header;
+
+        fn chain(&self) -> Chain {
+            self.0.chain()
+        }
+
+        fn base_fee_params_at_block(&self, block_number: u64) -> BaseFeeParams {
+            self.0.base_fee_params_at_block(block_number)
+        }
+
+        fn base_fee_params_at_timestamp(&self, timestamp: u64) -> BaseFeeParams {
+            self.0.base_fee_params_at_timestamp(timestamp)
+        }
+
+        fn blob_params_at_timestamp(&self, timestamp: u64) -> Option<BlobParams> {
+            EthChainSpec::blob_params_at_timestamp(&self.0, timestamp)
+        }
+
+        fn deposit_contract(&self) -> Option<&DepositContract> {
+            EthChainSpec::deposit_contract(&self.0)
+        }
+
+        fn genesis_hash(&self) -> B256 {
+            self.0.genesis_hash()
+        }
+
+        fn prune_delete_limit(&self) -> usize {
+            EthChainSpec::prune_delete_limit(&self.0)
+        }
+
+        fn display_hardforks(&self) -> Box<dyn Display> {
+            EthChainSpec::display_hardforks(&self.0)
+        }
+
+        fn genesis_header(&self) -> &Self::Header {
+            self.0.genesis_header()
+        }
+
+        fn genesis(&self) -> &Genesis {
+            self.0.genesis()
+        }
+
+        fn bootnodes(&self) -> Option<Vec<NodeRecord>> {
+            self.0.bootnodes()
+        }
+
+        fn block_nonce(&self) -> u64 {
+            0x42
+        }
+    }
+
+    #[test]
+    fn block_nonce_defaults_to_beacon_nonce() {
+        assert_eq!(MAINNET.block_nonce(), BEACON_NONCE);
+    }
+
+    #[test]
+    fn block_nonce_override() {
+        let spec = CustomNonceSpec(ChainSpec::clone(&MAINNET));
+        let nonce: B64 = spec.block_nonce().into();
+        assert_eq!(nonce, B64::from(0x42u64));
+        assert_ne!(nonce, B64::from(BEACON_NONCE));
+    }
+}
@@ -142,7 +142,7 @@ where
             .maybe_next_block_excess_blob_gas(
                 self.chain_spec.blob_params_at_timestamp(attributes.timestamp),
             )
-            .or_else(|| (spec_id == SpecId::CANCUN).then_some(0))
+            .or_else(|| (spec_id >= SpecId::CANCUN).then_some(0))
             .map(|gas| BlobExcessGasAndPrice::new(gas, spec_id >= SpecId::PRAGUE));
 
         let mut basefee = parent.next_block_base_fee(
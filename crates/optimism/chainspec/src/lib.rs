@@ -238,6 +238,10 @@ impl EthChainSpec for OpChainSpec {
         self.inner.bootnodes()
     }
 
+    fn block_nonce(&self) -> u64 {
+        self.inner.block_nonce()
+    }
+
     fn is_optimism(&self) -> bool {
         true
     }
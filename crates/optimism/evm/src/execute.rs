@@ -304,15 +304,16 @@ impl OpExecutorProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::OpChainSpec;
+    use crate::{OpChainSpec, OpNextBlockEnvAttributes};
     use alloy_consensus::{Block, BlockBody, Header, TxEip1559};
     use alloy_primitives::{
         b256, Address, PrimitiveSignature as Signature, StorageKey, StorageValue, U256,
     };
     use op_alloy_consensus::{OpTypedTransaction, TxDeposit};
-    use reth_chainspec::MIN_TRANSACTION_GAS;
+    use reth_chainspec::{ForkCondition, MIN_TRANSACTION_GAS};
     use reth_evm::execute::{BasicBlockExecutorProvider, BlockExecutorProvider, Executor};
     use reth_optimism_chainspec::OpChainSpecBuilder;
+    use reth_optimism_forks::OpHardfork;
     use reth_optimism_primitives::{OpReceipt, OpTransactionSigned};
     use reth_primitives_traits::{Account, RecoveredBlock};
     use reth_revm::{database::StateProviderDatabase, test_utils::StateProviderTest};
@@ -504,4 +505,74 @@ mod tests {
         // deposit_nonce is present only in deposit transactions
         assert!(deposit_receipt.deposit_nonce.is_some());
     }
+
+    fn execute_calldata_heavy_tx_at(chain_spec: Arc<OpChainSpec>, timestamp: u64) -> u64 {
+        let mut db = create_op_state_provider();
+        let addr = Address::ZERO;
+        let account = Account { balance: U256::MAX, ..Account::default() };
+        db.insert_account(addr, account, None, HashMap::default());
+
+        let parent = SealedHeader::seal_slow(Header {
+            number: 1,
+            timestamp: 10,
+            gas_limit: 30_000_000,
+            // holocene encoded eip-1559 params, zero means the chain spec defaults are used
+            extra_data: vec![0u8; 9].into(),
+            ..Default::default()
+        });
+
+        let tx = OpTransactionSigned::new_unhashed(
+            OpTypedTransaction::Eip1559(TxEip1559 {
+                chain_id: chain_spec.chain.id(),
+                nonce: 0,
+                gas_limit: 100_000,
+                to: Address::random().into(),
+                input: vec![1u8; 1000].into(),
+                ..Default::default()
+            }),
+            Signature::test_signature(),
+        );
+
+        let evm_config = OpEvmConfig::new(chain_spec, BasicOpReceiptBuilder::default());
+        let mut state = State::builder()
+            .with_database(StateProviderDatabase::new(&db))
+            .with_bundle_update()
+            .build();
+
+        // make sure the L1 block contract state is preloaded.
+        state.load_cache_account(L1_BLOCK_CONTRACT).unwrap();
+
+        let mut strategy = evm_config
+            .strategy_for_next_block(
+                &mut state,
+                &parent,
+                OpNextBlockEnvAttributes {
+                    timestamp,
+                    suggested_fee_recipient: Address::ZERO,
+                    prev_randao: B256::ZERO,
+                    gas_limit: 30_000_000,
+                    parent_beacon_block_root: Some(B256::ZERO),
+                    extra_data: Default::default(),
+                },
+            )
+            .unwrap();
+        strategy.apply_pre_execution_changes().unwrap();
+        strategy.execute_transaction(Recovered::new_unchecked(&tx, addr)).unwrap()
+    }
+
+    #[test]
+    fn calldata_floor_gas_in_next_block() {
+        let chain_spec = Arc::new(
+            OpChainSpecBuilder::base_mainnet()
+                .holocene_activated()
+                .with_fork(OpHardfork::Isthmus, ForkCondition::Timestamp(20))
+                .build(),
+        );
+
+        // 21000 intrinsic gas + 1000 non-zero calldata bytes at 16 gas each
+        assert_eq!(execute_calldata_heavy_tx_at(chain_spec.clone(), 12), 37_000);
+
+        // EIP-7623: 21000 + 4000 tokens at the floor cost of 10 gas each
+        assert_eq!(execute_calldata_heavy_tx_at(chain_spec, 20), 61_000);
+    }
 }
@@ -148,7 +148,7 @@ where
                 self.chain_spec().blob_params_at_timestamp(attributes.timestamp),
             )
             .or_else(|| (spec_id.into_eth_spec().is_enabled_in(SpecId::CANCUN)).then_some(0))
-            .map(|gas| BlobExcessGasAndPrice::new(gas, false));
+            .map(|gas| BlobExcessGasAndPrice::new(gas, spec_id.into_eth_spec() >= SpecId::PRAGUE));
 
         let block_env = BlockEnv {
             number: parent.number() + 1,
@@ -231,6 +231,38 @@ mod tests {
         assert_eq!(cfg_env.chain_id, chain_spec.chain().id());
     }
 
+    #[test]
+    fn test_next_evm_env_blob_base_fee() {
+        let evm_config = test_evm_config();
+
+        // Base Mainnet after Ecotone, before Holocene
+        let parent = Header {
+            timestamp: 1724928889,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1_000_000),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            parent_beacon_block_root: Some(B256::ZERO),
+            ..Default::default()
+        };
+        let attributes = OpNextBlockEnvAttributes {
+            timestamp: parent.timestamp + 2,
+            suggested_fee_recipient: Address::ZERO,
+            prev_randao: B256::ZERO,
+            gas_limit: parent.gas_limit,
+            parent_beacon_block_root: Some(B256::ZERO),
+            extra_data: Default::default(),
+        };
+
+        let evm_env = evm_config.next_evm_env(&parent, &attributes).unwrap();
+        let blob_excess_gas_and_price = evm_env.block_env.blob_excess_gas_and_price.unwrap();
+
+        // there are no blob transactions on OP Stack chains, so the blob base fee stays at the
+        // minimum
+        assert_eq!(blob_excess_gas_and_price.excess_blob_gas, 0);
+        assert_eq!(blob_excess_gas_and_price.blob_gasprice, 1);
+    }
+
     #[test]
     fn test_evm_with_env_default_spec() {
         let evm_config = test_evm_config();
@@ -26,10 +26,16 @@ pub struct RollupArgs {
     ///
     /// If `compute_pending_block` is not enabled, the payload builder
     /// will use the payload attributes from the latest block. Note
-    /// that this flag is not yet functional.
+    /// that this flag is not yet functional for the payload builder,
+    /// but the RPC serves the pending block built from the tx-pool.
     #[arg(long = "rollup.compute-pending-block")]
     pub compute_pending_block: bool,
 
+    /// Burn the base fee of transactions in the pending block computed from the tx-pool instead
+    /// of crediting it to the base fee vault, for forks with L1-style fee routing
+    #[arg(long = "rollup.pending-block-burn-base-fee")]
+    pub pending_block_burn_base_fee: bool,
+
     /// enables discovery v4 if provided
     #[arg(long = "rollup.discovery.v4", default_value = "false")]
     pub discovery_v4: bool,
@@ -47,6 +53,7 @@ impl Default for RollupArgs {
             disable_txpool_gossip: false,
             enable_genesis_walkback: false,
             compute_pending_block: false,
+            pending_block_burn_base_fee: false,
             discovery_v4: false,
             enable_tx_conditional: false,
         }
@@ -90,6 +97,17 @@ mod tests {
         assert_eq!(args, expected_args);
     }
 
+    #[test]
+    fn test_parse_optimism_pending_block_burn_base_fee_args() {
+        let expected_args = RollupArgs { pending_block_burn_base_fee: true, ..Default::default() };
+        let args = CommandParser::<RollupArgs>::parse_from([
+            "reth",
+            "--rollup.pending-block-burn-base-fee",
+        ])
+        .args;
+        assert_eq!(args, expected_args);
+    }
+
     #[test]
     fn test_parse_optimism_discovery_v4_args() {
         let expected_args = RollupArgs { discovery_v4: true, ..Default::default() };
@@ -37,7 +37,7 @@ use reth_optimism_payload_builder::{
 };
 use reth_optimism_primitives::{DepositReceipt, OpPrimitives, OpReceipt, OpTransactionSigned};
 use reth_optimism_rpc::{
-    eth::{ext::OpEthExtApi, OpEthApiBuilder},
+    eth::{ext::OpEthExtApi, OpEthApiBuilder, OpFeeRouting},
     miner::{MinerApiExtServer, OpMinerExtApi},
     witness::{DebugExecutionWitnessApiServer, OpDebugWitnessApi},
     OpEthApi, OpEthApiError, SequencerClient,
@@ -192,6 +192,12 @@ where
             .with_sequencer(self.args.sequencer_http.clone())
             .with_da_config(self.da_config.clone())
             .with_enable_tx_conditional(self.args.enable_tx_conditional)
+            .with_compute_pending_block(self.args.compute_pending_block)
+            .with_fee_routing(if self.args.pending_block_burn_base_fee {
+                OpFeeRouting::Burn
+            } else {
+                OpFeeRouting::Vaults
+            })
             .build()
     }
 }
@@ -398,6 +404,10 @@ pub struct OpAddOnsBuilder {
     da_config: Option<OpDAConfig>,
     /// Enable transaction conditionals.
     enable_tx_conditional: bool,
+    /// Whether the pending block served by the RPC is built from the transaction pool.
+    compute_pending_block: bool,
+    /// Routing of the base fee in the pending block built from the transaction pool.
+    fee_routing: OpFeeRouting,
 }
 
 impl OpAddOnsBuilder {
@@ -418,6 +428,19 @@ impl OpAddOnsBuilder {
         self.enable_tx_conditional = enable_tx_conditional;
         self
     }
+
+    /// Configure if the pending block served by the RPC should be built from the transaction
+    /// pool.
+    pub const fn with_compute_pending_block(mut self, compute_pending_block: bool) -> Self {
+        self.compute_pending_block = compute_pending_block;
+        self
+    }
+
+    /// Configure the [`OpFeeRouting`] of the pending block built from the transaction pool.
+    pub const fn with_fee_routing(mut self, fee_routing: OpFeeRouting) -> Self {
+        self.fee_routing = fee_routing;
+        self
+    }
 }
 
 impl OpAddOnsBuilder {
@@ -427,12 +450,21 @@ impl OpAddOnsBuilder {
         N: FullNodeComponents<Types: NodeTypes<Primitives = OpPrimitives>>,
         OpEthApiBuilder: EthApiBuilder<N>,
     {
-        let Self { sequencer_client, da_config, enable_tx_conditional } = self;
+        let Self {
+            sequencer_client,
+            da_config,
+            enable_tx_conditional,
+            compute_pending_block,
+            fee_routing,
+        } = self;
 
         let sequencer_client_clone = sequencer_client.clone();
         OpAddOns {
             rpc_add_ons: RpcAddOns::new(
-                OpEthApiBuilder::default().with_sequencer(sequencer_client_clone),
+                OpEthApiBuilder::default()
+                    .with_sequencer(sequencer_client_clone)
+                    .with_compute_pending_block(compute_pending_block)
+                    .with_fee_routing(fee_routing),
                 Default::default(),
                 Default::default(),
             ),
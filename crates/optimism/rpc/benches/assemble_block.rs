@@ -0,0 +1,86 @@
+#![allow(missing_docs)]
+use alloy_consensus::{transaction::Recovered, Header, Receipt};
+use alloy_primitives::{Address, TxKind, B256};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use op_alloy_consensus::{OpDepositReceipt, OpTypedTransaction, TxDeposit};
+use reth_optimism_chainspec::OP_MAINNET;
+use reth_optimism_primitives::{OpReceipt, OpTransactionSigned};
+use reth_optimism_rpc::eth::assemble_pending_block;
+use reth_primitives::SealedHeader;
+use reth_provider::BlockExecutionResult;
+use revm::context::BlockEnv;
+
+/// Number of transactions in the assembled pending block.
+const TX_COUNT: u64 = 10_000;
+
+/// Timestamp of OP mainnet block 124665056, after Ecotone.
+const ECOTONE_TIMESTAMP: u64 = 1724928889;
+
+/// Assembles a pending block from a large set of already executed transactions.
+pub fn assemble_block(c: &mut Criterion) {
+    let parent = SealedHeader::seal_slow(Header { number: 9, ..Default::default() });
+    let block_env = BlockEnv {
+        number: 10,
+        timestamp: ECOTONE_TIMESTAMP,
+        gas_limit: u64::MAX,
+        basefee: 7,
+        ..Default::default()
+    };
+
+    let transactions = (0..TX_COUNT)
+        .map(|i| {
+            let from = Address::random();
+            let tx = OpTransactionSigned::new_unhashed(
+                OpTypedTransaction::Deposit(TxDeposit {
+                    source_hash: B256::random(),
+                    from,
+                    to: TxKind::Call(Address::random()),
+                    gas_limit: 21_000 + i,
+                    ..Default::default()
+                }),
+                TxDeposit::signature(),
+            );
+            Recovered::new_unchecked(tx, from)
+        })
+        .collect::<Vec<_>>();
+    let result = BlockExecutionResult {
+        receipts: (1..=TX_COUNT)
+            .map(|i| {
+                OpReceipt::Deposit(OpDepositReceipt {
+                    inner: Receipt {
+                        status: true.into(),
+                        cumulative_gas_used: 21_000 * i,
+                        logs: vec![],
+                    },
+                    deposit_nonce: Some(i),
+                    deposit_receipt_version: Some(1),
+                })
+            })
+            .collect(),
+        requests: Default::default(),
+        gas_used: 21_000 * TX_COUNT,
+    };
+
+    let mut group = c.benchmark_group("Assemble pending block");
+    group.bench_function(format!("{TX_COUNT} transactions"), |b| {
+        b.iter_batched(
+            || transactions.clone(),
+            |transactions| {
+                assemble_pending_block(
+                    &**OP_MAINNET,
+                    &block_env,
+                    &result,
+                    &parent,
+                    B256::ZERO,
+                    transactions,
+                )
+                .unwrap()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, assemble_block);
+criterion_main!(benches);
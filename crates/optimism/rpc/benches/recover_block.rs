@@ -0,0 +1,28 @@
+#![allow(missing_docs)]
+use criterion::{criterion_group, criterion_main, Criterion};
+use reth_primitives::RecoveredBlock;
+use reth_testing_utils::generators::{self, BlockParams};
+use std::sync::Arc;
+
+/// Compares recovering the senders of a full block against reusing an already recovered block.
+pub fn recover_block(c: &mut Criterion) {
+    let mut rng = generators::rng();
+    let block = generators::random_block(
+        &mut rng,
+        1,
+        BlockParams { tx_count: Some(u8::MAX), ..Default::default() },
+    );
+    let cached = Arc::new(RecoveredBlock::try_recover_sealed(block.clone()).unwrap());
+
+    let mut group = c.benchmark_group("Recover block");
+    group.bench_function("recover senders", |b| {
+        b.iter(|| RecoveredBlock::try_recover_sealed(block.clone()).unwrap())
+    });
+    group.bench_function("reuse recovered block", |b| {
+        b.iter(|| Arc::unwrap_or_clone(cached.clone()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, recover_block);
+criterion_main!(benches);
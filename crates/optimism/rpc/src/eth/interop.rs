@@ -0,0 +1,97 @@
+//! Validation of cross-chain messages referenced by transactions in the OP pending block.
+
+use alloy_consensus::Transaction;
+use alloy_primitives::{address, Address, B256};
+use std::fmt;
+
+/// The predeploy through which interop transactions consume cross-chain messages.
+///
+/// Transactions declare the messages they depend on as storage keys of this address in their
+/// access list.
+pub const CROSS_L2_INBOX: Address = address!("4200000000000000000000000000000000000022");
+
+/// Validates cross-chain messages referenced by transactions in the locally built pending block.
+///
+/// Transactions with a message the oracle rejects are skipped, because their execution depends on
+/// a message that isn't (yet) valid on its source chain.
+pub trait InteropOracle: fmt::Debug + Send + Sync + 'static {
+    /// Returns whether the given message, as declared in the access list of a transaction, is
+    /// valid.
+    fn is_valid(&self, message: &B256) -> bool;
+}
+
+/// An [`InteropOracle`] that accepts all messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AcceptAllInteropOracle;
+
+impl InteropOracle for AcceptAllInteropOracle {
+    fn is_valid(&self, _message: &B256) -> bool {
+        true
+    }
+}
+
+/// Returns the cross-chain messages the given transaction depends on.
+pub fn interop_messages<T: Transaction>(tx: &T) -> impl Iterator<Item = &B256> {
+    tx.access_list()
+        .into_iter()
+        .flat_map(|access_list| access_list.iter())
+        .filter(|item| item.address == CROSS_L2_INBOX)
+        .flat_map(|item| item.storage_keys.iter())
+}
+
+/// Returns whether all cross-chain messages the given transaction depends on are valid according
+/// to the oracle.
+pub fn interop_dependencies_satisfied<T: Transaction>(oracle: &dyn InteropOracle, tx: &T) -> bool {
+    interop_messages(tx).all(|message| oracle.is_valid(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::TxEip1559;
+    use alloy_eips::eip2930::{AccessList, AccessListItem};
+    use std::collections::HashSet;
+
+    /// Accepts only the contained messages.
+    #[derive(Debug, Default)]
+    struct KnownMessages(HashSet<B256>);
+
+    impl InteropOracle for KnownMessages {
+        fn is_valid(&self, message: &B256) -> bool {
+            self.0.contains(message)
+        }
+    }
+
+    fn tx_with_access_list(items: Vec<AccessListItem>) -> TxEip1559 {
+        TxEip1559 { access_list: AccessList(items), ..Default::default() }
+    }
+
+    #[test]
+    fn skips_unsatisfied_interop_message() {
+        let known = B256::with_last_byte(1);
+        let unknown = B256::with_last_byte(2);
+        let oracle = KnownMessages(HashSet::from([known]));
+
+        let satisfied = tx_with_access_list(vec![AccessListItem {
+            address: CROSS_L2_INBOX,
+            storage_keys: vec![known],
+        }]);
+        assert!(interop_dependencies_satisfied(&oracle, &satisfied));
+
+        let unsatisfied = tx_with_access_list(vec![AccessListItem {
+            address: CROSS_L2_INBOX,
+            storage_keys: vec![known, unknown],
+        }]);
+        assert!(!interop_dependencies_satisfied(&oracle, &unsatisfied));
+        assert!(interop_dependencies_satisfied(&AcceptAllInteropOracle, &unsatisfied));
+
+        // storage keys of other addresses aren't messages
+        let unrelated = tx_with_access_list(vec![AccessListItem {
+            address: Address::with_last_byte(1),
+            storage_keys: vec![unknown],
+        }]);
+        assert_eq!(interop_messages(&unrelated).count(), 0);
+        assert!(interop_dependencies_satisfied(&oracle, &unrelated));
+    }
+}
@@ -6,8 +6,17 @@ pub mod transaction;
 
 mod block;
 mod call;
+mod interop;
 mod pending_block;
 
+pub use interop::{
+    interop_dependencies_satisfied, interop_messages, AcceptAllInteropOracle, InteropOracle,
+    CROSS_L2_INBOX,
+};
+pub use pending_block::{
+    assemble_pending_block, OpFeeRouting, OpReceiptPostProcessor, OpSelectionBaseFee,
+    BASE_FEE_VAULT, L1_FEE_VAULT,
+};
 pub use receipt::{OpReceiptBuilder, OpReceiptFieldsBuilder};
 
 use alloy_primitives::U256;
@@ -15,7 +24,7 @@ use op_alloy_network::Optimism;
 use reth_chainspec::{EthChainSpec, EthereumHardforks};
 use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
-use reth_node_api::{BlockTy, FullNodeComponents, ReceiptTy};
+use reth_node_api::{BlockTy, FullNodeComponents, NodePrimitives, ReceiptTy};
 use reth_node_builder::rpc::EthApiBuilder;
 use reth_optimism_primitives::OpPrimitives;
 use reth_provider::{
@@ -31,7 +40,9 @@ use reth_rpc_eth_api::{
     },
     EthApiTypes, FromEvmError, FullEthApiServer, RpcNodeCore, RpcNodeCoreExt,
 };
-use reth_rpc_eth_types::{EthConfig, EthStateCache, FeeHistoryCache, GasPriceOracle};
+use reth_rpc_eth_types::{
+    EthConfig, EthStateCache, FeeHistoryCache, GasPriceOracle, PendingBlockConfig,
+};
 use reth_tasks::{
     pool::{BlockingTaskGuard, BlockingTaskPool},
     TaskSpawner,
@@ -282,6 +293,18 @@ struct OpEthApiInner<N: OpNodeCore> {
     /// Sequencer client, configured to forward submitted transactions to sequencer of given OP
     /// network.
     sequencer_client: Option<SequencerClient>,
+    /// Routing of the base fee in the locally built pending block.
+    fee_routing: OpFeeRouting,
+    /// Validates the cross-chain messages of transactions in the locally built pending block.
+    interop_oracle: Arc<dyn InteropOracle>,
+    /// Transforms the receipts of the locally built pending block.
+    receipt_post_processor: Option<OpReceiptPostProcessor>,
+    /// Determines the base fee pool transactions of the locally built pending block are selected
+    /// against.
+    selection_base_fee: Option<OpSelectionBaseFee>,
+    /// Whether the pending block is built locally from the transaction pool, instead of being the
+    /// latest block.
+    compute_pending_block: bool,
 }
 
 impl<N: OpNodeCore> OpEthApiInner<N> {
@@ -297,17 +320,56 @@ impl<N: OpNodeCore> OpEthApiInner<N> {
 }
 
 /// Builds [`OpEthApi`] for Optimism.
-#[derive(Debug, Default)]
+#[derive(Default)]
 pub struct OpEthApiBuilder {
     /// Sequencer client, configured to forward submitted transactions to sequencer of given OP
     /// network.
     sequencer_client: Option<SequencerClient>,
+    /// Routing of the base fee in the locally built pending block.
+    fee_routing: OpFeeRouting,
+    /// Gas reserved for deposit transactions in the locally built pending block.
+    deposit_gas_reserve: Option<u64>,
+    /// Configuration for building the local pending block.
+    pending_block_config: Option<PendingBlockConfig>,
+    /// Validates the cross-chain messages of transactions in the locally built pending block,
+    /// defaults to [`AcceptAllInteropOracle`].
+    interop_oracle: Option<Arc<dyn InteropOracle>>,
+    /// Transforms the receipts of the locally built pending block before its receipt root is
+    /// calculated.
+    receipt_post_processor: Option<OpReceiptPostProcessor>,
+    /// Determines the base fee pool transactions of the locally built pending block are selected
+    /// against, defaults to the base fee of the block.
+    selection_base_fee: Option<OpSelectionBaseFee>,
+    /// Whether the pending block is built locally from the transaction pool.
+    compute_pending_block: bool,
+}
+
+impl fmt::Debug for OpEthApiBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpEthApiBuilder")
+            .field("sequencer_client", &self.sequencer_client)
+            .field("fee_routing", &self.fee_routing)
+            .field("deposit_gas_reserve", &self.deposit_gas_reserve)
+            .field("pending_block_config", &self.pending_block_config)
+            .field("interop_oracle", &self.interop_oracle)
+            .field("compute_pending_block", &self.compute_pending_block)
+            .finish_non_exhaustive()
+    }
 }
 
 impl OpEthApiBuilder {
     /// Creates a [`OpEthApiBuilder`] instance from core components.
     pub const fn new() -> Self {
-        Self { sequencer_client: None }
+        Self {
+            sequencer_client: None,
+            fee_routing: OpFeeRouting::Vaults,
+            deposit_gas_reserve: None,
+            pending_block_config: None,
+            interop_oracle: None,
+            receipt_post_processor: None,
+            selection_base_fee: None,
+            compute_pending_block: false,
+        }
     }
 
     /// With a [`SequencerClient`].
@@ -315,6 +377,117 @@ impl OpEthApiBuilder {
         self.sequencer_client = sequencer_client;
         self
     }
+
+    /// With the [`OpFeeRouting`] used for the locally built pending block.
+    pub const fn with_fee_routing(mut self, fee_routing: OpFeeRouting) -> Self {
+        self.fee_routing = fee_routing;
+        self
+    }
+
+    /// With the gas reserved for deposit transactions in the locally built pending block, which
+    /// pool transactions can't consume.
+    pub const fn with_deposit_gas_reserve(mut self, deposit_gas_reserve: Option<u64>) -> Self {
+        self.deposit_gas_reserve = deposit_gas_reserve;
+        self
+    }
+
+    /// With the [`PendingBlockConfig`] for building the local pending block.
+    ///
+    /// A gas reserve set with [`OpEthApiBuilder::with_deposit_gas_reserve`] takes precedence over
+    /// the one of the config.
+    pub fn with_pending_block_config(mut self, pending_block_config: PendingBlockConfig) -> Self {
+        self.pending_block_config = Some(pending_block_config);
+        self
+    }
+
+    /// With the [`InteropOracle`] that validates the cross-chain messages of transactions in the
+    /// locally built pending block, once interop is active.
+    pub fn with_interop_oracle(mut self, interop_oracle: impl InteropOracle) -> Self {
+        self.interop_oracle = Some(Arc::new(interop_oracle));
+        self
+    }
+
+    /// With an [`OpReceiptPostProcessor`] applied to each receipt of the locally built pending
+    /// block before its receipt root is calculated.
+    pub fn with_receipt_post_processor(
+        mut self,
+        receipt_post_processor: Option<OpReceiptPostProcessor>,
+    ) -> Self {
+        self.receipt_post_processor = receipt_post_processor;
+        self
+    }
+
+    /// With an [`OpSelectionBaseFee`] that determines the base fee pool transactions of the
+    /// locally built pending block are selected against, given the gas its deposits used.
+    pub fn with_selection_base_fee(
+        mut self,
+        selection_base_fee: Option<OpSelectionBaseFee>,
+    ) -> Self {
+        self.selection_base_fee = selection_base_fee;
+        self
+    }
+
+    /// With the pending block built locally from the transaction pool, instead of serving the
+    /// latest block as pending.
+    ///
+    /// Only a locally built pending block applies the [`OpFeeRouting`], the [`InteropOracle`], the
+    /// [`OpReceiptPostProcessor`] and the [`OpSelectionBaseFee`].
+    pub const fn with_compute_pending_block(mut self, compute_pending_block: bool) -> Self {
+        self.compute_pending_block = compute_pending_block;
+        self
+    }
+
+    /// Builds the [`OpEthApi`] on top of the given builder of its `eth_` backend.
+    fn build_with<N>(
+        self,
+        eth_api: reth_rpc::EthApiBuilder<N::Provider, N::Pool, N::Network, N::Evm>,
+    ) -> OpEthApi<N>
+    where
+        N: OpNodeCore<
+            Provider: BlockReaderIdExt
+                          + StateProviderFactory
+                          + ChainSpecProvider
+                          + CanonStateSubscriptions<
+                Primitives: NodePrimitives<
+                    Block = ProviderBlock<N::Provider>,
+                    Receipt = ProviderReceipt<N::Provider>,
+                >,
+            > + Clone
+                          + Unpin
+                          + 'static,
+        >,
+    {
+        let Self {
+            sequencer_client,
+            fee_routing,
+            deposit_gas_reserve,
+            pending_block_config,
+            interop_oracle,
+            receipt_post_processor,
+            selection_base_fee,
+            compute_pending_block,
+        } = self;
+        let interop_oracle = interop_oracle.unwrap_or_else(|| Arc::new(AcceptAllInteropOracle));
+        let pending_block_config = pending_block_config.unwrap_or_default();
+        let deposit_gas_reserve = deposit_gas_reserve.or(pending_block_config.deposit_gas_reserve);
+        let eth_api = eth_api
+            .pending_block_config(
+                pending_block_config.with_deposit_gas_reserve(deposit_gas_reserve),
+            )
+            .build_inner();
+
+        OpEthApi {
+            inner: Arc::new(OpEthApiInner {
+                eth_api,
+                sequencer_client,
+                fee_routing,
+                interop_oracle,
+                receipt_post_processor,
+                selection_base_fee,
+                compute_pending_block,
+            }),
+        }
+    }
 }
 
 impl<N> EthApiBuilder<N> for OpEthApiBuilder
@@ -330,7 +503,6 @@ where
         config: EthConfig,
         cache: EthStateCache<BlockTy<N::Types>, ReceiptTy<N::Types>>,
     ) -> Self::EthApi {
-        let Self { sequencer_client } = self;
         let eth_api = reth_rpc::EthApiBuilder::new(
             core_components.provider().clone(),
             core_components.pool().clone(),
@@ -343,9 +515,8 @@ where
         .max_simulate_blocks(config.rpc_max_simulate_blocks)
         .eth_proof_window(config.eth_proof_window)
         .fee_history_cache_config(config.fee_history_cache)
-        .proof_permits(config.proof_permits)
-        .build_inner();
+        .proof_permits(config.proof_permits);
 
-        OpEthApi { inner: Arc::new(OpEthApiInner { eth_api, sequencer_client }) }
+        self.build_with(eth_api)
     }
 }
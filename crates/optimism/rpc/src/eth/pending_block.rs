@@ -1,32 +1,84 @@
 //! Loads OP pending block for a RPC response.
 
-use crate::OpEthApi;
+use crate::{
+    eth::{interop_dependencies_satisfied, OpNodeCore},
+    OpEthApi, OpEthApiError, OpReceiptBuilder,
+};
 use alloy_consensus::{
-    constants::EMPTY_WITHDRAWALS, proofs::calculate_transaction_root, transaction::Recovered,
+    constants::EMPTY_WITHDRAWALS,
+    proofs::calculate_transaction_root,
+    transaction::{Recovered, TransactionMeta},
     BlockHeader, Header, Transaction as _, TxReceipt, EMPTY_OMMER_ROOT_HASH,
 };
-use alloy_eips::{eip7685::EMPTY_REQUESTS_HASH, merge::BEACON_NONCE, BlockNumberOrTag};
-use alloy_primitives::{B256, U256};
+use alloy_eips::{eip2718::Encodable2718, eip7685::EMPTY_REQUESTS_HASH, BlockNumberOrTag};
+use alloy_primitives::{address, Address, B256, U256};
+use op_alloy_rpc_types::OpTransactionReceipt;
 use reth_chainspec::{EthChainSpec, EthereumHardforks};
 use reth_evm::execute::BlockExecutionStrategyFactory;
 use reth_node_api::NodePrimitives;
+use reth_optimism_chainspec::OpChainSpec;
 use reth_optimism_consensus::calculate_receipt_root_no_memo_optimism;
-use reth_optimism_evm::OpNextBlockEnvAttributes;
+use reth_optimism_evm::{
+    extract_l1_info, revm_spec_by_timestamp_after_bedrock, OpNextBlockEnvAttributes,
+    RethL1BlockInfo,
+};
 use reth_optimism_forks::OpHardforks;
 use reth_optimism_primitives::{OpBlock, OpReceipt, OpTransactionSigned};
 use reth_primitives::{logs_bloom, BlockBody, RecoveredBlock, SealedHeader};
+use reth_primitives_traits::SignedTransaction;
 use reth_provider::{
     BlockExecutionResult, BlockReader, BlockReaderIdExt, ChainSpecProvider, ProviderBlock,
-    ProviderHeader, ProviderReceipt, ProviderTx, ReceiptProvider, StateProviderFactory,
+    ProviderError, ProviderHeader, ProviderReceipt, ProviderTx, PruneCheckpointReader,
+    StateProviderFactory,
 };
+use reth_revm::{database::StateProviderDatabase, db::State};
 use reth_rpc_eth_api::{
     helpers::{LoadPendingBlock, SpawnBlocking},
     types::RpcTypes,
     EthApiTypes, FromEthApiError, FromEvmError, RpcNodeCore,
 };
-use reth_rpc_eth_types::{EthApiError, PendingBlock};
+use reth_rpc_eth_types::{ensure_fork_ordering, EthApiError, PendingBlock, PendingBlockConfig};
 use reth_transaction_pool::{PoolTransaction, TransactionPool};
-use revm::{context::BlockEnv, context_interface::Block};
+use revm::{
+    context::BlockEnv, context_interface::Block, primitives::HashMap, state::EvmStorageSlot,
+    Database, DatabaseCommit,
+};
+use revm_optimism::constants::{L1_BASE_FEE_SLOT, L1_BLOCK_CONTRACT};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// The predeploy that collects the base fee on OP Stack chains.
+pub const BASE_FEE_VAULT: Address = address!("4200000000000000000000000000000000000019");
+
+/// The predeploy that collects the L1 data fee on OP Stack chains.
+pub const L1_FEE_VAULT: Address = address!("420000000000000000000000000000000000001a");
+
+/// The default block time of OP Stack chains, used to derive the timestamp of the pending block
+/// unless [`PendingBlockConfig::block_time`] is set.
+///
+/// This determines which hardforks are active in the pending env, e.g. whether the EIP-7623
+/// calldata floor is charged after Isthmus.
+const OP_BLOCK_TIME: u64 = 2;
+
+/// Transforms a receipt of the locally built pending block after execution, before the receipt
+/// root is calculated, e.g. to stamp fork specific receipt fields.
+pub type OpReceiptPostProcessor = Arc<dyn Fn(&mut OpReceipt) + Send + Sync>;
+
+/// Returns the base fee pool transactions of the locally built pending block are selected
+/// against, given its block env and the gas used by the deposits at the top of the block.
+pub type OpSelectionBaseFee = Arc<dyn Fn(&BlockEnv, u64) -> u64 + Send + Sync>;
+
+/// Determines where the base fee of transactions in the locally built pending block ends up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OpFeeRouting {
+    /// Standard OP Stack routing: the base fee is credited to the [`BASE_FEE_VAULT`] and the L1
+    /// data fee to the [`L1_FEE_VAULT`].
+    #[default]
+    Vaults,
+    /// L1-style routing for non-standard forks: the base fee is burnt instead of being collected
+    /// by the [`BASE_FEE_VAULT`].
+    Burn,
+}
 
 impl<N> LoadPendingBlock for OpEthApi<N>
 where
@@ -44,7 +96,8 @@ where
             Receipt = OpReceipt,
             Header = reth_primitives::Header,
         > + ChainSpecProvider<ChainSpec: EthChainSpec + OpHardforks>
-                      + StateProviderFactory,
+                      + StateProviderFactory
+                      + PruneCheckpointReader,
         Pool: TransactionPool<Transaction: PoolTransaction<Consensus = ProviderTx<N::Provider>>>,
         Evm: BlockExecutionStrategyFactory<
             Primitives: NodePrimitives<
@@ -65,21 +118,39 @@ where
         self.inner.eth_api.pending_block()
     }
 
-    fn next_env_attributes(
+    #[inline]
+    fn pending_block_config(&self) -> &PendingBlockConfig {
+        self.inner.eth_api.pending_block_config()
+    }
+
+    #[inline]
+    fn pending_block_sender(
+        &self,
+    ) -> Option<&broadcast::Sender<Arc<RecoveredBlock<ProviderBlock<Self::Provider>>>>> {
+        Some(self.inner.eth_api.pending_block_sender())
+    }
+
+    fn next_env_attributes_with_fee_recipient(
         &self,
         parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+        fee_recipient: Option<Address>,
     ) -> Result<<Self::Evm as reth_evm::ConfigureEvmEnv>::NextBlockEnvCtx, Self::Error> {
+        let block_time = self.pending_block_config().block_time.unwrap_or(OP_BLOCK_TIME);
         Ok(OpNextBlockEnvAttributes {
-            timestamp: parent.timestamp().saturating_add(12),
-            suggested_fee_recipient: parent.beneficiary(),
+            timestamp: parent.timestamp().saturating_add(block_time),
+            suggested_fee_recipient: fee_recipient.unwrap_or_else(|| parent.beneficiary()),
             prev_randao: B256::random(),
-            gas_limit: parent.gas_limit(),
+            gas_limit: self
+                .pending_block_config()
+                .next_gas_limit(parent.gas_limit())
+                .map_err(Self::Error::from_eth_err)?,
             parent_beacon_block_root: parent.parent_beacon_block_root(),
             extra_data: parent.extra_data.clone(),
         })
     }
 
-    /// Returns the locally built pending block
+    /// Returns the locally built pending block if it's computed from the pool, otherwise the
+    /// latest block.
     async fn local_pending_block(
         &self,
     ) -> Result<
@@ -89,26 +160,165 @@ where
         )>,
         Self::Error,
     > {
+        if self.inner.compute_pending_block {
+            return self.build_local_pending_block().await
+        }
+
+        // chains with a fixed block cadence produce a block even if there is nothing to include
+        if self.pending_block_config().produce_empty_blocks && self.pool().pool_size().pending == 0
+        {
+            return self.build_local_pending_block().await
+        }
+
         // See: <https://github.com/ethereum-optimism/op-geth/blob/f2e69450c6eec9c35d56af91389a1c47737206ca/miner/worker.go#L367-L375>
         let latest = self
             .provider()
             .latest_header()
             .map_err(Self::Error::from_eth_err)?
             .ok_or(EthApiError::HeaderNotFound(BlockNumberOrTag::Latest.into()))?;
-        let block_id = latest.hash().into();
-        let block = self
-            .provider()
-            .block_with_senders(block_id, Default::default())
-            .map_err(Self::Error::from_eth_err)?
-            .ok_or(EthApiError::HeaderNotFound(block_id.into()))?;
+        let block_hash = latest.hash();
 
-        let receipts = self
-            .provider()
-            .receipts_by_block(block_id)
+        // the latest block is usually already in the cache, which avoids recovering the senders
+        // of all its transactions again
+        let cache = self.inner.eth_api.cache();
+        let block = cache
+            .get_sealed_block_with_senders(block_hash)
+            .await
             .map_err(Self::Error::from_eth_err)?
-            .ok_or(EthApiError::ReceiptsNotFound(block_id.into()))?;
+            .ok_or(EthApiError::HeaderNotFound(block_hash.into()))?;
+        let receipts = cache
+            .get_receipts(block_hash)
+            .await
+            .map_err(Self::Error::from_eth_err)?
+            .ok_or(EthApiError::ReceiptsNotFound(block_hash.into()))?;
 
-        Ok(Some((block, receipts)))
+        Ok(Some((Arc::unwrap_or_clone(block), Arc::unwrap_or_clone(receipts))))
+    }
+
+    fn apply_pending_pre_execution_changes<DB>(
+        &self,
+        db: &mut State<DB>,
+        _block_env: &BlockEnv,
+    ) -> Result<(), Self::Error>
+    where
+        DB: Database<Error = ProviderError>,
+    {
+        let Some(l1_base_fee) = self.pending_block_config().speculative_l1_base_fee else {
+            return Ok(())
+        };
+        apply_speculative_l1_base_fee(db, l1_base_fee).map_err(Self::Error::from_eth_err)
+    }
+
+    fn apply_pending_state_changes<DB>(
+        &self,
+        db: &mut State<DB>,
+        block_env: &BlockEnv,
+        result: &BlockExecutionResult<ProviderReceipt<Self::Provider>>,
+        transactions: &[Recovered<ProviderTx<Self::Provider>>],
+    ) -> Result<(), Self::Error>
+    where
+        DB: Database<Error = ProviderError>,
+    {
+        if self.inner.fee_routing != OpFeeRouting::Burn {
+            // the OP EVM already credits the vaults during execution
+            return Ok(())
+        }
+
+        // the OP EVM credits the base fee to the vault, so we need to take it out again
+        let mut burnt_fees = U256::ZERO;
+        let mut cumulative_gas_used = 0;
+        for (tx, receipt) in transactions.iter().zip(&result.receipts) {
+            let gas_used = receipt.cumulative_gas_used() - cumulative_gas_used;
+            cumulative_gas_used = receipt.cumulative_gas_used();
+            if tx.is_deposit() {
+                // deposits don't pay the base fee
+                continue
+            }
+            burnt_fees += U256::from(gas_used) * U256::from(block_env.basefee);
+        }
+
+        if burnt_fees.is_zero() {
+            return Ok(())
+        }
+
+        let vault = db.load_cache_account(BASE_FEE_VAULT).map_err(Self::Error::from_eth_err)?;
+        let mut vault_info = vault.account_info().unwrap_or_default();
+        vault_info.balance = vault_info.balance.saturating_sub(burnt_fees);
+
+        let mut vault_account: revm::state::Account = vault_info.into();
+        vault_account.mark_touch();
+        db.commit(HashMap::from_iter([(BASE_FEE_VAULT, vault_account)]));
+
+        Ok(())
+    }
+
+    fn pending_selection_base_fee(&self, block_env: &BlockEnv, system_gas_used: u64) -> u64 {
+        match &self.inner.selection_base_fee {
+            Some(selection_base_fee) => selection_base_fee(block_env, system_gas_used),
+            None => block_env.basefee,
+        }
+    }
+
+    fn post_process_pending_receipts(&self, receipts: &mut [ProviderReceipt<Self::Provider>]) {
+        if let Some(processor) = &self.inner.receipt_post_processor {
+            receipts.iter_mut().for_each(|receipt| processor(receipt));
+        }
+    }
+
+    fn pending_data_fees(
+        &self,
+        block: &RecoveredBlock<ProviderBlock<Self::Provider>>,
+    ) -> Result<U256, Self::Error> {
+        let transactions = &block.body().transactions;
+        // only blocks with transactions start with the L1 info deposit
+        if transactions.is_empty() {
+            return Ok(U256::ZERO)
+        }
+
+        let mut l1_block_info = extract_l1_info(block.body())
+            .map_err(|err| Self::Error::from_eth_err(EthApiError::EvmCustom(err.to_string())))?;
+        let chain_spec = self.provider().chain_spec();
+
+        transactions.iter().try_fold(U256::ZERO, |fees, tx| {
+            // different transactions have different L1 costs
+            l1_block_info.clear_tx_l1_cost();
+            let l1_fee = l1_block_info
+                .l1_tx_data_fee(
+                    &*chain_spec,
+                    block.timestamp(),
+                    block.number(),
+                    &tx.encoded_2718(),
+                    tx.is_deposit(),
+                )
+                .map_err(Self::Error::from_eth_err)?;
+            Ok(fees + l1_fee)
+        })
+    }
+
+    fn is_system_transaction(&self, tx: &ProviderTx<Self::Provider>) -> bool {
+        tx.is_deposit()
+    }
+
+    fn is_pending_transaction_allowed(
+        &self,
+        block_env: &BlockEnv,
+        tx: &Recovered<ProviderTx<Self::Provider>>,
+    ) -> bool {
+        let chain_spec = self.provider().chain_spec();
+
+        // the EVM only applies authorization lists from Isthmus on, before that set code
+        // transactions can't be executed
+        if tx.authorization_list().is_some() &&
+            !chain_spec.is_isthmus_active_at_timestamp(block_env.timestamp)
+        {
+            return false
+        }
+
+        // transactions can only depend on cross-chain messages once interop is active
+        if !chain_spec.is_interop_active_at_timestamp(block_env.timestamp) {
+            return true
+        }
+        interop_dependencies_satisfied(&*self.inner.interop_oracle, tx.tx())
     }
 
     fn assemble_block(
@@ -118,53 +328,1237 @@ where
         parent: &SealedHeader<ProviderHeader<Self::Provider>>,
         state_root: B256,
         transactions: Vec<Recovered<ProviderTx<Self::Provider>>>,
-    ) -> reth_provider::ProviderBlock<Self::Provider> {
+    ) -> Result<reth_provider::ProviderBlock<Self::Provider>, Self::Error> {
+        // the extra data is not stamped, it carries the EIP-1559 parameters since Holocene
+        assemble_pending_block(
+            &*self.provider().chain_spec(),
+            block_env,
+            result,
+            parent,
+            state_root,
+            transactions,
+        )
+        .map_err(Self::Error::from_eth_err)
+    }
+}
+
+impl<N> OpEthApi<N>
+where
+    Self: LoadPendingBlock<Error = OpEthApiError> + SpawnBlocking,
+    N: OpNodeCore<
+        Provider: BlockReader<Block = OpBlock, Receipt = OpReceipt>
+                      + ChainSpecProvider<ChainSpec = OpChainSpec>,
+    >,
+{
+    /// Returns the receipt of the transaction with the given hash if it's included in the locally
+    /// built pending block.
+    ///
+    /// A pending block built from the pool doesn't start with an L1 info deposit, so the L1 fee
+    /// fields are derived from the [`L1_BLOCK_CONTRACT`] in the pending state instead. This
+    /// includes the [`PendingBlockConfig::speculative_l1_base_fee`], if set.
+    pub async fn pending_transaction_receipt(
+        &self,
+        hash: B256,
+    ) -> Result<Option<OpTransactionReceipt>, OpEthApiError> {
+        let Some((block, receipts)) = self.local_pending_block().await? else { return Ok(None) };
+        let Some(index) = block.body().transactions.iter().position(|tx| *tx.tx_hash() == hash)
+        else {
+            return Ok(None)
+        };
+        let Some(state) = self.pending_state_provider().await? else { return Ok(None) };
+
         let chain_spec = self.provider().chain_spec();
-        let timestamp = block_env.timestamp;
+        let mut l1_block_info = revm_optimism::L1BlockInfo::try_fetch(
+            &mut StateProviderDatabase::new(state),
+            block.number(),
+            revm_spec_by_timestamp_after_bedrock(&*chain_spec, block.timestamp()),
+        )
+        .map_err(OpEthApiError::from_eth_err)?;
+
+        let meta = TransactionMeta {
+            tx_hash: hash,
+            index: index as u64,
+            block_hash: block.hash(),
+            block_number: block.number(),
+            base_fee: block.base_fee_per_gas(),
+            excess_blob_gas: block.excess_blob_gas(),
+            timestamp: block.timestamp(),
+        };
+
+        Ok(Some(
+            OpReceiptBuilder::new(
+                &chain_spec,
+                &block.body().transactions[index],
+                meta,
+                &receipts[index],
+                &receipts,
+                &mut l1_block_info,
+            )?
+            .build(),
+        ))
+    }
+}
+
+/// Overrides the L1 base fee stored in the [`L1_BLOCK_CONTRACT`], from which the EVM computes
+/// the L1 fees of all transactions executed afterwards.
+pub fn apply_speculative_l1_base_fee<DB: Database>(
+    db: &mut State<DB>,
+    l1_base_fee: u128,
+) -> Result<(), DB::Error> {
+    // the account needs to be loaded before its storage can be read from the state
+    let info = Database::basic(db, L1_BLOCK_CONTRACT)?.unwrap_or_default();
+    let original = Database::storage(db, L1_BLOCK_CONTRACT, L1_BASE_FEE_SLOT)?;
+
+    let mut account: revm::state::Account = info.into();
+    account
+        .storage
+        .insert(L1_BASE_FEE_SLOT, EvmStorageSlot::new_changed(original, U256::from(l1_base_fee)));
+    account.mark_touch();
+    db.commit(HashMap::from_iter([(L1_BLOCK_CONTRACT, account)]));
+
+    Ok(())
+}
+
+/// Assembles an OP pending block from the result of executing the given transactions on top of
+/// `parent`.
+///
+/// This only covers the last steps of a pending build: it computes the transaction and receipt
+/// roots and seals the header for the hardforks active at the timestamp of the block env. The env,
+/// the transaction selection, the execution and the state root are up to the caller, see
+/// [`LoadPendingBlock::build_block`] for the full build. It only depends on the chain spec, so it
+/// can seal pending blocks outside of [`OpEthApi`], e.g. from custom RPC methods or tools.
+pub fn assemble_pending_block<ChainSpec>(
+    chain_spec: &ChainSpec,
+    block_env: &BlockEnv,
+    result: &BlockExecutionResult<OpReceipt>,
+    parent: &SealedHeader,
+    state_root: B256,
+    transactions: Vec<Recovered<OpTransactionSigned>>,
+) -> Result<OpBlock, EthApiError>
+where
+    ChainSpec: EthChainSpec + OpHardforks,
+{
+    let timestamp = block_env.timestamp;
+
+    let transactions_root = calculate_transaction_root(&transactions);
+    let receipts_root =
+        calculate_receipt_root_no_memo_optimism(&result.receipts, chain_spec, timestamp);
+
+    let logs_bloom = logs_bloom(result.receipts.iter().flat_map(|r| r.logs()));
+
+    ensure_fork_ordering(chain_spec, timestamp)?;
+    let is_cancun = chain_spec.is_cancun_active_at_timestamp(timestamp);
+    let is_prague = chain_spec.is_prague_active_at_timestamp(timestamp);
+    let is_shanghai = chain_spec.is_shanghai_active_at_timestamp(timestamp);
 
-        let transactions_root = calculate_transaction_root(&transactions);
-        let receipts_root =
-            calculate_receipt_root_no_memo_optimism(&result.receipts, &chain_spec, timestamp);
+    let header = Header {
+        parent_hash: parent.hash(),
+        ommers_hash: EMPTY_OMMER_ROOT_HASH,
+        beneficiary: block_env.beneficiary,
+        state_root,
+        transactions_root,
+        receipts_root,
+        withdrawals_root: (is_shanghai).then_some(EMPTY_WITHDRAWALS),
+        logs_bloom,
+        timestamp,
+        mix_hash: block_env.prevrandao.unwrap_or_default(),
+        nonce: chain_spec.block_nonce().into(),
+        base_fee_per_gas: Some(block_env.basefee),
+        number: block_env.number,
+        gas_limit: block_env.gas_limit,
+        difficulty: U256::ZERO,
+        gas_used: result.gas_used,
+        blob_gas_used: is_cancun.then(|| {
+            transactions.iter().map(|tx| tx.blob_gas_used().unwrap_or_default()).sum::<u64>()
+        }),
+        excess_blob_gas: block_env.blob_excess_gas(),
+        extra_data: Default::default(),
+        parent_beacon_block_root: is_cancun.then_some(B256::ZERO),
+        requests_hash: is_prague.then_some(EMPTY_REQUESTS_HASH),
+    };
 
-        let logs_bloom = logs_bloom(result.receipts.iter().flat_map(|r| r.logs()));
-        let is_cancun = chain_spec.is_cancun_active_at_timestamp(timestamp);
-        let is_prague = chain_spec.is_prague_active_at_timestamp(timestamp);
-        let is_shanghai = chain_spec.is_shanghai_active_at_timestamp(timestamp);
+    // seal the block
+    Ok(reth_primitives::Block {
+        header,
+        body: BlockBody {
+            transactions: transactions.into_iter().map(|tx| tx.into_tx()).collect(),
+            ommers: vec![],
+            withdrawals: is_shanghai.then(Default::default),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::{InteropOracle, OpEthApiBuilder, CROSS_L2_INBOX};
+    use alloy_consensus::{
+        constants::EMPTY_ROOT_HASH, Receipt, SignableTransaction, TxEip1559, TxEip7702,
+    };
+    use alloy_eips::{
+        eip2930::{AccessList, AccessListItem},
+        eip7702::Authorization,
+        BlockId,
+    };
+    use alloy_primitives::{hex, Bytes, PrimitiveSignature as Signature, StorageKey, TxKind};
+    use op_alloy_consensus::{OpDepositReceipt, OpTypedTransaction, TxDeposit};
+    use reth_chainspec::{ForkCondition, MIN_TRANSACTION_GAS};
+    use reth_evm::{
+        execute::{
+            BasicBlockExecutorProvider, BlockExecutionError, BlockExecutionStrategy,
+            BlockExecutorProvider, BlockValidationError, Executor,
+        },
+        Evm,
+    };
+    use reth_network_api::noop::NoopNetwork;
+    use reth_optimism_chainspec::{OpChainSpec, OpChainSpecBuilder, OP_MAINNET};
+    use reth_optimism_evm::OpEvmConfig;
+    use reth_optimism_forks::OpHardfork;
+    use reth_optimism_primitives::OpPrimitives;
+    use reth_optimism_txpool::OpPooledTransaction;
+    use reth_primitives_traits::{
+        crypto::secp256k1::{recover_signer, sign_message},
+        Account, Block as _,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_revm::{database::StateProviderDatabase, test_utils::StateProviderTest};
+    use reth_rpc_eth_api::helpers::{EthCall, EthState, EthTransactions};
+    use reth_rpc_eth_types::{priority_fee_revenue, PendingBuildTimings};
+    use reth_transaction_pool::{
+        blobstore::InMemoryBlobStore, noop::MockTransactionValidator, CoinbaseTipOrdering, Pool,
+        TransactionOrigin,
+    };
+    use revm::{database_interface::EmptyDBTyped, inspector::NoOpInspector};
+    use revm_optimism::L1BlockInfo;
+
+    /// Timestamp of OP mainnet block 124665056, after Ecotone.
+    const ECOTONE_TIMESTAMP: u64 = 1724928889;
+
+    type TestProvider = MockEthProvider<OpPrimitives, OpChainSpec>;
 
+    type TestPool = Pool<
+        MockTransactionValidator<OpPooledTransaction>,
+        CoinbaseTipOrdering<OpPooledTransaction>,
+        InMemoryBlobStore,
+    >;
+
+    /// The `eth_` backend the [`OpEthApi`] under test is built on.
+    type TestNode = reth_rpc::EthApi<TestProvider, TestPool, NoopNetwork, OpEvmConfig>;
+
+    /// Returns a provider with an empty latest block at timestamp 10, on top of which the pending
+    /// block is built.
+    fn test_provider(chain_spec: Arc<OpChainSpec>) -> TestProvider {
+        let provider = MockEthProvider::<OpPrimitives>::new().with_chain_spec(chain_spec);
         let header = Header {
-            parent_hash: parent.hash(),
-            ommers_hash: EMPTY_OMMER_ROOT_HASH,
-            beneficiary: block_env.beneficiary,
-            state_root,
-            transactions_root,
-            receipts_root,
-            withdrawals_root: (is_shanghai).then_some(EMPTY_WITHDRAWALS),
-            logs_bloom,
-            timestamp,
-            mix_hash: block_env.prevrandao.unwrap_or_default(),
-            nonce: BEACON_NONCE.into(),
-            base_fee_per_gas: Some(block_env.basefee),
-            number: block_env.number,
-            gas_limit: block_env.gas_limit,
-            difficulty: U256::ZERO,
-            gas_used: result.gas_used,
-            blob_gas_used: is_cancun.then(|| {
-                transactions.iter().map(|tx| tx.blob_gas_used().unwrap_or_default()).sum::<u64>()
+            number: 1,
+            timestamp: 10,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1_000),
+            // holocene encoded eip-1559 params, zero means the chain spec defaults are used
+            extra_data: vec![0u8; 9].into(),
+            parent_beacon_block_root: Some(B256::ZERO),
+            ..Default::default()
+        };
+        let hash = header.hash_slow();
+        provider.add_block(hash, OpBlock { header, body: Default::default() });
+        provider.add_receipts(hash, vec![]);
+        provider
+    }
+
+    fn test_pool() -> TestPool {
+        Pool::new(
+            MockTransactionValidator::default(),
+            CoinbaseTipOrdering::default(),
+            InMemoryBlobStore::default(),
+            Default::default(),
+        )
+    }
+
+    /// Funds the sender and adds the transaction to the pool.
+    async fn add_pool_transaction(
+        provider: &TestProvider,
+        pool: &TestPool,
+        tx: OpTypedTransaction,
+        sender: Address,
+    ) -> B256 {
+        provider.add_account(sender, ExtendedAccount::new(0, U256::from(10u128.pow(18))));
+        let tx = OpTransactionSigned::new_unhashed(tx, Signature::test_signature());
+        let encoded_length = tx.encode_2718_len();
+        pool.add_transaction(
+            TransactionOrigin::External,
+            OpPooledTransaction::new(Recovered::new_unchecked(tx, sender), encoded_length),
+        )
+        .await
+        .unwrap()
+    }
+
+    fn build_op_eth_api(
+        provider: TestProvider,
+        pool: TestPool,
+        builder: OpEthApiBuilder,
+    ) -> OpEthApi<TestNode> {
+        let evm_config = OpEvmConfig::optimism(provider.chain_spec());
+        builder.build_with::<TestNode>(reth_rpc::EthApiBuilder::new(
+            provider,
+            pool,
+            NoopNetwork::default(),
+            evm_config,
+        ))
+    }
+
+    /// Accepts only the contained message.
+    #[derive(Debug)]
+    struct KnownMessage(B256);
+
+    impl InteropOracle for KnownMessage {
+        fn is_valid(&self, message: &B256) -> bool {
+            *message == self.0
+        }
+    }
+
+    #[test]
+    fn assemble_empty_pending_block() {
+        let parent = SealedHeader::seal_slow(Header { number: 9, ..Default::default() });
+        let block_env = BlockEnv {
+            number: 10,
+            timestamp: ECOTONE_TIMESTAMP,
+            gas_limit: 30_000_000,
+            basefee: 7,
+            ..Default::default()
+        };
+
+        let block = assemble_pending_block(
+            &**OP_MAINNET,
+            &block_env,
+            &BlockExecutionResult::default(),
+            &parent,
+            B256::ZERO,
+            vec![],
+        )
+        .unwrap();
+
+        assert_eq!(block.header.parent_hash, parent.hash());
+        assert_eq!(block.header.number, 10);
+        assert_eq!(block.header.base_fee_per_gas, Some(7));
+        assert_eq!(block.header.transactions_root, EMPTY_ROOT_HASH);
+        assert_eq!(block.header.withdrawals_root, Some(EMPTY_WITHDRAWALS));
+        assert_eq!(block.header.blob_gas_used, Some(0));
+        assert_eq!(block.header.requests_hash, None);
+        assert!(block.body.transactions.is_empty());
+        assert_eq!(block.body.withdrawals, Some(Default::default()));
+    }
+
+    #[test]
+    fn speculative_l1_base_fee_changes_l1_fee() {
+        let l1_fee = |l1_base_fee| {
+            let mut db =
+                State::builder().with_database(EmptyDBTyped::<ProviderError>::default()).build();
+            apply_speculative_l1_base_fee(&mut db, l1_base_fee).unwrap();
+
+            // the L1 fee of a transaction is computed from the L1 block contract's storage
+            let mut l1_block_info = L1BlockInfo::default();
+            l1_block_info.l1_base_fee =
+                Database::storage(&mut db, L1_BLOCK_CONTRACT, L1_BASE_FEE_SLOT).unwrap();
+            l1_block_info.l1_base_fee_scalar = U256::from(1_000_000);
+            l1_block_info
+                .l1_tx_data_fee(&**OP_MAINNET, ECOTONE_TIMESTAMP, 0, &[1; 200], false)
+                .unwrap()
+        };
+
+        assert_eq!(l1_fee(0), U256::ZERO);
+        assert!(l1_fee(2_000_000_000) > l1_fee(1_000_000_000));
+    }
+
+    #[test]
+    fn l1_fee_counts_towards_sender_balance() {
+        let chain_spec = Arc::new(OpChainSpecBuilder::base_mainnet().canyon_activated().build());
+        let sender = Address::with_last_byte(1);
+        let tx = OpTransactionSigned::new_unhashed(
+            OpTypedTransaction::Eip1559(TxEip1559 {
+                chain_id: chain_spec.chain.id(),
+                gas_limit: MIN_TRANSACTION_GAS,
+                max_fee_per_gas: 1_000_000_000,
+                to: Address::ZERO.into(),
+                ..Default::default()
+            }),
+            Signature::test_signature(),
+        );
+        let l2_cost = U256::from(MIN_TRANSACTION_GAS as u128 * tx.max_fee_per_gas());
+
+        let execute = |balance| {
+            let mut db = StateProviderTest::default();
+            db.insert_account(
+                L1_BLOCK_CONTRACT,
+                Account { nonce: 1, ..Default::default() },
+                None,
+                HashMap::from_iter([
+                    // l1 base fee
+                    (StorageKey::with_last_byte(1), U256::from(1_000_000_000)),
+                    // l1 fee overhead
+                    (StorageKey::with_last_byte(5), U256::from(188)),
+                    // l1 fee scalar
+                    (StorageKey::with_last_byte(6), U256::from(684_000)),
+                ]),
+            );
+            db.insert_account(
+                sender,
+                Account { balance, ..Default::default() },
+                None,
+                HashMap::default(),
+            );
+
+            let provider =
+                BasicBlockExecutorProvider::new(OpEvmConfig::optimism(chain_spec.clone()));
+            let mut executor = provider.executor(StateProviderDatabase::new(&db));
+            executor.with_state_mut(|state| {
+                state.load_cache_account(L1_BLOCK_CONTRACT).unwrap();
+            });
+            executor.execute(&RecoveredBlock::new_unhashed(
+                OpBlock {
+                    header: Header {
+                        timestamp: 2,
+                        number: 1,
+                        gas_limit: 1_000_000,
+                        ..Default::default()
+                    },
+                    body: BlockBody { transactions: vec![tx.clone()], ..Default::default() },
+                },
+                vec![sender],
+            ))
+        };
+
+        // the sender can pay the L2 gas but not the L1 data fee on top, the transaction is invalid
+        // and the pending selection skips it
+        let err = execute(l2_cost).unwrap_err();
+        assert!(matches!(
+            err,
+            BlockExecutionError::Validation(BlockValidationError::InvalidTx { ref error, .. })
+                if !error.is_nonce_too_low()
+        ));
+
+        // with enough funds for both the transaction is executed
+        assert!(execute(l2_cost * U256::from(2)).is_ok());
+    }
+
+    #[test]
+    fn pool_transactions_pay_base_fee_after_deposits() {
+        let chain_spec = Arc::new(OpChainSpecBuilder::base_mainnet().canyon_activated().build());
+        let sender = Address::with_last_byte(1);
+        let base_fee = 7;
+        let deposit = OpTransactionSigned::new_unhashed(
+            OpTypedTransaction::Deposit(TxDeposit {
+                source_hash: B256::random(),
+                from: Address::with_last_byte(2),
+                to: TxKind::Call(Address::ZERO),
+                gas_limit: 100_000,
+                ..Default::default()
+            }),
+            TxDeposit::signature(),
+        );
+        let tx = OpTransactionSigned::new_unhashed(
+            OpTypedTransaction::Eip1559(TxEip1559 {
+                chain_id: chain_spec.chain.id(),
+                gas_limit: MIN_TRANSACTION_GAS,
+                max_fee_per_gas: 1_000_000_000,
+                to: Address::ZERO.into(),
+                ..Default::default()
+            }),
+            Signature::test_signature(),
+        );
+
+        let mut db = StateProviderTest::default();
+        db.insert_account(
+            L1_BLOCK_CONTRACT,
+            Account { nonce: 1, ..Default::default() },
+            None,
+            HashMap::default(),
+        );
+        db.insert_account(
+            sender,
+            Account { balance: U256::from(10u128.pow(18)), ..Default::default() },
+            None,
+            HashMap::default(),
+        );
+
+        let provider = BasicBlockExecutorProvider::new(OpEvmConfig::optimism(chain_spec));
+        let mut executor = provider.executor(StateProviderDatabase::new(&db));
+        executor.with_state_mut(|state| {
+            state.load_cache_account(L1_BLOCK_CONTRACT).unwrap();
+        });
+        let output = executor
+            .execute(&RecoveredBlock::new_unhashed(
+                OpBlock {
+                    header: Header {
+                        timestamp: 2,
+                        number: 1,
+                        gas_limit: 1_000_000,
+                        base_fee_per_gas: Some(base_fee),
+                        ..Default::default()
+                    },
+                    body: BlockBody { transactions: vec![deposit, tx], ..Default::default() },
+                },
+                vec![Address::with_last_byte(2), sender],
+            ))
+            .unwrap();
+
+        // the gas of the deposit counts towards the block, but only the pool transaction pays the
+        // base fee of the block, which the deposit gas doesn't change
+        let receipts = &output.result.receipts;
+        assert_eq!(receipts[0].cumulative_gas_used(), MIN_TRANSACTION_GAS);
+        assert_eq!(receipts[1].cumulative_gas_used(), 2 * MIN_TRANSACTION_GAS);
+        let vault = output.state.account(&BASE_FEE_VAULT).and_then(|acc| acc.info.clone());
+        assert_eq!(vault.unwrap().balance, U256::from(MIN_TRANSACTION_GAS * base_fee));
+    }
+
+    /// Executes a set code transaction in the block after a parent at timestamp 10 and returns
+    /// the code of its authority afterwards.
+    fn authority_code_in_next_block(
+        chain_spec: Arc<OpChainSpec>,
+        timestamp: u64,
+    ) -> Result<Bytes, BlockExecutionError> {
+        let sender = Address::with_last_byte(1);
+        let delegate = Address::with_last_byte(2);
+
+        let authorization = Authorization {
+            chain_id: U256::from(chain_spec.chain.id()),
+            address: delegate,
+            nonce: 0,
+        };
+        let signature =
+            sign_message(B256::with_last_byte(1), authorization.signature_hash()).unwrap();
+        let authority = recover_signer(&signature, authorization.signature_hash()).unwrap();
+        let tx = OpTransactionSigned::new_unhashed(
+            OpTypedTransaction::Eip7702(TxEip7702 {
+                chain_id: chain_spec.chain.id(),
+                gas_limit: 100_000,
+                to: Address::random(),
+                authorization_list: vec![authorization.into_signed(signature)],
+                ..Default::default()
+            }),
+            Signature::test_signature(),
+        );
+
+        let mut db = StateProviderTest::default();
+        db.insert_account(
+            L1_BLOCK_CONTRACT,
+            Account { nonce: 1, ..Default::default() },
+            None,
+            HashMap::from_iter([
+                // l1 base fee
+                (StorageKey::with_last_byte(1), U256::from(1_000_000_000)),
+            ]),
+        );
+        db.insert_account(
+            sender,
+            Account { balance: U256::MAX, ..Default::default() },
+            None,
+            HashMap::default(),
+        );
+
+        let parent = SealedHeader::seal_slow(Header {
+            number: 1,
+            timestamp: 10,
+            gas_limit: 30_000_000,
+            // holocene encoded eip-1559 params, zero means the chain spec defaults are used
+            extra_data: vec![0u8; 9].into(),
+            ..Default::default()
+        });
+        let evm_config = OpEvmConfig::optimism(chain_spec);
+        let mut state = State::builder()
+            .with_database(StateProviderDatabase::new(&db))
+            .with_bundle_update()
+            .build();
+        state.load_cache_account(L1_BLOCK_CONTRACT).unwrap();
+
+        let mut strategy = evm_config
+            .strategy_for_next_block(
+                &mut state,
+                &parent,
+                OpNextBlockEnvAttributes {
+                    timestamp,
+                    suggested_fee_recipient: Address::ZERO,
+                    prev_randao: B256::ZERO,
+                    gas_limit: 30_000_000,
+                    parent_beacon_block_root: Some(B256::ZERO),
+                    extra_data: Default::default(),
+                },
+            )
+            .unwrap();
+        strategy.apply_pre_execution_changes()?;
+        strategy.execute_transaction(Recovered::new_unchecked(&tx, sender))?;
+
+        let db = strategy.evm_mut().db_mut();
+        let code_hash = db.basic(authority).unwrap().unwrap_or_default().code_hash;
+        Ok(db.code_by_hash(code_hash).unwrap().original_bytes())
+    }
+
+    #[test]
+    fn set_code_transactions_delegate_after_isthmus() {
+        let chain_spec = Arc::new(
+            OpChainSpecBuilder::base_mainnet()
+                .holocene_activated()
+                .with_fork(OpHardfork::Isthmus, ForkCondition::Timestamp(20))
+                .build(),
+        );
+
+        // before Isthmus the transaction is invalid and the pending selection skips it
+        let err = authority_code_in_next_block(chain_spec.clone(), 12).unwrap_err();
+        assert!(matches!(
+            err,
+            BlockExecutionError::Validation(BlockValidationError::InvalidTx { ref error, .. })
+                if !error.is_nonce_too_low()
+        ));
+
+        // once Isthmus is active the authority's code delegates to the authorized address
+        let code = authority_code_in_next_block(chain_spec, 20).unwrap();
+        assert_eq!(
+            code.to_vec(),
+            [&[0xef, 0x01, 0x00][..], Address::with_last_byte(2).as_slice()].concat()
+        );
+    }
+
+    #[test]
+    fn deposits_pay_no_priority_fee() {
+        let deposit = OpTransactionSigned::new_unhashed(
+            OpTypedTransaction::Deposit(TxDeposit {
+                gas_limit: 1_000_000,
+                mint: Some(u128::MAX),
+                ..Default::default()
+            }),
+            TxDeposit::signature(),
+        );
+        let tx = OpTransactionSigned::new_unhashed(
+            OpTypedTransaction::Eip1559(TxEip1559 {
+                gas_limit: MIN_TRANSACTION_GAS,
+                max_fee_per_gas: 10,
+                max_priority_fee_per_gas: 5,
+                to: Address::ZERO.into(),
+                ..Default::default()
+            }),
+            Signature::test_signature(),
+        );
+        let receipt = |cumulative_gas_used| Receipt {
+            status: true.into(),
+            cumulative_gas_used,
+            logs: vec![],
+        };
+        let receipts = [
+            OpReceipt::Deposit(OpDepositReceipt {
+                inner: receipt(50_000),
+                deposit_nonce: Some(0),
+                deposit_receipt_version: None,
+            }),
+            OpReceipt::Eip1559(receipt(50_000 + MIN_TRANSACTION_GAS)),
+        ];
+        let transactions = [deposit, tx];
+
+        // only the normal transaction pays its priority fee for the gas it used
+        let revenue =
+            priority_fee_revenue(3, transactions.iter().zip(&receipts), |tx| tx.is_deposit());
+        assert_eq!(revenue, U256::from(MIN_TRANSACTION_GAS * 5));
+
+        let revenue = priority_fee_revenue(3, transactions.iter().take(1).zip(&receipts), |tx| {
+            tx.is_deposit()
+        });
+        assert_eq!(revenue, U256::ZERO);
+    }
+
+    #[test]
+    fn recover_deposit_sender() {
+        let from = Address::random();
+        let deposit = OpTransactionSigned::new_unhashed(
+            OpTypedTransaction::Deposit(TxDeposit {
+                source_hash: B256::random(),
+                from,
+                to: TxKind::Call(Address::random()),
+                gas_limit: 100_000,
+                ..Default::default()
             }),
-            excess_blob_gas: block_env.blob_excess_gas(),
-            extra_data: Default::default(),
-            parent_beacon_block_root: is_cancun.then_some(B256::ZERO),
-            requests_hash: is_prague.then_some(EMPTY_REQUESTS_HASH),
-        };
-
-        // seal the block
-        reth_primitives::Block {
-            header,
-            body: BlockBody {
-                transactions: transactions.into_iter().map(|tx| tx.into_tx()).collect(),
-                ommers: vec![],
-                withdrawals: None,
+            TxDeposit::signature(),
+        );
+
+        // deposits carry no signature, their sender is the encoded source
+        assert_eq!(deposit.recover_signer().unwrap(), from);
+        assert_eq!(deposit.recover_signer_unchecked().unwrap(), from);
+
+        let block = OpBlock {
+            header: Header::default(),
+            body: BlockBody { transactions: vec![deposit], ..Default::default() },
+        };
+        let block = block.try_into_recovered().unwrap();
+        assert_eq!(block.senders(), &[from]);
+    }
+
+    #[tokio::test]
+    async fn pending_block_skips_unsatisfied_interop_dependencies() {
+        let chain_spec = Arc::new(OpChainSpecBuilder::base_mainnet().interop_activated().build());
+        let provider = test_provider(chain_spec.clone());
+        let pool = test_pool();
+
+        let known = B256::with_last_byte(1);
+        let tx = |nonce, messages| {
+            OpTypedTransaction::Eip1559(TxEip1559 {
+                chain_id: chain_spec.chain.id(),
+                nonce,
+                gas_limit: 50_000,
+                max_fee_per_gas: 1_000_000_000,
+                to: Address::ZERO.into(),
+                access_list: AccessList(vec![AccessListItem {
+                    address: CROSS_L2_INBOX,
+                    storage_keys: messages,
+                }]),
+                ..Default::default()
+            })
+        };
+        let (sender, other) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        add_pool_transaction(&provider, &pool, tx(0, vec![B256::with_last_byte(2)]), sender).await;
+        // the next transaction of the sender depends on the one with the unknown message
+        add_pool_transaction(&provider, &pool, tx(1, vec![]), sender).await;
+        let included = add_pool_transaction(&provider, &pool, tx(0, vec![known]), other).await;
+
+        let eth_api = build_op_eth_api(
+            provider,
+            pool,
+            OpEthApiBuilder::new()
+                .with_interop_oracle(KnownMessage(known))
+                .with_compute_pending_block(true),
+        );
+
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        let hashes = block.body().transactions.iter().map(|tx| *tx.tx_hash()).collect::<Vec<_>>();
+        assert_eq!(hashes, [included]);
+        assert_eq!(block.senders(), &[other]);
+    }
+
+    #[tokio::test]
+    async fn empty_pending_block_with_produce_empty_blocks() {
+        let chain_spec = Arc::new(OpChainSpecBuilder::base_mainnet().holocene_activated().build());
+        let provider = test_provider(chain_spec);
+        let latest = provider.latest_header().unwrap().unwrap();
+        let local_pending_block = |produce_empty_blocks| {
+            let eth_api = build_op_eth_api(
+                provider.clone(),
+                test_pool(),
+                OpEthApiBuilder::new().with_pending_block_config(
+                    PendingBlockConfig::default().with_produce_empty_blocks(produce_empty_blocks),
+                ),
+            );
+            async move { eth_api.local_pending_block().await.unwrap().unwrap().0 }
+        };
+
+        // without anything to include the latest block is pending
+        let block = local_pending_block(false).await;
+        assert_eq!(block.hash(), latest.hash());
+
+        // chains with a fixed block cadence build an empty block on top of it instead
+        let block = local_pending_block(true).await;
+        assert_eq!(block.parent_hash(), latest.hash());
+        assert_eq!(block.number(), latest.number() + 1);
+        assert_eq!(block.timestamp(), latest.timestamp() + OP_BLOCK_TIME);
+        assert!(block.body().transactions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn pending_block_timestamp_follows_block_time() {
+        let chain_spec = Arc::new(
+            OpChainSpecBuilder::base_mainnet()
+                .holocene_activated()
+                .with_fork(OpHardfork::Isthmus, ForkCondition::Timestamp(12))
+                .build(),
+        );
+        let tx = OpTypedTransaction::Eip1559(TxEip1559 {
+            chain_id: chain_spec.chain.id(),
+            gas_limit: 100_000,
+            max_fee_per_gas: 1_000_000_000,
+            to: Address::random().into(),
+            input: vec![1u8; 1000].into(),
+            ..Default::default()
+        });
+        let pending_block = |block_time| {
+            let provider = test_provider(chain_spec.clone());
+            let pool = test_pool();
+            let tx = tx.clone();
+            async move {
+                add_pool_transaction(&provider, &pool, tx, Address::with_last_byte(1)).await;
+                let eth_api = build_op_eth_api(
+                    provider,
+                    pool,
+                    OpEthApiBuilder::new()
+                        .with_compute_pending_block(true)
+                        .with_pending_block_config(
+                            PendingBlockConfig::default().with_block_time(block_time),
+                        ),
+                );
+                let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+                (block.timestamp(), block.gas_used())
+            }
+        };
+
+        // on a chain with 1s blocks Isthmus isn't active yet in the block after the latest one:
+        // 21000 intrinsic gas + 1000 non-zero calldata bytes at 16 gas each
+        assert_eq!(pending_block(Some(1)).await, (11, 37_000));
+
+        // with the default 2s blocks the EIP-7623 calldata floor applies: 21000 + 4000 tokens at
+        // the floor cost of 10 gas each
+        assert_eq!(pending_block(None).await, (12, 61_000));
+    }
+
+    #[tokio::test]
+    async fn pending_block_fee_routing() {
+        let chain_spec = Arc::new(OpChainSpecBuilder::base_mainnet().holocene_activated().build());
+        let sender = Address::with_last_byte(1);
+        let balance = U256::from(10u128.pow(18));
+        let tx = OpTypedTransaction::Eip1559(TxEip1559 {
+            chain_id: chain_spec.chain.id(),
+            gas_limit: MIN_TRANSACTION_GAS,
+            max_fee_per_gas: 1_000_000_000,
+            to: Address::with_last_byte(2).into(),
+            ..Default::default()
+        });
+        let pending_balances = |fee_routing| {
+            let provider = test_provider(chain_spec.clone());
+            provider.add_account(
+                L1_BLOCK_CONTRACT,
+                ExtendedAccount::new(1, U256::ZERO).extend_storage([
+                    // l1 base fee
+                    (StorageKey::with_last_byte(1), U256::from(1_000_000_000)),
+                    // l1 base fee scalar, packed into the ecotone fee scalars
+                    (StorageKey::with_last_byte(3), U256::from(1_000_000) << 96),
+                ]),
+            );
+            let pool = test_pool();
+            let tx = tx.clone();
+            async move {
+                add_pool_transaction(&provider, &pool, tx, sender).await;
+                let eth_api = build_op_eth_api(
+                    provider,
+                    pool,
+                    OpEthApiBuilder::new()
+                        .with_compute_pending_block(true)
+                        .with_fee_routing(fee_routing),
+                );
+                let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+                let balances = eth_api
+                    .pending_balances(vec![sender, BASE_FEE_VAULT, L1_FEE_VAULT])
+                    .await
+                    .unwrap();
+                (block, balances)
+            }
+        };
+
+        let (block, vaults) = pending_balances(OpFeeRouting::Vaults).await;
+        assert_eq!(block.body().transactions.len(), 1);
+        let base_fee = U256::from(MIN_TRANSACTION_GAS * block.base_fee_per_gas().unwrap());
+        let l1_fee = vaults[2];
+        assert!(!l1_fee.is_zero());
+        // without a priority fee the sender pays exactly the base fee and the L1 data fee
+        assert_eq!(vaults[0], balance - base_fee - l1_fee);
+        assert_eq!(vaults[1], base_fee);
+
+        // the burnt base fee doesn't reach the vault, the L1 data fee is still collected
+        let (_, burn) = pending_balances(OpFeeRouting::Burn).await;
+        assert_eq!(burn, [vaults[0], U256::ZERO, l1_fee]);
+    }
+
+    #[tokio::test]
+    async fn post_processed_receipts_change_receipt_root() {
+        let chain_spec = Arc::new(OpChainSpecBuilder::base_mainnet().holocene_activated().build());
+        let tx = OpTypedTransaction::Eip1559(TxEip1559 {
+            chain_id: chain_spec.chain.id(),
+            gas_limit: MIN_TRANSACTION_GAS,
+            max_fee_per_gas: 1_000_000_000,
+            to: Address::ZERO.into(),
+            ..Default::default()
+        });
+        let local_pending_block = |receipt_post_processor| {
+            let provider = test_provider(chain_spec.clone());
+            let pool = test_pool();
+            let tx = tx.clone();
+            async move {
+                add_pool_transaction(&provider, &pool, tx, Address::with_last_byte(1)).await;
+                let eth_api = build_op_eth_api(
+                    provider,
+                    pool,
+                    OpEthApiBuilder::new()
+                        .with_compute_pending_block(true)
+                        .with_receipt_post_processor(receipt_post_processor),
+                );
+                eth_api.local_pending_block().await.unwrap().unwrap()
+            }
+        };
+
+        let (block, _) = local_pending_block(None).await;
+
+        let processor: OpReceiptPostProcessor = Arc::new(|receipt: &mut OpReceipt| {
+            if let OpReceipt::Eip1559(receipt) = receipt {
+                receipt.logs.push(Default::default());
+            }
+        });
+        let (processed, receipts) = local_pending_block(Some(processor)).await;
+
+        // the pending receipts are the processed ones and the header commits to them
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].logs().len(), 1);
+        assert_ne!(processed.header().receipts_root, block.header().receipts_root);
+        assert_eq!(
+            processed.header().receipts_root,
+            calculate_receipt_root_no_memo_optimism(&receipts, &*chain_spec, processed.timestamp())
+        );
+    }
+
+    #[tokio::test]
+    async fn pending_receipt_uses_speculative_l1_base_fee() {
+        let chain_spec = Arc::new(OpChainSpecBuilder::base_mainnet().holocene_activated().build());
+        let provider = test_provider(chain_spec.clone());
+        provider.add_account(
+            L1_BLOCK_CONTRACT,
+            ExtendedAccount::new(1, U256::ZERO).extend_storage([
+                (StorageKey::with_last_byte(1), U256::from(1_000_000_000)),
+                (StorageKey::with_last_byte(3), U256::from(1_000_000) << 96),
+            ]),
+        );
+        let pool = test_pool();
+        let tx = OpTypedTransaction::Eip1559(TxEip1559 {
+            chain_id: chain_spec.chain.id(),
+            gas_limit: MIN_TRANSACTION_GAS,
+            max_fee_per_gas: 1_000_000_000,
+            to: Address::with_last_byte(2).into(),
+            ..Default::default()
+        });
+        let hash = add_pool_transaction(&provider, &pool, tx, Address::with_last_byte(1)).await;
+        let eth_api = build_op_eth_api(
+            provider,
+            pool,
+            OpEthApiBuilder::new().with_compute_pending_block(true).with_pending_block_config(
+                PendingBlockConfig::default().with_speculative_l1_base_fee(Some(5_000_000_000)),
+            ),
+        );
+
+        // the pending transaction has a receipt priced with the speculative L1 base fee
+        let receipt = EthTransactions::transaction_receipt(&eth_api, hash).await.unwrap().unwrap();
+        assert_eq!(receipt.inner.transaction_hash, hash);
+        assert_eq!(receipt.l1_block_info.l1_gas_price, Some(5_000_000_000));
+
+        // and reports the L1 fee the EVM charged during the pending build
+        let l1_fee = eth_api.pending_balances(vec![L1_FEE_VAULT]).await.unwrap()[0];
+        assert!(!l1_fee.is_zero());
+        assert_eq!(receipt.l1_block_info.l1_fee, Some(l1_fee.to()));
+
+        // unknown transactions have no receipt
+        assert!(EthTransactions::transaction_receipt(&eth_api, B256::ZERO)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn pending_authority_code_after_isthmus() {
+        let pending_code = |isthmus_timestamp| async move {
+            let chain_spec = Arc::new(
+                OpChainSpecBuilder::base_mainnet()
+                    .holocene_activated()
+                    .with_fork(OpHardfork::Isthmus, ForkCondition::Timestamp(isthmus_timestamp))
+                    .build(),
+            );
+            let authorization = Authorization {
+                chain_id: U256::from(chain_spec.chain.id()),
+                address: Address::with_last_byte(2),
+                nonce: 0,
+            };
+            let signature =
+                sign_message(B256::with_last_byte(1), authorization.signature_hash()).unwrap();
+            let authority = recover_signer(&signature, authorization.signature_hash()).unwrap();
+            let tx = OpTypedTransaction::Eip7702(TxEip7702 {
+                chain_id: chain_spec.chain.id(),
+                gas_limit: 100_000,
+                max_fee_per_gas: 1_000_000_000,
+                to: Address::random(),
+                authorization_list: vec![authorization.into_signed(signature)],
+                ..Default::default()
+            });
+
+            let provider = test_provider(chain_spec);
+            let pool = test_pool();
+            add_pool_transaction(&provider, &pool, tx, Address::with_last_byte(1)).await;
+            let eth_api = build_op_eth_api(
+                provider,
+                pool,
+                OpEthApiBuilder::new().with_compute_pending_block(true),
+            );
+            let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+            let code =
+                EthState::get_code(&eth_api, authority, Some(BlockId::pending())).await.unwrap();
+            (block.body().transactions.len(), code)
+        };
+
+        // before Isthmus the pending selection skips the set code transaction
+        let (transactions, code) = pending_code(20).await;
+        assert_eq!(transactions, 0);
+        assert!(code.is_empty());
+
+        // the pending block is at timestamp 12, where the authority delegates to the address
+        let (transactions, code) = pending_code(12).await;
+        assert_eq!(transactions, 1);
+        assert_eq!(
+            code.to_vec(),
+            [&[0xef, 0x01, 0x00][..], Address::with_last_byte(2).as_slice()].concat()
+        );
+    }
+
+    #[tokio::test]
+    async fn pending_block_keeps_deposit_gas_reserve() {
+        let chain_spec = Arc::new(OpChainSpecBuilder::base_mainnet().holocene_activated().build());
+        let depositor = Address::with_last_byte(9);
+        let deposit = Recovered::new_unchecked(
+            OpTransactionSigned::new_unhashed(
+                OpTypedTransaction::Deposit(TxDeposit {
+                    source_hash: B256::random(),
+                    from: depositor,
+                    to: TxKind::Call(Address::ZERO),
+                    gas_limit: 100_000,
+                    ..Default::default()
+                }),
+                TxDeposit::signature(),
+            ),
+            depositor,
+        );
+        let build_block = |builder: OpEthApiBuilder,
+                           bundle: Vec<Recovered<OpTransactionSigned>>| {
+            let provider = test_provider(chain_spec.clone());
+            let pool = test_pool();
+            let chain_id = chain_spec.chain.id();
+            async move {
+                for sender in 1..=3 {
+                    let tx = OpTypedTransaction::Eip1559(TxEip1559 {
+                        chain_id,
+                        gas_limit: MIN_TRANSACTION_GAS,
+                        max_fee_per_gas: 1_000_000_000,
+                        to: Address::ZERO.into(),
+                        ..Default::default()
+                    });
+                    add_pool_transaction(&provider, &pool, tx, Address::with_last_byte(sender))
+                        .await;
+                }
+                let parent = provider.latest_header().unwrap().unwrap();
+                let eth_api = build_op_eth_api(provider, pool, builder);
+                eth_api.build_block_with_bundle(&parent, &bundle).unwrap().0
+            }
+        };
+
+        // leaves room for two transfers only
+        let reserve = 30_000_000 - 50_000;
+        let block =
+            build_block(OpEthApiBuilder::new().with_deposit_gas_reserve(Some(reserve)), vec![])
+                .await;
+        assert_eq!(block.body().transactions.len(), 2);
+        assert!(block.header().gas_used <= block.header().gas_limit - reserve);
+
+        // the gas of the deposit is covered by the reserve, so the same two transfers fit
+        let block = build_block(
+            OpEthApiBuilder::new().with_deposit_gas_reserve(Some(reserve)),
+            vec![deposit.clone()],
+        )
+        .await;
+        assert_eq!(block.body().transactions.len(), 3);
+        assert_eq!(block.header().gas_used, 3 * MIN_TRANSACTION_GAS);
+
+        // the reserve of the builder takes precedence over the one of the pending block config
+        let block = build_block(
+            OpEthApiBuilder::new()
+                .with_deposit_gas_reserve(Some(reserve))
+                .with_pending_block_config(
+                    PendingBlockConfig::default().with_deposit_gas_reserve(Some(0)),
+                ),
+            vec![],
+        )
+        .await;
+        assert_eq!(block.body().transactions.len(), 2);
+
+        // without a reserve all transfers fit
+        let block = build_block(OpEthApiBuilder::new(), vec![deposit]).await;
+        assert_eq!(block.body().transactions.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn selection_base_fee_after_deposits() {
+        let chain_spec = Arc::new(OpChainSpecBuilder::base_mainnet().holocene_activated().build());
+        let depositor = Address::with_last_byte(9);
+        let deposit = Recovered::new_unchecked(
+            OpTransactionSigned::new_unhashed(
+                OpTypedTransaction::Deposit(TxDeposit {
+                    source_hash: B256::random(),
+                    from: depositor,
+                    to: TxKind::Call(Address::ZERO),
+                    gas_limit: 100_000,
+                    ..Default::default()
+                }),
+                TxDeposit::signature(),
+            ),
+            depositor,
+        );
+        let transfer = |max_fee_per_gas| {
+            OpTypedTransaction::Eip1559(TxEip1559 {
+                chain_id: chain_spec.chain.id(),
+                gas_limit: MIN_TRANSACTION_GAS,
+                max_fee_per_gas,
+                to: Address::ZERO.into(),
+                ..Default::default()
+            })
+        };
+        let (cheap, expensive) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let build_with_deposit = |selection_base_fee| {
+            let provider = test_provider(chain_spec.clone());
+            let pool = test_pool();
+            let deposit = deposit.clone();
+            let (cheap_tx, expensive_tx) = (transfer(10_000), transfer(1_000_000_000));
+            async move {
+                add_pool_transaction(&provider, &pool, cheap_tx, cheap).await;
+                add_pool_transaction(&provider, &pool, expensive_tx, expensive).await;
+                let parent = provider.latest_header().unwrap().unwrap();
+                let eth_api = build_op_eth_api(
+                    provider,
+                    pool,
+                    OpEthApiBuilder::new().with_selection_base_fee(selection_base_fee),
+                );
+                let (block, _, state, _) = eth_api
+                    .execute_pending_block(
+                        &parent,
+                        eth_api.next_env_attributes(&parent).unwrap(),
+                        None,
+                        None,
+                        &[deposit],
+                        None::<NoOpInspector>,
+                        eth_api.pending_block_config(),
+                        &mut PendingBuildTimings::default(),
+                    )
+                    .unwrap();
+                let balance = |address| {
+                    state
+                        .account(&address)
+                        .and_then(|account| account.info.as_ref())
+                        .unwrap()
+                        .balance
+                };
+                let senders = block.senders().to_vec();
+                (
+                    block.base_fee_per_gas().unwrap(),
+                    senders,
+                    balance(expensive),
+                    balance(BASE_FEE_VAULT),
+                )
+            }
+        };
+
+        // by default both transfers can pay the base fee of the block
+        let (_, senders, ..) = build_with_deposit(None).await;
+        assert_eq!(senders.len(), 3);
+        assert!(senders.contains(&cheap));
+
+        // the selection base fee rises with the gas of the deposit, which the cheap transfer
+        // can't pay
+        let selection_base_fee: OpSelectionBaseFee =
+            Arc::new(|block_env: &BlockEnv, deposit_gas_used| {
+                assert_eq!(deposit_gas_used, MIN_TRANSACTION_GAS);
+                block_env.basefee + deposit_gas_used
+            });
+        let (base_fee, senders, sender_balance, vault_balance) =
+            build_with_deposit(Some(selection_base_fee)).await;
+        assert!(base_fee + MIN_TRANSACTION_GAS > 10_000);
+        assert_eq!(senders, [depositor, expensive]);
+
+        // the selected transfer is still charged the base fee of the block and the deposit none
+        let charged = U256::from(MIN_TRANSACTION_GAS * base_fee);
+        assert_eq!(sender_balance, U256::from(10u128.pow(18)) - charged);
+        assert_eq!(vault_balance, charged);
+    }
+
+    #[tokio::test]
+    async fn call_many_pending_replays_uncached_pending_block() {
+        let chain_spec = Arc::new(OpChainSpecBuilder::base_mainnet().holocene_activated().build());
+        let provider = MockEthProvider::<OpPrimitives>::new().with_chain_spec(chain_spec.clone());
+
+        // increments the counter in slot 0 and returns its new value:
+        // PUSH1 0 SLOAD PUSH1 1 ADD DUP1 PUSH1 0 SSTORE PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+        let counter = Address::with_last_byte(3);
+        provider.add_account(
+            counter,
+            ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode(hex!("6000546001018060005560005260206000f3").into()),
+        );
+        // returns the block number: NUMBER PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+        let block_info = Address::with_last_byte(4);
+        provider.add_account(
+            block_info,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(hex!("4360005260206000f3").into()),
+        );
+        let call = |nonce, to| TxEip1559 {
+            chain_id: chain_spec.chain.id(),
+            nonce,
+            gas_limit: 100_000,
+            max_fee_per_gas: 1_000_000_000,
+            to: TxKind::Call(to),
+            ..Default::default()
+        };
+
+        // the latest block increments the counter once, its senders are recovered by the cache
+        let increment = call(0, counter);
+        let signature = sign_message(B256::with_last_byte(1), increment.signature_hash()).unwrap();
+        let sender = recover_signer(&signature, increment.signature_hash()).unwrap();
+        provider.add_account(sender, ExtendedAccount::new(0, U256::from(10u128.pow(18))));
+
+        let parent = Header {
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1_000),
+            extra_data: vec![0u8; 9].into(),
+            parent_beacon_block_root: Some(B256::ZERO),
+            ..Default::default()
+        };
+        let parent_hash = parent.hash_slow();
+        let header = Header { parent_hash, number: 1, timestamp: 10, ..parent.clone() };
+        let hash = header.hash_slow();
+        provider.add_block(parent_hash, OpBlock { header: parent, body: Default::default() });
+        provider.add_block(
+            hash,
+            OpBlock {
+                header,
+                body: BlockBody {
+                    transactions: vec![OpTransactionSigned::new_unhashed(
+                        OpTypedTransaction::Eip1559(increment),
+                        signature,
+                    )],
+                    ..Default::default()
+                },
             },
-        }
+        );
+        provider.add_receipts(hash, vec![]);
+
+        // without computing the pending block from the pool, the latest block is the pending
+        // block, which isn't in the pending block cache
+        let eth_api = build_op_eth_api(provider, test_pool(), OpEthApiBuilder::new());
+        assert!(eth_api.pending_block().lock().await.is_none());
+
+        let recovered = |tx| {
+            Recovered::new_unchecked(
+                OpTransactionSigned::new_unhashed(
+                    OpTypedTransaction::Eip1559(tx),
+                    Signature::test_signature(),
+                ),
+                sender,
+            )
+        };
+        let results = EthCall::call_many_pending(
+            &eth_api,
+            vec![recovered(call(1, counter)), recovered(call(2, block_info))],
+        )
+        .await
+        .unwrap();
+
+        // the calls see the replayed state changes of the pending block and run in the env of the
+        // block after it
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].error, None);
+        assert_eq!(results[0].value, Some(B256::with_last_byte(2).into()));
+        assert_eq!(results[1].error, None);
+        assert_eq!(results[1].value, Some(B256::with_last_byte(2).into()));
     }
 }
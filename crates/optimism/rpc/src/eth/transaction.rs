@@ -1,19 +1,22 @@
 //! Loads and formats OP transaction RPC response.
 
 use alloy_consensus::Transaction as _;
-use alloy_primitives::{Bytes, PrimitiveSignature as Signature, Sealable, Sealed, B256};
+use alloy_primitives::{Address, Bytes, PrimitiveSignature as Signature, Sealable, Sealed, B256};
 use alloy_rpc_types_eth::TransactionInfo;
 use op_alloy_consensus::OpTxEnvelope;
 use op_alloy_rpc_types::{OpTransactionRequest, Transaction};
-use reth_node_api::FullNodeComponents;
-use reth_optimism_primitives::{OpReceipt, OpTransactionSigned};
+use reth_optimism_chainspec::OpChainSpec;
+use reth_optimism_primitives::{OpBlock, OpReceipt, OpTransactionSigned};
 use reth_primitives::Recovered;
 use reth_provider::{
-    BlockReader, BlockReaderIdExt, ProviderTx, ReceiptProvider, TransactionsProvider,
+    BlockReader, BlockReaderIdExt, ChainSpecProvider, ProviderTx, ReceiptProvider,
+    TransactionsProvider,
 };
 use reth_rpc_eth_api::{
-    helpers::{EthSigner, EthTransactions, LoadTransaction, SpawnBlocking},
-    FromEthApiError, FullEthApiTypes, RpcNodeCore, RpcNodeCoreExt, TransactionCompat,
+    helpers::{
+        EthSigner, EthTransactions, LoadPendingBlock, LoadReceipt, LoadTransaction, SpawnBlocking,
+    },
+    FromEthApiError, FullEthApiTypes, RpcNodeCore, RpcNodeCoreExt, RpcReceipt, TransactionCompat,
 };
 use reth_rpc_eth_types::{utils::recover_raw_transaction, EthApiError};
 use reth_transaction_pool::{PoolTransaction, TransactionOrigin, TransactionPool};
@@ -22,8 +25,16 @@ use crate::{eth::OpNodeCore, OpEthApi, OpEthApiError, SequencerClient};
 
 impl<N> EthTransactions for OpEthApi<N>
 where
-    Self: LoadTransaction<Provider: BlockReaderIdExt>,
-    N: OpNodeCore<Provider: BlockReader<Transaction = ProviderTx<Self::Provider>>>,
+    Self: LoadTransaction<Provider: BlockReaderIdExt>
+        + LoadPendingBlock<Error = OpEthApiError>
+        + SpawnBlocking,
+    N: OpNodeCore<
+        Provider: BlockReader<
+            Transaction = ProviderTx<Self::Provider>,
+            Block = OpBlock,
+            Receipt = OpReceipt,
+        > + ChainSpecProvider<ChainSpec = OpChainSpec>,
+    >,
 {
     fn signers(&self) -> &parking_lot::RwLock<Vec<Box<dyn EthSigner<ProviderTx<Self::Provider>>>>> {
         self.inner.eth_api.signers()
@@ -54,6 +65,25 @@ where
 
         Ok(hash)
     }
+
+    /// Returns the receipt of a mined transaction, or of a transaction in the pending block if
+    /// the pending block is built from the pool.
+    async fn transaction_receipt(
+        &self,
+        hash: B256,
+    ) -> Result<Option<RpcReceipt<Self::NetworkTypes>>, Self::Error>
+    where
+        Self: LoadReceipt + 'static,
+    {
+        if let Some((tx, meta, receipt)) = self.load_transaction_and_receipt(hash).await? {
+            return self.build_transaction_receipt(tx, meta, receipt).await.map(Some)
+        }
+
+        if !self.inner.compute_pending_block {
+            return Ok(None)
+        }
+        self.pending_transaction_receipt(hash).await
+    }
 }
 
 impl<N> LoadTransaction for OpEthApi<N>
@@ -76,7 +106,7 @@ where
 
 impl<N> TransactionCompat<OpTransactionSigned> for OpEthApi<N>
 where
-    N: FullNodeComponents<Provider: ReceiptProvider<Receipt = OpReceipt>>,
+    N: OpNodeCore<Provider: ReceiptProvider<Receipt = OpReceipt>>,
 {
     type Transaction = Transaction;
     type Error = OpEthApiError;
@@ -107,35 +137,7 @@ where
                 });
         }
 
-        let TransactionInfo {
-            block_hash, block_number, index: transaction_index, base_fee, ..
-        } = tx_info;
-
-        let effective_gas_price = if inner.is_deposit() {
-            // For deposits, we must always set the `gasPrice` field to 0 in rpc
-            // deposit tx don't have a gas price field, but serde of `Transaction` will take care of
-            // it
-            0
-        } else {
-            base_fee
-                .map(|base_fee| {
-                    inner.effective_tip_per_gas(base_fee).unwrap_or_default() + base_fee as u128
-                })
-                .unwrap_or_else(|| inner.max_fee_per_gas())
-        };
-
-        Ok(Transaction {
-            inner: alloy_rpc_types_eth::Transaction {
-                inner,
-                block_hash,
-                block_number,
-                transaction_index,
-                from,
-                effective_gas_price: Some(effective_gas_price),
-            },
-            deposit_nonce,
-            deposit_receipt_version,
-        })
+        Ok(into_rpc_transaction(inner, from, tx_info, deposit_nonce, deposit_receipt_version))
     }
 
     fn build_simulate_v1_transaction(
@@ -173,3 +175,93 @@ where
         *input = input.slice(..4);
     }
 }
+
+/// Converts the envelope of a transaction included in a block into its RPC representation.
+///
+/// The envelope is kept intact, so typed transactions, including deposits, retain their type and
+/// type specific fields.
+fn into_rpc_transaction(
+    inner: OpTxEnvelope,
+    from: Address,
+    tx_info: TransactionInfo,
+    deposit_nonce: Option<u64>,
+    deposit_receipt_version: Option<u64>,
+) -> Transaction {
+    let TransactionInfo { block_hash, block_number, index: transaction_index, base_fee, .. } =
+        tx_info;
+
+    let effective_gas_price = if inner.is_deposit() {
+        // For deposits, we must always set the `gasPrice` field to 0 in rpc
+        // deposit tx don't have a gas price field, but serde of `Transaction` will take care of
+        // it
+        0
+    } else {
+        base_fee
+            .map(|base_fee| {
+                inner.effective_tip_per_gas(base_fee).unwrap_or_default() + base_fee as u128
+            })
+            .unwrap_or_else(|| inner.max_fee_per_gas())
+    };
+
+    Transaction {
+        inner: alloy_rpc_types_eth::Transaction {
+            inner,
+            block_hash,
+            block_number,
+            transaction_index,
+            from,
+            effective_gas_price: Some(effective_gas_price),
+        },
+        deposit_nonce,
+        deposit_receipt_version,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{TxKind, U256};
+    use op_alloy_consensus::{OpTxType, TxDeposit};
+
+    #[test]
+    fn deposit_round_trips_through_json() {
+        let from = Address::random();
+        let deposit = TxDeposit {
+            source_hash: B256::random(),
+            from,
+            to: TxKind::Call(Address::random()),
+            mint: Some(5),
+            value: U256::from(3),
+            gas_limit: 100_000,
+            is_system_transaction: false,
+            input: Bytes::from_static(&[1, 2, 3]),
+        };
+        let tx_info = TransactionInfo {
+            hash: None,
+            index: Some(0),
+            block_hash: Some(B256::random()),
+            block_number: Some(1),
+            base_fee: Some(7),
+        };
+
+        let tx = into_rpc_transaction(
+            OpTxEnvelope::Deposit(deposit.clone().seal_slow()),
+            from,
+            tx_info,
+            Some(4),
+            Some(1),
+        );
+
+        let json = serde_json::to_value(&tx).unwrap();
+        assert_eq!(json["type"], "0x7e");
+        assert_eq!(json["sourceHash"], deposit.source_hash.to_string());
+        assert_eq!(json["mint"], "0x5");
+        assert_eq!(json["gasPrice"], "0x0");
+        assert_eq!(json["depositReceiptVersion"], "0x1");
+
+        let decoded: Transaction = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.inner.inner.tx_type(), OpTxType::Deposit);
+        assert_eq!(decoded.inner.inner.as_deposit().map(|tx| tx.inner()), Some(&deposit));
+        assert_eq!(decoded, tx);
+    }
+}
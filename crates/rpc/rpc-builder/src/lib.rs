@@ -198,7 +198,7 @@ use reth_rpc::{
 };
 use reth_rpc_api::servers::*;
 use reth_rpc_eth_api::{
-    helpers::{Call, EthApiSpec, EthTransactions, LoadPendingBlock, TraceExt},
+    helpers::{Call, EthApiSpec, EthTransactions, LoadPendingBlock, SpawnBlocking, TraceExt},
     EthApiServer, EthApiTypes, FullEthApiServer, RpcBlock, RpcHeader, RpcReceipt, RpcTransaction,
 };
 use reth_rpc_eth_types::{EthConfig, EthSubscriptionIdProvider};
@@ -677,6 +677,7 @@ where
         Pool: Clone,
         EvmConfig: Clone,
         Network: Clone,
+        EthApi<Provider, Pool, Network, EvmConfig>: LoadPendingBlock + SpawnBlocking,
     {
         self.eth_api_builder().build()
     }
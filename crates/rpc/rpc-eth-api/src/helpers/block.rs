@@ -49,6 +49,10 @@ pub trait EthBlocks: LoadBlock {
     ///
     /// If `full` is true, the block object will contain all transaction objects, otherwise it will
     /// only contain the transaction hashes.
+    ///
+    /// The pending block is returned with the hash of the block as it was built, which its
+    /// transaction objects refer to as well. While the block is cached, it can also be fetched by
+    /// that hash, see [`LoadPendingBlock::local_pending_block_by_hash`].
     fn rpc_block(
         &self,
         block_id: BlockId,
@@ -241,10 +245,19 @@ pub trait LoadBlock: LoadPendingBlock + SpawnBlocking + RpcNodeCoreExt {
                 None => return Ok(None),
             };
 
-            self.cache()
+            let block = self
+                .cache()
                 .get_sealed_block_with_senders(block_hash)
                 .await
-                .map_err(Self::Error::from_eth_err)
+                .map_err(Self::Error::from_eth_err)?;
+
+            // the locally built pending block isn't stored, so it can only be found by its hash
+            // in the pending block cache
+            if block.is_none() && block_id.is_hash() {
+                return Ok(self.local_pending_block_by_hash(block_hash).await.map(Arc::new))
+            }
+
+            Ok(block)
         }
     }
 }
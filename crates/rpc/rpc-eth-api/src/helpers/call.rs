@@ -12,7 +12,7 @@ use alloy_rpc_types_eth::{
     simulate::{SimBlock, SimulatePayload, SimulatedBlock},
     state::{EvmOverrides, StateOverride},
     transaction::TransactionRequest,
-    BlockId, Bundle, EthCallResponse, StateContext, TransactionInfo,
+    BlockId, BlockNumberOrTag, Bundle, EthCallResponse, StateContext, TransactionInfo,
 };
 use futures::Future;
 use reth_errors::{ProviderError, RethError};
@@ -23,7 +23,10 @@ use reth_evm::{
 use reth_node_api::BlockBody;
 use reth_primitives::{Recovered, SealedHeader};
 use reth_primitives_traits::SignedTransaction;
-use reth_provider::{BlockIdReader, ProviderHeader};
+use reth_provider::{
+    providers::BundleStateProvider, BlockIdReader, ExecutionOutcome, ProviderHeader, ProviderTx,
+    StateProviderFactory,
+};
 use reth_revm::{
     database::StateProviderDatabase,
     db::{CacheDB, State},
@@ -32,7 +35,7 @@ use reth_revm::{
 use reth_rpc_eth_types::{
     cache::db::{StateCacheDbRefMutWrapper, StateProviderTraitObjWrapper},
     error::{api::FromEvmHalt, ensure_success, FromEthApiError},
-    revm_utils::{apply_block_overrides, apply_state_overrides, caller_gas_allowance},
+    revm_utils::{apply_block_overrides, apply_state_overrides, caller_gas_allowance, CallOutcome},
     simulate::{self, EthSimulateError},
     EthApiError, RevertError, RpcInvalidTransactionError, StateCacheDb,
 };
@@ -198,7 +201,7 @@ pub trait EthCall: EstimateCall + Call + LoadPendingBlock + LoadBlock + FullEthA
                         // state root calculation is skipped for performance reasons
                         B256::ZERO,
                         transactions,
-                    );
+                    )?;
 
                     let block = simulate::build_simulated_block(
                         senders,
@@ -238,6 +241,97 @@ pub trait EthCall: EstimateCall + Call + LoadPendingBlock + LoadBlock + FullEthA
         }
     }
 
+    /// Executes the call request against the pending state and returns its output and gas used.
+    ///
+    /// Unlike [`EthCall::call`], a revert isn't an error. Instead the revert data is returned as
+    /// output, together with the revert reason decoded from it, e.g. the message of a standard
+    /// `Error(string)` revert.
+    fn call_pending_outcome(
+        &self,
+        request: TransactionRequest,
+        overrides: EvmOverrides,
+    ) -> impl Future<Output = Result<CallOutcome, Self::Error>> + Send {
+        async move {
+            let (res, _env) = self.transact_call_at(request, BlockId::pending(), overrides).await?;
+
+            match res.result {
+                ExecutionResult::Success { output, gas_used, .. } => Ok(CallOutcome {
+                    output: output.into_data(),
+                    gas_used,
+                    reverted: false,
+                    revert_reason: None,
+                }),
+                ExecutionResult::Revert { output, gas_used } => Ok(CallOutcome {
+                    revert_reason: RevertError::new(output.clone()).reason(),
+                    output,
+                    gas_used,
+                    reverted: true,
+                }),
+                ExecutionResult::Halt { reason, gas_used } => {
+                    Err(Self::Error::from_evm_halt(reason, gas_used))
+                }
+            }
+        }
+    }
+
+    /// Executes multiple independent call requests (`eth_call`) against the same state and returns
+    /// the output of each call, in request order.
+    ///
+    /// The state for the given [`BlockId`] (e.g. `pending`) is only acquired once and shared by
+    /// all calls. Each call is still executed in an isolated EVM context, so state changes of one
+    /// call are not visible to any other call of the batch.
+    ///
+    /// A failing call does not abort the batch, instead its error is returned in the
+    /// corresponding [`EthCallResponse`].
+    fn call_batch(
+        &self,
+        requests: Vec<TransactionRequest>,
+        block_number: Option<BlockId>,
+        overrides: EvmOverrides,
+    ) -> impl Future<Output = Result<Vec<EthCallResponse>, Self::Error>> + Send {
+        async move {
+            if requests.is_empty() {
+                return Err(EthApiError::InvalidParams(String::from("calls are empty.")).into())
+            }
+
+            let (evm_env, at) = self.evm_env_at(block_number.unwrap_or_default()).await?;
+
+            let this = self.clone();
+            self.spawn_blocking_io(move |_| {
+                let state = this.state_at_block_id(at)?;
+
+                let mut results = Vec::with_capacity(requests.len());
+                for request in requests {
+                    // each call gets a fresh cache layer on top of the shared state, so that the
+                    // calls can't observe each other's state changes
+                    let mut db = CacheDB::new(StateProviderDatabase::new(
+                        StateProviderTraitObjWrapper(&state),
+                    ));
+
+                    let res = this
+                        .prepare_call_env(evm_env.clone(), request, &mut db, overrides.clone())
+                        .and_then(|(evm_env, tx_env)| this.transact(&mut db, evm_env, tx_env))
+                        .and_then(|(res, _)| ensure_success::<_, Self::Error>(res.result));
+
+                    match res {
+                        Ok(output) => {
+                            results.push(EthCallResponse { value: Some(output), error: None });
+                        }
+                        Err(err) => {
+                            results.push(EthCallResponse {
+                                value: None,
+                                error: Some(err.to_string()),
+                            });
+                        }
+                    }
+                }
+
+                Ok(results)
+            })
+            .await
+        }
+    }
+
     /// Simulate arbitrary number of transactions at an arbitrary blockchain index, with the
     /// optionality of state overrides
     fn call_many(
@@ -348,6 +442,76 @@ pub trait EthCall: EstimateCall + Call + LoadPendingBlock + LoadBlock + FullEthA
         }
     }
 
+    /// Executes the given signed transactions in order on top of the local pending block, each
+    /// seeing the state changes of the transactions before it.
+    ///
+    /// Unlike independent calls, this reuses the state after the pending block for the whole
+    /// sequence. The state and the env are both derived from the same pending block, see
+    /// [`LoadPendingBlock::local_pending_block_with_bundle_state`], and the transactions are
+    /// executed in the env of the block after it. Reverted transactions yield an error response,
+    /// but still pay for their gas like in a block. Invalid transactions, e.g. because of their
+    /// nonce, yield an error response and leave the state unchanged. Fails if there is no local
+    /// pending block.
+    fn call_many_pending(
+        &self,
+        transactions: Vec<Recovered<ProviderTx<Self::Provider>>>,
+    ) -> impl Future<Output = Result<Vec<EthCallResponse>, Self::Error>> + Send {
+        async move {
+            let (block, bundle_state) =
+                self.local_pending_block_with_bundle_state().await?.ok_or_else(|| {
+                    Self::Error::from_eth_err(EthApiError::HeaderNotFound(
+                        BlockNumberOrTag::Pending.into(),
+                    ))
+                })?;
+
+            self.spawn_blocking_io(move |this| {
+                let parent = block.clone_sealed_header();
+                let state = this
+                    .provider()
+                    .history_by_block_hash(parent.parent_hash())
+                    .map_err(Self::Error::from_eth_err)?;
+                let outcome = ExecutionOutcome { bundle: bundle_state, ..Default::default() };
+                let state = BundleStateProvider::new(state, outcome);
+                let evm_env =
+                    this.next_pending_evm_env(&parent, &this.next_env_attributes(&parent)?)?;
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+
+                let mut results = Vec::with_capacity(transactions.len());
+                for tx in &transactions {
+                    let tx_env = RpcNodeCore::evm_config(&this).tx_env(tx.as_recovered_ref());
+                    let res = match this.transact(&mut db, evm_env.clone(), tx_env) {
+                        Ok((res, _)) => res,
+                        Err(err) => {
+                            results.push(EthCallResponse {
+                                value: None,
+                                error: Some(err.to_string()),
+                            });
+                            continue
+                        }
+                    };
+
+                    match ensure_success::<_, Self::Error>(res.result) {
+                        Ok(output) => {
+                            results.push(EthCallResponse { value: Some(output), error: None });
+                        }
+                        Err(err) => {
+                            results.push(EthCallResponse {
+                                value: None,
+                                error: Some(err.to_string()),
+                            });
+                        }
+                    }
+
+                    // the next transaction sees the state changes of this one
+                    db.commit(res.state);
+                }
+
+                Ok(results)
+            })
+            .await
+        }
+    }
+
     /// Creates [`AccessListResult`] for the [`TransactionRequest`] at the given
     /// [`BlockId`], or latest block.
     fn create_access_list_at(
@@ -31,7 +31,7 @@ pub use block::{EthBlocks, LoadBlock};
 pub use blocking_task::SpawnBlocking;
 pub use call::{Call, EthCall};
 pub use fee::{EthFees, LoadFee};
-pub use pending_block::LoadPendingBlock;
+pub use pending_block::{LoadPendingBlock, PendingStateProvider};
 pub use receipt::LoadReceipt;
 pub use signer::{AddDevSigners, EthSigner};
 pub use spec::EthApiSpec;
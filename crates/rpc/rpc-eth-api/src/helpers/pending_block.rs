@@ -3,38 +3,66 @@
 
 use super::SpawnBlocking;
 use crate::{types::RpcTypes, EthApiTypes, FromEthApiError, FromEvmError, RpcNodeCore};
-use alloy_consensus::{transaction::Recovered, BlockHeader, Transaction};
-use alloy_eips::eip4844::MAX_DATA_GAS_PER_BLOCK;
-use alloy_primitives::B256;
-use alloy_rpc_types_eth::BlockNumberOrTag;
-use futures::Future;
+use alloy_consensus::{transaction::Recovered, BlockHeader, Transaction, TxReceipt};
+use alloy_eips::{
+    eip1559::{BaseFeeParams, INITIAL_BASE_FEE},
+    eip4844::MAX_DATA_GAS_PER_BLOCK,
+};
+use alloy_primitives::{Address, Bytes, TxHash, B256, U256};
+use alloy_rpc_types_eth::{BlockNumberOrTag, Filter, FilteredParams, Header, Log, TransactionInfo};
+use futures::{Future, Stream, StreamExt};
 use reth_chainspec::{EthChainSpec, EthereumHardforks};
 use reth_errors::{BlockExecutionError, BlockValidationError, RethError};
 use reth_evm::{
     execute::{BlockExecutionStrategy, BlockExecutionStrategyFactory},
-    ConfigureEvmEnv, Evm,
+    ConfigureEvm, ConfigureEvmEnv, Evm, EvmEnv, EvmFor, InspectorFor,
 };
 use reth_node_api::NodePrimitives;
 use reth_primitives::{InvalidTransactionError, RecoveredBlock, SealedHeader};
-use reth_primitives_traits::Receipt;
+use reth_primitives_traits::{BlockBody as _, Receipt, SignedTransaction};
 use reth_provider::{
-    BlockExecutionResult, BlockReader, BlockReaderIdExt, ChainSpecProvider, ProviderBlock,
-    ProviderError, ProviderHeader, ProviderReceipt, ProviderTx, ReceiptProvider,
-    StateProviderFactory,
+    providers::BundleStateProvider, BlockExecutionResult, BlockReader, BlockReaderIdExt,
+    ChainSpecProvider, ExecutionOutcome, ProviderBlock, ProviderError, ProviderHeader,
+    ProviderReceipt, ProviderTx, PruneCheckpointReader, ReceiptProvider, StateProvider,
+    StateProviderBox, StateProviderFactory,
 };
 use reth_revm::{
     database::StateProviderDatabase,
-    db::{states::bundle_state::BundleRetention, State},
+    db::{states::bundle_state::BundleRetention, BundleState, State},
+};
+use reth_rpc_eth_types::{
+    cumulative_blob_gas, ensure_parent_state_available, intrinsic_gas,
+    logs_utils::matching_block_logs_with_tx_hashes, panic_message, pending_parent_state_error,
+    priority_fee_revenue, AccountDiff, DeferredTransactions, EthApiError, PendingBlock,
+    PendingBlockConfig, PendingBlockDiff, PendingBlockEnv, PendingBlockEnvOrigin,
+    PendingBlockPolicy, PendingBlockSelection, PendingBlockStatus, PendingBlockSummary,
+    PendingBuildTimings, PendingBundleTransactionResult, PendingConversionFailure,
+    PendingExclusion, PendingExclusionReason, PendingPoolSnapshot, PendingStateDiff,
+    PendingTransactionFee, PendingTransactionProof, MAX_PENDING_EXCLUSIONS,
 };
-use reth_rpc_eth_types::{EthApiError, PendingBlock, PendingBlockEnv, PendingBlockEnvOrigin};
 use reth_transaction_pool::{
-    error::InvalidPoolTransactionError, BestTransactionsAttributes, PoolTransaction,
+    error::{Eip4844PoolTransactionError, InvalidPoolTransactionError},
+    BestTransactionsAttributes, BestTransactionsFor, PoolTransaction, TransactionListenerKind,
     TransactionPool,
 };
-use revm::{context::BlockEnv, context_interface::Block};
-use std::time::{Duration, Instant};
-use tokio::sync::Mutex;
-use tracing::debug;
+use reth_trie_common::HashedPostState;
+use revm::{context::BlockEnv, context_interface::Block, inspector::NoOpInspector, Database};
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::{broadcast, mpsc, Mutex};
+use tokio_stream::wrappers::{BroadcastStream, UnboundedReceiverStream};
+use tracing::{debug, debug_span, field, trace, trace_span, warn};
+
+/// A [`StateProvider`] for the state after the local pending block.
+///
+/// Accounts, storage and code changed by the pending block are read from its post state, all
+/// other reads fall through to the state of its parent.
+pub type PendingStateProvider = BundleStateProvider<StateProviderBox, ExecutionOutcome>;
 
 /// Loads a pending block from database.
 ///
@@ -48,7 +76,8 @@ pub trait LoadPendingBlock:
     > + RpcNodeCore<
         Provider: BlockReaderIdExt<Receipt: Receipt>
                       + ChainSpecProvider<ChainSpec: EthChainSpec + EthereumHardforks>
-                      + StateProviderFactory,
+                      + StateProviderFactory
+                      + PruneCheckpointReader,
         Pool: TransactionPool<Transaction: PoolTransaction<Consensus = ProviderTx<Self::Provider>>>,
         Evm: BlockExecutionStrategyFactory<
             Primitives: NodePrimitives<
@@ -56,6 +85,7 @@ pub trait LoadPendingBlock:
                 SignedTx = ProviderTx<Self::Provider>,
                 Receipt = ProviderReceipt<Self::Provider>,
             >,
+            NextBlockEnvCtx: Send + Sync + 'static,
         >,
     >
 {
@@ -67,6 +97,11 @@ pub trait LoadPendingBlock:
         &self,
     ) -> &Mutex<Option<PendingBlock<ProviderBlock<Self::Provider>, ProviderReceipt<Self::Provider>>>>;
 
+    /// Returns the configuration for building the local pending block.
+    ///
+    /// Data access in default (L1) trait method implementations.
+    fn pending_block_config(&self) -> &PendingBlockConfig;
+
     /// Configures the [`PendingBlockEnv`] for the pending block
     ///
     /// If no pending block is available, this will derive it from the `latest` block
@@ -109,21 +144,95 @@ pub trait LoadPendingBlock:
             .map_err(Self::Error::from_eth_err)?
             .ok_or(EthApiError::HeaderNotFound(BlockNumberOrTag::Latest.into()))?;
 
-        let evm_env = self
+        let evm_env = self.next_pending_evm_env(&latest, &self.next_env_attributes(&latest)?)?;
+
+        Ok(PendingBlockEnv::new(evm_env, PendingBlockEnvOrigin::DerivedFromLatest(latest)))
+    }
+
+    /// Returns the [`EvmEnv`] of a pending block built on top of `parent` with the given
+    /// attributes.
+    ///
+    /// If [`PendingBlockConfig::base_fee_max_change_denominator`] is set, the base fee is computed
+    /// with it instead of the denominator of the chain spec. If the parent has no base fee to
+    /// derive the pending one from, [`PendingBlockConfig::fallback_base_fee`] applies.
+    fn next_pending_evm_env(
+        &self,
+        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+        attributes: &<Self::Evm as ConfigureEvmEnv>::NextBlockEnvCtx,
+    ) -> Result<EvmEnv<<Self::Evm as ConfigureEvmEnv>::Spec>, Self::Error> {
+        let mut evm_env = self
             .evm_config()
-            .next_evm_env(&latest, &self.next_env_attributes(&latest)?)
+            .next_evm_env(parent, attributes)
             .map_err(RethError::other)
             .map_err(Self::Error::from_eth_err)?;
 
-        Ok(PendingBlockEnv::new(evm_env, PendingBlockEnvOrigin::DerivedFromLatest(latest)))
+        if let Some(denominator) = self.pending_block_config().base_fee_max_change_denominator {
+            let elasticity_multiplier = self
+                .provider()
+                .chain_spec()
+                .base_fee_params_at_timestamp(evm_env.block_env.timestamp)
+                .elasticity_multiplier;
+            if let Some(base_fee) = parent
+                .next_block_base_fee(BaseFeeParams::new(denominator.get(), elasticity_multiplier))
+            {
+                evm_env.block_env.basefee = base_fee;
+            }
+        }
+
+        // the parent of the London activation block has no base fee, which EVM configs may
+        // derive a zero base fee from
+        let chain_spec = self.provider().chain_spec();
+        if parent.base_fee_per_gas().is_none() &&
+            chain_spec.is_london_active_at_block(evm_env.block_env.number)
+        {
+            evm_env.block_env.basefee = self
+                .pending_block_config()
+                .fallback_base_fee
+                .or_else(|| {
+                    chain_spec.genesis().base_fee_per_gas.and_then(|fee| u64::try_from(fee).ok())
+                })
+                .unwrap_or(INITIAL_BASE_FEE);
+        }
+
+        Ok(evm_env)
     }
 
     /// Returns [`ConfigureEvmEnv::NextBlockEnvCtx`] for building a local pending block.
+    ///
+    /// Delegates to [`LoadPendingBlock::next_env_attributes_with_fee_recipient`] without a fee
+    /// recipient.
     fn next_env_attributes(
         &self,
         parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+    ) -> Result<<Self::Evm as ConfigureEvmEnv>::NextBlockEnvCtx, Self::Error> {
+        self.next_env_attributes_with_fee_recipient(parent, None)
+    }
+
+    /// Returns [`ConfigureEvmEnv::NextBlockEnvCtx`] for building a local pending block that
+    /// suggests the given fee recipient.
+    ///
+    /// If no fee recipient is given, the beneficiary of the parent is suggested.
+    fn next_env_attributes_with_fee_recipient(
+        &self,
+        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+        fee_recipient: Option<Address>,
     ) -> Result<<Self::Evm as ConfigureEvmEnv>::NextBlockEnvCtx, Self::Error>;
 
+    /// Returns [`ConfigureEvmEnv::NextBlockEnvCtx`] for building a local pending block, like
+    /// [`LoadPendingBlock::next_env_attributes`], but allows awaiting provider reads.
+    ///
+    /// This is used when building the local pending block from async contexts, so that fetching
+    /// the attributes doesn't block a runtime thread. Delegates to
+    /// [`LoadPendingBlock::next_env_attributes`] by default.
+    fn next_env_attributes_async(
+        &self,
+        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+    ) -> impl Future<Output = Result<<Self::Evm as ConfigureEvmEnv>::NextBlockEnvCtx, Self::Error>> + Send
+    {
+        let attributes = self.next_env_attributes(parent);
+        async move { attributes }
+    }
+
     /// Returns the locally built pending block
     #[expect(clippy::type_complexity)]
     fn local_pending_block(
@@ -137,6 +246,30 @@ pub trait LoadPendingBlock:
             Self::Error,
         >,
     > + Send
+    where
+        Self: SpawnBlocking,
+    {
+        self.build_local_pending_block()
+    }
+
+    /// Returns the pending block as received from the CL, or builds the pending block on top of
+    /// the latest block via txpool.
+    ///
+    /// The built block is cached until it expires or the latest block changes. This is the
+    /// default for [`LoadPendingBlock::local_pending_block`], and yields an empty block at the
+    /// next timestamp if there is nothing to include.
+    #[expect(clippy::type_complexity)]
+    fn build_local_pending_block(
+        &self,
+    ) -> impl Future<
+        Output = Result<
+            Option<(
+                RecoveredBlock<<Self::Provider as BlockReader>::Block>,
+                Vec<ProviderReceipt<Self::Provider>>,
+            )>,
+            Self::Error,
+        >,
+    > + Send
     where
         Self: SpawnBlocking,
     {
@@ -165,11 +298,68 @@ pub trait LoadPendingBlock:
                 }
             }
 
+            let attributes = match self.next_env_attributes_async(&parent).await {
+                Ok(attributes) => attributes,
+                Err(err) => {
+                    debug!(target: "rpc", "Failed to build pending block: {:?}", err);
+                    return Ok(None)
+                }
+            };
+
+            // transactions of the previous build that are still in the pool are considered first,
+            // carried transactions that became invalid in the meantime are skipped during the build
+            let mut carried: Option<Vec<TxHash>> = if self.pending_block_config().sticky_pending {
+                lock.as_ref().map(|pending| {
+                    pending.block.body().transaction_hashes_iter().copied().collect()
+                })
+            } else {
+                None
+            };
+
+            // the pool can be unavailable for a while, e.g. while it's locked for a large update,
+            // in which case the pending block is built without any pool transactions
+            // instead of waiting for it
+            let snapshot = match self.pending_block_config().pool_timeout {
+                Some(pool_timeout) => {
+                    let carried = carried.take();
+                    let snapshot = self.spawn_blocking_io(move |this| {
+                        let snapshot = this.snapshot_pool();
+                        Ok(match carried {
+                            Some(hashes) => snapshot.prioritized(&hashes),
+                            None => snapshot,
+                        })
+                    });
+                    match tokio::time::timeout(pool_timeout, snapshot).await {
+                        Ok(Ok(snapshot)) => Some(snapshot),
+                        Ok(Err(err)) => {
+                            warn!(target: "rpc", ?err, "Building pending block without pool transactions");
+                            Some(PendingPoolSnapshot::new(std::iter::empty()))
+                        }
+                        Err(_) => {
+                            warn!(target: "rpc", ?pool_timeout, "Pool unavailable, building pending block without pool transactions");
+                            Some(PendingPoolSnapshot::new(std::iter::empty()))
+                        }
+                    }
+                }
+                None => None,
+            };
+
             // no pending block from the CL yet, so we need to build it ourselves via txpool
-            let (sealed_block, receipts) = match self
+            let (sealed_block, receipts, bundle_state, excluded) = match self
                 .spawn_blocking_io(move |this| {
                     // we rebuild the block
-                    this.build_block(&parent)
+                    let snapshot = snapshot.or_else(|| {
+                        carried.map(|hashes| this.snapshot_pool().prioritized(&hashes))
+                    });
+                    this.build_block_with_timings(
+                        &parent,
+                        attributes,
+                        snapshot.as_ref(),
+                        None,
+                        None::<NoOpInspector>,
+                        this.pending_block_config().clone(),
+                        &mut PendingBuildTimings::default(),
+                    )
                 })
                 .await
             {
@@ -182,177 +372,1935 @@ pub trait LoadPendingBlock:
 
             let now = Instant::now();
             *lock = Some(PendingBlock::new(
+                now,
                 now + Duration::from_secs(1),
                 sealed_block.clone(),
                 receipts.clone(),
+                bundle_state,
+                excluded,
             ));
 
+            self.notify_pending_block(&sealed_block);
+
             Ok(Some((sealed_block, receipts)))
         }
     }
 
-    /// Assembles a pending block.
-    fn assemble_block(
+    /// Returns the sender the locally built pending block is broadcast with each time it's
+    /// rebuilt, if notifications about pending blocks are supported.
+    ///
+    /// This is `None` by default.
+    #[expect(clippy::type_complexity)]
+    fn pending_block_sender(
         &self,
-        block_env: &BlockEnv,
-        result: &BlockExecutionResult<ProviderReceipt<Self::Provider>>,
-        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
-        state_root: B256,
-        transactions: Vec<Recovered<ProviderTx<Self::Provider>>>,
-    ) -> ProviderBlock<Self::Provider>;
+    ) -> Option<&broadcast::Sender<Arc<RecoveredBlock<ProviderBlock<Self::Provider>>>>> {
+        None
+    }
 
-    /// Builds a pending block using the configured provider and pool.
-    ///
-    /// If the origin is the actual pending block, the block is built with withdrawals.
+    /// Broadcasts a rebuilt local pending block to the subscribers, if there are any.
+    fn notify_pending_block(&self, block: &RecoveredBlock<ProviderBlock<Self::Provider>>) {
+        if let Some(sender) = self.pending_block_sender() {
+            let _ = sender.send(Arc::new(block.clone()));
+        }
+    }
+
+    /// Subscribes to the locally built pending block, which is sent each time it's rebuilt.
     ///
-    /// After Cancun, if the origin is the actual pending block, the block includes the EIP-4788 pre
-    /// block contract call using the parent beacon block root received from the CL.
+    /// Returns `None` if notifications about pending blocks are not supported, see
+    /// [`LoadPendingBlock::pending_block_sender`].
     #[expect(clippy::type_complexity)]
-    fn build_block(
+    fn subscribe_to_pending_blocks(
         &self,
-        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
-    ) -> Result<
-        (RecoveredBlock<ProviderBlock<Self::Provider>>, Vec<ProviderReceipt<Self::Provider>>),
-        Self::Error,
-    >
+    ) -> Option<broadcast::Receiver<Arc<RecoveredBlock<ProviderBlock<Self::Provider>>>>> {
+        self.pending_block_sender().map(broadcast::Sender::subscribe)
+    }
+
+    /// Returns a stream of the headers of the locally built pending block, formatted like
+    /// `newHeads` subscription entries, which yields each time the pending block is rebuilt.
+    ///
+    /// The headers are taken from the same notifications as
+    /// [`LoadPendingBlock::subscribe_to_pending_blocks`]. Notifications a slow subscriber missed
+    /// are skipped. Returns `None` if notifications about pending blocks are not supported.
+    fn pending_heads_stream(
+        &self,
+    ) -> Option<impl Stream<Item = Header<ProviderHeader<Self::Provider>>> + Send + 'static> {
+        let blocks = self.subscribe_to_pending_blocks()?;
+        Some(BroadcastStream::new(blocks).filter_map(|block| async move {
+            let block = block.ok()?;
+            Some(Header::from_consensus(block.clone_sealed_header().into(), None, None))
+        }))
+    }
+
+    /// Returns the hash of the pending block, building the local pending block if necessary.
+    ///
+    /// Once known, the locally built pending block can also be fetched by this hash, see
+    /// [`LoadPendingBlock::local_pending_block_by_hash`].
+    fn pending_block_hash(&self) -> impl Future<Output = Result<Option<B256>, Self::Error>> + Send
     where
-        EthApiError: From<ProviderError>,
+        Self: SpawnBlocking,
     {
-        let state_provider = self
-            .provider()
-            .history_by_block_hash(parent.hash())
-            .map_err(Self::Error::from_eth_err)?;
-        let state = StateProviderDatabase::new(state_provider);
-        let mut db = State::builder().with_database(state).with_bundle_update().build();
-
-        let mut strategy = self
-            .evm_config()
-            .strategy_for_next_block(&mut db, parent, self.next_env_attributes(parent)?)
-            .map_err(RethError::other)
-            .map_err(Self::Error::from_eth_err)?;
+        async move { Ok(self.local_pending_block().await?.map(|(block, _)| block.hash())) }
+    }
 
-        strategy.apply_pre_execution_changes().map_err(Self::Error::from_eth_err)?;
+    /// Returns the cached locally built pending block, if it has the given hash.
+    ///
+    /// The locally built pending block isn't stored, so unlike canonical blocks it can't be found
+    /// by its hash through the provider.
+    fn local_pending_block_by_hash(
+        &self,
+        hash: B256,
+    ) -> impl Future<Output = Option<RecoveredBlock<ProviderBlock<Self::Provider>>>> + Send {
+        async move {
+            let lock = self.pending_block().lock().await;
+            lock.as_ref()
+                .filter(|pending| pending.block.hash() == hash)
+                .map(|pending| pending.block.clone())
+        }
+    }
 
-        let block_env = strategy.evm_mut().block().clone();
+    /// Returns a digest of the composition of the pending block, which changes if its
+    /// transactions or key header fields change, see [`PendingBlock::digest_of`].
+    ///
+    /// This allows detecting changes without fetching the block. The digest of a locally built
+    /// block is only computed when it's rebuilt. Returns `None` if there is no pending block.
+    fn pending_block_digest(&self) -> impl Future<Output = Result<Option<B256>, Self::Error>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((block, _)) = self.local_pending_block().await? else { return Ok(None) };
 
-        let mut cumulative_gas_used = 0;
-        let mut sum_blob_gas_used = 0;
-        let block_gas_limit: u64 = block_env.gas_limit;
+            let lock = self.pending_block().lock().await;
+            let digest = match lock.as_ref().filter(|pending| pending.block.hash() == block.hash())
+            {
+                Some(pending) => pending.digest,
+                // the pending block was received from the CL, so it's not cached
+                None => PendingBlock::<_, ProviderReceipt<Self::Provider>>::digest_of(&block),
+            };
+            Ok(Some(digest))
+        }
+    }
 
-        let mut executed_txs = Vec::new();
-        let mut best_txs =
-            self.pool().best_transactions_with_attributes(BestTransactionsAttributes::new(
-                block_env.basefee,
-                block_env.blob_gasprice().map(|gasprice| gasprice as u64),
-            ));
+    /// Returns the [`PendingBlockStatus`] of the cached locally built pending block.
+    ///
+    /// Returns `None` if no pending block was built yet.
+    fn pending_block_status(
+        &self,
+    ) -> impl Future<Output = Result<Option<PendingBlockStatus>, Self::Error>> + Send {
+        async move {
+            let latest_hash = self
+                .provider()
+                .latest_header()
+                .map_err(Self::Error::from_eth_err)?
+                .ok_or(EthApiError::HeaderNotFound(BlockNumberOrTag::Latest.into()))?
+                .hash();
 
-        while let Some(pool_tx) = best_txs.next() {
-            // ensure we still have capacity for this transaction
-            if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
-                // we can't fit this transaction into the block, so we need to mark it as invalid
-                // which also removes all dependent transaction from the iterator before we can
-                // continue
-                best_txs.mark_invalid(
-                    &pool_tx,
-                    InvalidPoolTransactionError::ExceedsGasLimit(
-                        pool_tx.gas_limit(),
-                        block_gas_limit,
-                    ),
-                );
-                continue
-            }
+            let lock = self.pending_block().lock().await;
+            Ok(lock.as_ref().map(|pending| pending.status(latest_hash, Instant::now())))
+        }
+    }
 
-            if pool_tx.origin.is_private() {
-                // we don't want to leak any state changes made by private transactions, so we mark
-                // them as invalid here which removes all dependent transactions from the iterator
-                // before we can continue
-                best_txs.mark_invalid(
-                    &pool_tx,
-                    InvalidPoolTransactionError::Consensus(
-                        InvalidTransactionError::TxTypeNotSupported,
-                    ),
-                );
-                continue
+    /// Compares the canonical block against the last locally built pending block at the same
+    /// height.
+    ///
+    /// Returns `None` if the last built pending block was built for a different height.
+    fn compare_pending_block(
+        &self,
+        canonical: &RecoveredBlock<ProviderBlock<Self::Provider>>,
+    ) -> impl Future<Output = Option<PendingBlockDiff>> + Send {
+        async move {
+            let lock = self.pending_block().lock().await;
+            let pending = lock.as_ref()?;
+            if pending.block.number() != canonical.number() {
+                return None
             }
+            Some(PendingBlockDiff::new(&pending.block, canonical))
+        }
+    }
 
-            // convert tx to a signed transaction
-            let tx = pool_tx.to_consensus();
-
-            // There's only limited amount of blob space available per block, so we need to check if
-            // the EIP-4844 can still fit in the block
-            if let Some(tx_blob_gas) = tx.blob_gas_used() {
-                if sum_blob_gas_used + tx_blob_gas > MAX_DATA_GAS_PER_BLOCK {
-                    // we can't fit this _blob_ transaction into the block, so we mark it as
-                    // invalid, which removes its dependent transactions from
-                    // the iterator. This is similar to the gas limit condition
-                    // for regular transactions above.
-                    best_txs.mark_invalid(
-                        &pool_tx,
-                        InvalidPoolTransactionError::ExceedsGasLimit(
-                            tx_blob_gas,
-                            MAX_DATA_GAS_PER_BLOCK,
-                        ),
-                    );
-                    continue
-                }
-            }
+    /// Returns a stream of the transactions of the pending block.
+    ///
+    /// If no pending block was received from the CL, this builds a new pending block on top of
+    /// the latest block and yields every transaction as soon as it is included during the build.
+    /// The stream completes once the block is assembled, at which point the built block also
+    /// replaces the cached pending block.
+    #[allow(clippy::type_complexity)]
+    fn stream_pending_transactions(
+        &self,
+    ) -> impl Future<
+        Output = Result<
+            UnboundedReceiverStream<Recovered<ProviderTx<Self::Provider>>>,
+            Self::Error,
+        >,
+    > + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let (tx, rx) = mpsc::unbounded_channel();
 
-            let gas_used = match strategy.execute_transaction(tx.as_recovered_ref()) {
-                Ok(gas_used) => gas_used,
-                Err(BlockExecutionError::Validation(BlockValidationError::InvalidTx {
-                    error,
-                    ..
-                })) => {
-                    if error.is_nonce_too_low() {
-                        // if the nonce is too low, we can skip this transaction
-                    } else {
-                        // if the transaction is invalid, we can skip it and all of its
-                        // descendants
-                        best_txs.mark_invalid(
-                            &pool_tx,
-                            InvalidPoolTransactionError::Consensus(
-                                InvalidTransactionError::TxTypeNotSupported,
-                            ),
-                        );
+            let parent = match self.pending_block_env_and_cfg()?.origin {
+                PendingBlockEnvOrigin::ActualPending(block, _) => {
+                    // the block is already complete, so we can yield all transactions right away
+                    for recovered in block.clone_transactions_recovered() {
+                        let _ = tx.send(recovered);
                     }
-                    continue
+                    return Ok(UnboundedReceiverStream::new(rx))
                 }
-                // this is an error that we should treat as fatal for this attempt
-                Err(err) => return Err(Self::Error::from_eth_err(err)),
+                PendingBlockEnvOrigin::DerivedFromLatest(parent) => parent,
             };
 
-            // add to the total blob gas used if the transaction successfully executed
-            if let Some(tx_blob_gas) = tx.blob_gas_used() {
-                sum_blob_gas_used += tx_blob_gas;
+            let attributes = self.next_env_attributes_async(&parent).await?;
 
-                // if we've reached the max data gas per block, we can skip blob txs entirely
-                if sum_blob_gas_used == MAX_DATA_GAS_PER_BLOCK {
-                    best_txs.skip_blobs();
+            let this = self.clone();
+            self.io_task_spawner().spawn_blocking(Box::pin(async move {
+                match this.build_block_with_timings(
+                    &parent,
+                    attributes,
+                    None,
+                    Some(&tx),
+                    None::<NoOpInspector>,
+                    this.pending_block_config().clone(),
+                    &mut PendingBuildTimings::default(),
+                ) {
+                    Ok((block, receipts, bundle_state, excluded)) => {
+                        this.notify_pending_block(&block);
+                        let now = Instant::now();
+                        *this.pending_block().lock().await = Some(PendingBlock::new(
+                            now,
+                            now + Duration::from_secs(1),
+                            block,
+                            receipts,
+                            bundle_state,
+                            excluded,
+                        ));
+                    }
+                    Err(err) => {
+                        debug!(target: "rpc", "Failed to build pending block: {:?}", err);
+                    }
                 }
-            }
-
-            // add gas used by the transaction to cumulative gas used, before creating the receipt
-            cumulative_gas_used += gas_used;
+                // the stream completes once the sender is dropped
+                drop(tx);
+            }));
 
-            // append transaction to the list of executed transactions
-            executed_txs.push(tx);
+            Ok(UnboundedReceiverStream::new(rx))
         }
+    }
 
-        let result = strategy.apply_post_execution_changes().map_err(Self::Error::from_eth_err)?;
+    /// Rebuilds the local pending block on top of the latest block, regardless of the cached
+    /// pending block, and returns the time spent in each phase of the build.
+    ///
+    /// The rebuilt block only replaces the cached pending block if the build succeeds.
+    fn rebuild_pending_block_timed(
+        &self,
+    ) -> impl Future<Output = Result<PendingBuildTimings, Self::Error>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let parent = self
+                .provider()
+                .latest_header()
+                .map_err(Self::Error::from_eth_err)?
+                .ok_or(EthApiError::HeaderNotFound(BlockNumberOrTag::Latest.into()))?;
+            let attributes = self.next_env_attributes_async(&parent).await?;
 
-        // merge all transitions into bundle state.
-        db.merge_transitions(BundleRetention::PlainState);
+            let (block, receipts, bundle_state, excluded, timings) = self
+                .spawn_blocking_io(move |this| {
+                    let mut timings = PendingBuildTimings::default();
+                    let (block, receipts, bundle_state, excluded) = this.build_block_with_timings(
+                        &parent,
+                        attributes,
+                        None,
+                        None,
+                        None::<NoOpInspector>,
+                        this.pending_block_config().clone(),
+                        &mut timings,
+                    )?;
+                    Ok((block, receipts, bundle_state, excluded, timings))
+                })
+                .await?;
 
-        let bundle_state = db.take_bundle();
-        let hashed_state = db.database.hashed_post_state(&bundle_state);
+            self.notify_pending_block(&block);
+            let now = Instant::now();
+            *self.pending_block().lock().await = Some(PendingBlock::new(
+                now,
+                now + Duration::from_secs(1),
+                block,
+                receipts,
+                bundle_state,
+                excluded,
+            ));
 
-        // calculate the state root
-        let state_root = db.database.state_root(hashed_state).map_err(Self::Error::from_eth_err)?;
+            Ok(timings)
+        }
+    }
 
-        let senders = executed_txs.iter().map(|tx| tx.signer()).collect();
+    /// Rebuilds the local pending block whenever a transaction enters the pending pool, which
+    /// keeps the cached pending block fresh for RPC requests.
+    ///
+    /// Transactions that enter the pool during a rebuild trigger a single rebuild afterwards. The
+    /// pool is subscribed to right away, the returned future runs until the pool stops emitting
+    /// events and is meant to be spawned as a background task. It completes right away unless
+    /// [`PendingBlockConfig::rebuild_on_pool_events`] is set.
+    fn rebuild_pending_block_on_pool_events(self) -> impl Future<Output = ()> + Send + 'static
+    where
+        Self: SpawnBlocking,
+    {
+        let events = self
+            .pending_block_config()
+            .rebuild_on_pool_events
+            .then(|| self.pool().pending_transactions_listener_for(TransactionListenerKind::All));
+
+        async move {
+            let Some(mut events) = events else { return };
 
-        let block = self.assemble_block(&block_env, &result, parent, state_root, executed_txs);
+            while events.recv().await.is_some() {
+                // the rebuild covers all transactions that are pending by now
+                while events.try_recv().is_ok() {}
+
+                if let Err(err) = self.rebuild_pending_block_timed().await {
+                    debug!(target: "rpc", "Failed to rebuild pending block on pool event: {:?}", err);
+                }
+            }
+        }
+    }
+
+    /// Returns the changes the local pending block makes to the state of its parent, together
+    /// with the hash of the parent.
+    ///
+    /// See [`LoadPendingBlock::local_pending_block_with_bundle_state`].
+    fn local_pending_bundle_state(
+        &self,
+    ) -> impl Future<Output = Result<Option<(B256, BundleState)>, Self::Error>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            Ok(self
+                .local_pending_block_with_bundle_state()
+                .await?
+                .map(|(block, bundle_state)| (block.parent_hash(), bundle_state)))
+        }
+    }
+
+    /// Returns the local pending block together with the changes it makes to the state of its
+    /// parent.
+    ///
+    /// The changes of a locally built pending block are recorded during its build, so they are
+    /// returned without executing the block again. A pending block that isn't cached, e.g. one
+    /// received from the CL or one replaced by a concurrent rebuild, is replayed on top of its
+    /// parent, so the changes always belong to the returned block. Returns `None` if there is no
+    /// local pending block.
+    #[expect(clippy::type_complexity)]
+    fn local_pending_block_with_bundle_state(
+        &self,
+    ) -> impl Future<
+        Output = Result<
+            Option<(RecoveredBlock<ProviderBlock<Self::Provider>>, BundleState)>,
+            Self::Error,
+        >,
+    > + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((block, _)) = self.local_pending_block().await? else { return Ok(None) };
+
+            if let Some(pending) = self.pending_block().lock().await.as_ref() {
+                if pending.block.hash() == block.hash() {
+                    let bundle_state = pending.bundle_state.clone();
+                    return Ok(Some((block, bundle_state)))
+                }
+            }
+
+            self.spawn_blocking_io(move |this| {
+                let parent_hash = block.parent_hash();
+                let parent = this
+                    .provider()
+                    .sealed_header_by_id(parent_hash.into())
+                    .map_err(Self::Error::from_eth_err)?
+                    .ok_or(EthApiError::HeaderNotFound(parent_hash.into()))?;
+
+                let state_provider = this
+                    .provider()
+                    .history_by_block_hash(parent_hash)
+                    .map_err(Self::Error::from_eth_err)?;
+                let state = StateProviderDatabase::new(state_provider);
+                let mut db = State::builder().with_database(state).with_bundle_update().build();
+
+                // replay with the env of the pending block
+                let evm = this
+                    .evm_config()
+                    .evm_with_env(&mut db, this.evm_config().evm_env(block.header()));
+                let ctx = this
+                    .evm_config()
+                    .context_for_next_block(&parent, this.next_env_attributes(&parent)?);
+                let mut strategy = this.evm_config().create_strategy(evm, ctx);
+
+                strategy.apply_pre_execution_changes().map_err(Self::Error::from_eth_err)?;
+                let block_env = strategy.evm_mut().block().clone();
+                for tx in block.transactions_recovered() {
+                    strategy.execute_transaction(tx).map_err(Self::Error::from_eth_err)?;
+                }
+                let result =
+                    strategy.apply_post_execution_changes().map_err(Self::Error::from_eth_err)?;
+
+                let transactions = block.clone_transactions_recovered().collect::<Vec<_>>();
+                this.apply_pending_state_changes(&mut db, &block_env, &result, &transactions)?;
+
+                db.merge_transitions(BundleRetention::PlainState);
+
+                Ok(Some((block, db.take_bundle())))
+            })
+            .await
+        }
+    }
+
+    /// Returns the hashed post state of the local pending block, together with the hash of the
+    /// block it was built on.
+    ///
+    /// See [`LoadPendingBlock::local_pending_bundle_state`].
+    fn local_pending_state(
+        &self,
+    ) -> impl Future<Output = Result<Option<(B256, HashedPostState)>, Self::Error>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((parent_hash, bundle_state)) = self.local_pending_bundle_state().await? else {
+                return Ok(None)
+            };
+
+            self.spawn_blocking_io(move |this| {
+                let state = this
+                    .provider()
+                    .history_by_block_hash(parent_hash)
+                    .map_err(Self::Error::from_eth_err)?;
+                Ok(Some((parent_hash, state.hashed_post_state(&bundle_state))))
+            })
+            .await
+        }
+    }
+
+    /// Returns a [`PendingStateProvider`] for the state after the local pending block.
+    ///
+    /// Returns `None` if there is no local pending block.
+    fn pending_state_provider(
+        &self,
+    ) -> impl Future<Output = Result<Option<PendingStateProvider>, Self::Error>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((parent_hash, bundle_state)) = self.local_pending_bundle_state().await? else {
+                return Ok(None)
+            };
+
+            self.spawn_blocking_io(move |this| {
+                let state = this
+                    .provider()
+                    .history_by_block_hash(parent_hash)
+                    .map_err(Self::Error::from_eth_err)?;
+                let outcome = ExecutionOutcome { bundle: bundle_state, ..Default::default() };
+                Ok(Some(BundleStateProvider::new(state, outcome)))
+            })
+            .await
+        }
+    }
+
+    /// Returns the balances of the given accounts in the state after the local pending block, in
+    /// the order of the given addresses.
+    ///
+    /// The pending state is only acquired once for all accounts. If there is no local pending
+    /// block, the balances are read from the latest state.
+    fn pending_balances(
+        &self,
+        addresses: Vec<Address>,
+    ) -> impl Future<Output = Result<Vec<U256>, Self::Error>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let pending = self.pending_state_provider().await?;
+
+            self.spawn_blocking_io(move |this| {
+                let state: StateProviderBox = match pending {
+                    Some(pending) => Box::new(pending),
+                    None => this.provider().latest().map_err(Self::Error::from_eth_err)?,
+                };
+                addresses
+                    .iter()
+                    .map(|address| {
+                        Ok(state
+                            .account_balance(address)
+                            .map_err(Self::Error::from_eth_err)?
+                            .unwrap_or_default())
+                    })
+                    .collect()
+            })
+            .await
+        }
+    }
+
+    /// Returns the balance, nonce and storage changes the given account would experience if the
+    /// pending block were applied on top of the latest state.
+    ///
+    /// This is derived from the bundle state of
+    /// [`LoadPendingBlock::local_pending_bundle_state`], which is recorded when the pending block
+    /// is built locally. The diff is empty if the account isn't touched by the pending block.
+    fn pending_account_diff(
+        &self,
+        address: Address,
+    ) -> impl Future<Output = Result<AccountDiff, Self::Error>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((_, bundle_state)) = self.local_pending_bundle_state().await? else {
+                return Ok(AccountDiff::default())
+            };
+
+            Ok(bundle_state
+                .account(&address)
+                .map(AccountDiff::from_bundle_account)
+                .unwrap_or_default())
+        }
+    }
+
+    /// Returns the balance, nonce and storage changes of all accounts that would be modified if
+    /// the pending block were applied on top of the latest state.
+    ///
+    /// This is derived from the bundle state of
+    /// [`LoadPendingBlock::local_pending_bundle_state`], which is recorded when the pending block
+    /// is built locally, so it neither executes the block again nor requires any state lookups.
+    /// The diff is empty if there is no local pending block.
+    fn pending_state_diff(
+        &self,
+    ) -> impl Future<Output = Result<PendingStateDiff, Self::Error>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((_, bundle_state)) = self.local_pending_bundle_state().await? else {
+                return Ok(PendingStateDiff::default())
+            };
+
+            Ok(PendingStateDiff::from_bundle_state(&bundle_state))
+        }
+    }
+
+    /// Returns the RLP encoded pending block.
+    ///
+    /// The encoding is the same as that of a canonical block with the same contents, e.g. as
+    /// returned by `debug_getRawBlock`. Returns `None` if there is no pending block.
+    fn pending_block_rlp(&self) -> impl Future<Output = Result<Option<Bytes>, Self::Error>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((block, _)) = self.local_pending_block().await? else { return Ok(None) };
+            Ok(Some(alloy_rlp::encode(block.into_block()).into()))
+        }
+    }
+
+    /// Returns the receipt of the transaction at the given index of the pending block.
+    ///
+    /// Returns `None` if there is no pending block or the index is not below its transaction
+    /// count.
+    fn pending_receipt_by_index(
+        &self,
+        index: usize,
+    ) -> impl Future<Output = Result<Option<ProviderReceipt<Self::Provider>>, Self::Error>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((block, receipts)) = self.local_pending_block().await? else {
+                return Ok(None)
+            };
+
+            if index >= block.body().transaction_count() {
+                return Ok(None)
+            }
+
+            Ok(receipts.into_iter().nth(index))
+        }
+    }
+
+    /// Returns the transactions of the pending block paired with their receipts, in block order.
+    ///
+    /// Returns `None` if there is no pending block.
+    #[expect(clippy::type_complexity)]
+    fn pending_transactions_with_receipts(
+        &self,
+    ) -> impl Future<
+        Output = Result<
+            Option<
+                impl Iterator<
+                        Item = (
+                            Recovered<ProviderTx<Self::Provider>>,
+                            ProviderReceipt<Self::Provider>,
+                        ),
+                    > + Send,
+            >,
+            Self::Error,
+        >,
+    > + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((block, receipts)) = self.local_pending_block().await? else {
+                return Ok(None)
+            };
+
+            // the executor creates exactly one receipt per transaction, in the same order
+            debug_assert_eq!(block.body().transaction_count(), receipts.len());
+            Ok(Some(block.into_transactions_recovered().zip(receipts)))
+        }
+    }
+
+    /// Returns the transaction at the given index of the pending block together with its pending
+    /// block context, i.e. the hash, number and base fee of the pending block.
+    ///
+    /// Returns `None` if there is no pending block or the index is not below its transaction
+    /// count.
+    #[expect(clippy::type_complexity)]
+    fn pending_transaction_by_index(
+        &self,
+        index: usize,
+    ) -> impl Future<
+        Output = Result<
+            Option<(Recovered<ProviderTx<Self::Provider>>, TransactionInfo)>,
+            Self::Error,
+        >,
+    > + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((block, _)) = self.local_pending_block().await? else { return Ok(None) };
+
+            let Some((signer, tx)) = block.transactions_with_sender().nth(index) else {
+                return Ok(None)
+            };
+            let tx_info = TransactionInfo {
+                hash: Some(*tx.tx_hash()),
+                block_hash: Some(block.hash()),
+                block_number: Some(block.number()),
+                base_fee: block.base_fee_per_gas(),
+                index: Some(index as u64),
+            };
+
+            Ok(Some((tx.clone().with_signer(*signer), tx_info)))
+        }
+    }
+
+    /// Returns the transactions of the pending block sent by the given address, in block order,
+    /// each together with its pending block context including its index in the block.
+    ///
+    /// Returns an empty list if there is no pending block.
+    #[expect(clippy::type_complexity)]
+    fn pending_transactions_by_sender(
+        &self,
+        sender: Address,
+    ) -> impl Future<
+        Output = Result<Vec<(Recovered<ProviderTx<Self::Provider>>, TransactionInfo)>, Self::Error>,
+    > + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((block, _)) = self.local_pending_block().await? else { return Ok(Vec::new()) };
+
+            Ok(block
+                .transactions_with_sender()
+                .enumerate()
+                .filter(|(_, (signer, _))| **signer == sender)
+                .map(|(index, (signer, tx))| {
+                    let tx_info = TransactionInfo {
+                        hash: Some(*tx.tx_hash()),
+                        block_hash: Some(block.hash()),
+                        block_number: Some(block.number()),
+                        base_fee: block.base_fee_per_gas(),
+                        index: Some(index as u64),
+                    };
+                    (tx.clone().with_signer(*signer), tx_info)
+                })
+                .collect())
+        }
+    }
+
+    /// Returns the logs of the pending block that match the given filter, in the same form as
+    /// `eth_getLogs` returns logs of sealed blocks.
+    ///
+    /// If [`PendingBlockConfig::sort_pending_logs`] is set, the logs are sorted by transaction and
+    /// log index. Returns `None` if there is no pending block.
+    fn pending_logs(
+        &self,
+        filter: Filter,
+    ) -> impl Future<Output = Result<Option<Vec<Log>>, Self::Error>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((block, receipts)) = self.local_pending_block().await? else {
+                return Ok(None)
+            };
+
+            let mut logs = matching_block_logs_with_tx_hashes(
+                &FilteredParams::new(Some(filter)),
+                block.num_hash(),
+                block.body().transactions_iter().map(|tx| *tx.tx_hash()).zip(&receipts),
+                false,
+            );
+            if self.pending_block_config().sort_pending_logs {
+                logs.sort_by_key(|log| (log.transaction_index, log.log_index));
+            }
+            Ok(Some(logs))
+        }
+    }
+
+    /// Returns a Merkle proof that the transaction with the given hash is included in the
+    /// transactions trie of the pending block, see [`PendingTransactionProof`].
+    ///
+    /// Returns `None` if there is no pending block or the transaction is not part of it.
+    fn pending_tx_proof(
+        &self,
+        hash: TxHash,
+    ) -> impl Future<Output = Result<Option<PendingTransactionProof>, Self::Error>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((block, _)) = self.local_pending_block().await? else { return Ok(None) };
+
+            let transactions = block.body().transactions();
+            let Some(index) = transactions.iter().position(|tx| *tx.tx_hash() == hash) else {
+                return Ok(None)
+            };
+            Ok(PendingTransactionProof::new(transactions, index))
+        }
+    }
+
+    /// Returns the total fees the fee recipient earns from the pending block: the priority fees
+    /// of all its transactions plus their network specific fees, see
+    /// [`LoadPendingBlock::pending_data_fees`].
+    ///
+    /// Returns `None` if there is no pending block.
+    fn pending_block_fee_revenue(
+        &self,
+    ) -> impl Future<Output = Result<Option<U256>, Self::Error>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((block, receipts)) = self.local_pending_block().await? else {
+                return Ok(None)
+            };
+
+            let base_fee = block.header().base_fee_per_gas().unwrap_or_default();
+            let revenue = priority_fee_revenue(
+                base_fee,
+                block.body().transactions().iter().zip(&receipts),
+                |tx| self.is_system_transaction(tx),
+            );
+
+            Ok(Some(revenue + self.pending_data_fees(&block)?))
+        }
+    }
+
+    /// Returns the pending block together with the fee each of its transactions would pay at the
+    /// current pending base fee of the pool, for fee market tooling.
+    ///
+    /// Transactions whose max fee is below the current pending base fee are flagged as
+    /// underpriced. Returns `None` if there is no pending block.
+    #[expect(clippy::type_complexity)]
+    fn pending_block_fee_preview(
+        &self,
+    ) -> impl Future<
+        Output = Result<
+            Option<(RecoveredBlock<ProviderBlock<Self::Provider>>, Vec<PendingTransactionFee>)>,
+            Self::Error,
+        >,
+    > + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((block, _)) = self.local_pending_block().await? else { return Ok(None) };
+
+            let base_fee = self.pool().block_info().pending_basefee;
+            let fees = block
+                .body()
+                .transactions()
+                .iter()
+                .map(|tx| {
+                    // system transactions don't pay a base fee, so they can't be underpriced
+                    let tip = if self.is_system_transaction(tx) {
+                        Some(0)
+                    } else {
+                        tx.effective_tip_per_gas(base_fee)
+                    };
+                    PendingTransactionFee {
+                        hash: *tx.tx_hash(),
+                        priority_fee: tip.unwrap_or_default(),
+                        underpriced: tip.is_none(),
+                    }
+                })
+                .collect();
+
+            Ok(Some((block, fees)))
+        }
+    }
+
+    /// Returns the blob gas used by each transaction of the pending block and all transactions
+    /// before it, in block order.
+    ///
+    /// The last entry equals the blob gas used by the pending block. Returns `None` if there is no
+    /// pending block.
+    fn pending_cumulative_blob_gas(
+        &self,
+    ) -> impl Future<Output = Result<Option<Vec<u64>>, Self::Error>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((block, _)) = self.local_pending_block().await? else { return Ok(None) };
+            Ok(Some(cumulative_blob_gas(block.body().transactions())))
+        }
+    }
+
+    /// Returns the pending block together with the pool transactions it doesn't include, although
+    /// they would have fit into the gas left at the end of the build, e.g. because a limit stopped
+    /// the selection.
+    ///
+    /// The excluded transactions are recorded during the build of the pending block, in the order
+    /// the pool yielded them, and the remaining gas is the gas target of
+    /// [`PendingBlockConfig::gas_target`] minus the gas used by the pool transactions, but at most
+    /// the gas left in the block. At most
+    /// [`MAX_PENDING_EXCLUSIONS`] transactions are reported. Returns `None` if there is no locally
+    /// built pending block.
+    #[expect(clippy::type_complexity)]
+    fn pending_excluded_fitting_transactions(
+        &self,
+    ) -> impl Future<
+        Output = Result<
+            Option<(RecoveredBlock<ProviderBlock<Self::Provider>>, Vec<PendingExclusion>)>,
+            Self::Error,
+        >,
+    > + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some((block, _)) = self.local_pending_block().await? else { return Ok(None) };
+
+            let excluded = match self.pending_block().lock().await.as_ref() {
+                Some(pending) if pending.block.hash() == block.hash() => pending.excluded.clone(),
+                _ => return Ok(None),
+            };
+
+            Ok(Some((block, excluded)))
+        }
+    }
+
+    /// Returns true if the given transaction is a system transaction that doesn't pay a priority
+    /// fee, e.g. a deposit on OP, which fee accounting excludes.
+    ///
+    /// This is false by default.
+    fn is_system_transaction(&self, _tx: &ProviderTx<Self::Provider>) -> bool {
+        false
+    }
+
+    /// Returns the fees the transactions of the pending block pay besides their gas, e.g. the
+    /// L1 data fees on OP.
+    ///
+    /// This is zero by default.
+    fn pending_data_fees(
+        &self,
+        _block: &RecoveredBlock<ProviderBlock<Self::Provider>>,
+    ) -> Result<U256, Self::Error> {
+        Ok(U256::ZERO)
+    }
+
+    /// Applies additional state changes to the state of the pending block, after the
+    /// pre-execution changes were applied and before the first transaction is executed.
+    ///
+    /// This is a no-op by default.
+    fn apply_pending_pre_execution_changes<DB>(
+        &self,
+        _db: &mut State<DB>,
+        _block_env: &BlockEnv,
+    ) -> Result<(), Self::Error>
+    where
+        DB: Database<Error = ProviderError>,
+    {
+        Ok(())
+    }
+
+    /// Returns the base fee the pool transactions of the pending block are selected against, after
+    /// the [system transactions](LoadPendingBlock::is_system_transaction) placed at the top of the
+    /// block, e.g. deposits on OP, used the given gas.
+    ///
+    /// The base fee of a block doesn't depend on the gas its own transactions use, so all
+    /// transactions are charged the base fee of the block env, which is returned by default. A
+    /// lower base fee doesn't let in transactions execution rejects, they are skipped as invalid.
+    fn pending_selection_base_fee(&self, block_env: &BlockEnv, _system_gas_used: u64) -> u64 {
+        block_env.basefee
+    }
+
+    /// Applies additional state changes to the state of the pending block, after all
+    /// transactions were executed and before the state root is calculated.
+    ///
+    /// This is a no-op by default.
+    fn apply_pending_state_changes<DB>(
+        &self,
+        _db: &mut State<DB>,
+        _block_env: &BlockEnv,
+        _result: &BlockExecutionResult<ProviderReceipt<Self::Provider>>,
+        _transactions: &[Recovered<ProviderTx<Self::Provider>>],
+    ) -> Result<(), Self::Error>
+    where
+        DB: Database<Error = ProviderError>,
+    {
+        Ok(())
+    }
+
+    /// Transforms the receipts of the pending block after all transactions were executed, before
+    /// the block and its receipt root are assembled from them.
+    ///
+    /// This is a no-op by default.
+    fn post_process_pending_receipts(&self, _receipts: &mut [ProviderReceipt<Self::Provider>]) {}
+
+    /// Returns whether the given transaction may be included in the pending block built on top of
+    /// the given block env.
+    ///
+    /// This is checked for every transaction yielded by the pool, before it is executed. Rejected
+    /// transactions are skipped together with their dependent transactions. All transactions are
+    /// allowed by default.
+    fn is_pending_transaction_allowed(
+        &self,
+        _block_env: &BlockEnv,
+        _tx: &Recovered<ProviderTx<Self::Provider>>,
+    ) -> bool {
+        true
+    }
+
+    /// Converts a transaction yielded by the pool into the transaction type of the pending block.
+    ///
+    /// The conversion can't fail by default. Pool transaction types that can hold transactions
+    /// they can't decode return an error instead, which skips the transaction or fails the build
+    /// depending on [`PendingBlockConfig::conversion_failure`].
+    fn try_to_consensus(
+        &self,
+        tx: &<Self::Pool as TransactionPool>::Transaction,
+    ) -> Result<Recovered<ProviderTx<Self::Provider>>, Self::Error> {
+        Ok(tx.clone_into_consensus())
+    }
+
+    /// Returns whether the given transaction is valid at the timestamp of the pending block, for
+    /// transactions with time-based validity.
+    ///
+    /// Invalid transactions are skipped together with their dependent transactions. Delegates to
+    /// the configured [`PendingBlockConfig::validity_check`], all transactions are valid if none
+    /// is configured.
+    fn validity_check(
+        &self,
+        tx: &Recovered<ProviderTx<Self::Provider>>,
+        pending_timestamp: u64,
+    ) -> bool {
+        self.pending_block_config()
+            .validity_check
+            .as_ref()
+            .is_none_or(|check| check.is_valid(tx.tx(), pending_timestamp))
+    }
+
+    /// Assembles a pending block.
+    ///
+    /// Returns an error if the forks active at the block's timestamp are inconsistent.
+    fn assemble_block(
+        &self,
+        block_env: &BlockEnv,
+        result: &BlockExecutionResult<ProviderReceipt<Self::Provider>>,
+        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+        state_root: B256,
+        transactions: Vec<Recovered<ProviderTx<Self::Provider>>>,
+    ) -> Result<ProviderBlock<Self::Provider>, Self::Error>;
+
+    /// Builds a pending block using the configured provider and pool.
+    ///
+    /// If the origin is the actual pending block, the block is built with withdrawals.
+    ///
+    /// After Cancun, if the origin is the actual pending block, the block includes the EIP-4788 pre
+    /// block contract call using the parent beacon block root received from the CL.
+    #[expect(clippy::type_complexity)]
+    fn build_block(
+        &self,
+        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+    ) -> Result<
+        (RecoveredBlock<ProviderBlock<Self::Provider>>, Vec<ProviderReceipt<Self::Provider>>),
+        Self::Error,
+    >
+    where
+        EthApiError: From<ProviderError>,
+    {
+        self.build_block_with_listener(parent, self.next_env_attributes(parent)?, None, None)
+    }
+
+    /// Builds a pending block like [`LoadPendingBlock::build_block`], but with the given fee
+    /// recipient as beneficiary.
+    ///
+    /// The fee recipient is only used for this build, so callers can model different coinbases
+    /// without changing the shared configuration.
+    #[expect(clippy::type_complexity)]
+    fn build_block_with_fee_recipient(
+        &self,
+        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+        fee_recipient: Address,
+    ) -> Result<
+        (RecoveredBlock<ProviderBlock<Self::Provider>>, Vec<ProviderReceipt<Self::Provider>>),
+        Self::Error,
+    >
+    where
+        EthApiError: From<ProviderError>,
+    {
+        let attributes =
+            self.next_env_attributes_with_fee_recipient(parent, Some(fee_recipient))?;
+        self.build_block_with_listener(parent, attributes, None, None)
+    }
+
+    /// Takes a snapshot of the transactions the pool currently yields for the pending block.
+    ///
+    /// Transactions with the same effective tip are ordered by hash, so the same set of pool
+    /// transactions always yields the same snapshot. See
+    /// [`LoadPendingBlock::build_block_with_snapshot`].
+    fn snapshot_pool(&self) -> PendingPoolSnapshot<<Self::Pool as TransactionPool>::Transaction> {
+        PendingPoolSnapshot::new(self.pool().best_transactions())
+            .with_hash_tie_break(self.pool().block_info().pending_basefee)
+    }
+
+    /// Builds a pending block like [`LoadPendingBlock::build_block`], but only from the
+    /// transactions of the given snapshot.
+    ///
+    /// Building against the same snapshot and parent yields identical blocks.
+    #[expect(clippy::type_complexity)]
+    fn build_block_with_snapshot(
+        &self,
+        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+        snapshot: &PendingPoolSnapshot<<Self::Pool as TransactionPool>::Transaction>,
+    ) -> Result<
+        (RecoveredBlock<ProviderBlock<Self::Provider>>, Vec<ProviderReceipt<Self::Provider>>),
+        Self::Error,
+    >
+    where
+        EthApiError: From<ProviderError>,
+    {
+        self.build_block_with_listener(
+            parent,
+            self.next_env_attributes(parent)?,
+            Some(snapshot),
+            None,
+        )
+    }
+
+    /// Builds a pending block using the configured provider and pool, like
+    /// [`LoadPendingBlock::build_block`].
+    ///
+    /// The block is built with the given attributes for the next block. If a snapshot is
+    /// provided, transactions are taken from it instead of the pool. If a listener is provided,
+    /// every transaction is sent to it as soon as it was included in the block.
+    #[expect(clippy::type_complexity)]
+    fn build_block_with_listener(
+        &self,
+        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+        attributes: <Self::Evm as ConfigureEvmEnv>::NextBlockEnvCtx,
+        snapshot: Option<&PendingPoolSnapshot<<Self::Pool as TransactionPool>::Transaction>>,
+        listener: Option<&mpsc::UnboundedSender<Recovered<ProviderTx<Self::Provider>>>>,
+    ) -> Result<
+        (RecoveredBlock<ProviderBlock<Self::Provider>>, Vec<ProviderReceipt<Self::Provider>>),
+        Self::Error,
+    >
+    where
+        EthApiError: From<ProviderError>,
+    {
+        self.build_block_with_timings(
+            parent,
+            attributes,
+            snapshot,
+            listener,
+            None::<NoOpInspector>,
+            self.pending_block_config().clone(),
+            &mut PendingBuildTimings::default(),
+        )
+        .map(|(block, receipts, _, _)| (block, receipts))
+    }
+
+    /// Builds one candidate pending block per policy, all from the same snapshot of the pool.
+    ///
+    /// This allows comparing the compositions different ordering and selection policies yield.
+    /// Candidates are returned in the order of the given policies.
+    fn build_candidate_blocks(
+        &self,
+        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+        policies: &[PendingBlockPolicy],
+    ) -> Result<Vec<(PendingBlockPolicy, PendingBlockSummary)>, Self::Error>
+    where
+        EthApiError: From<ProviderError>,
+    {
+        let snapshot = self.snapshot_pool();
+        let attributes = self.next_env_attributes(parent)?;
+        let evm_env = self.next_pending_evm_env(parent, &attributes)?;
+        policies
+            .iter()
+            .map(|policy| {
+                let snapshot = snapshot.ordered_by(
+                    policy.ordering,
+                    evm_env.block_env.basefee,
+                    policy.config.gas_target(evm_env.block_env.gas_limit),
+                );
+                let (block, ..) = self.build_block_with_timings(
+                    parent,
+                    attributes.clone(),
+                    Some(&snapshot),
+                    None,
+                    None::<NoOpInspector>,
+                    policy.config.clone(),
+                    &mut PendingBuildTimings::default(),
+                )?;
+                Ok((policy.clone(), PendingBlockSummary::new(&block)))
+            })
+            .collect()
+    }
+
+    /// Builds a pending block like [`LoadPendingBlock::build_block_with_listener`], executing all
+    /// transactions with the given inspector attached and selecting them with the given config
+    /// instead of [`LoadPendingBlock::pending_block_config`].
+    ///
+    /// This allows collecting metrics, e.g. opcode counts or storage access patterns, during the
+    /// build without replaying the block. Pass the inspector by mutable reference to inspect it
+    /// afterwards.
+    #[expect(clippy::type_complexity)]
+    fn build_block_with_inspector<I>(
+        &self,
+        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+        attributes: <Self::Evm as ConfigureEvmEnv>::NextBlockEnvCtx,
+        snapshot: Option<&PendingPoolSnapshot<<Self::Pool as TransactionPool>::Transaction>>,
+        listener: Option<&mpsc::UnboundedSender<Recovered<ProviderTx<Self::Provider>>>>,
+        inspector: I,
+        config: PendingBlockConfig,
+    ) -> Result<
+        (RecoveredBlock<ProviderBlock<Self::Provider>>, Vec<ProviderReceipt<Self::Provider>>),
+        Self::Error,
+    >
+    where
+        EthApiError: From<ProviderError>,
+        I: for<'a> InspectorFor<&'a mut State<StateProviderDatabase<StateProviderBox>>, Self::Evm>,
+    {
+        self.build_block_with_timings(
+            parent,
+            attributes,
+            snapshot,
+            listener,
+            Some(inspector),
+            config,
+            &mut PendingBuildTimings::default(),
+        )
+        .map(|(block, receipts, _, _)| (block, receipts))
+    }
+
+    /// Builds a pending block like [`LoadPendingBlock::build_block_with_inspector`] and records
+    /// the time spent in each phase of the build into the given timings.
+    ///
+    /// Returns the block and its receipts together with the changes it makes to the state of the
+    /// parent and the pool transactions it excludes although they would have fit.
+    ///
+    /// If [`PendingBlockConfig::include_replacements`] is set and the pool received a replacement
+    /// for an included transaction during the build, the block is built once more. The timings
+    /// then cover both builds.
+    ///
+    /// If [`PendingBlockConfig::verify_determinism`] is set, the block is built from a snapshot of
+    /// the pool, which is taken if none is given, and built once more from the same snapshot.
+    /// If the encoded blocks differ, [`EthApiError::PendingBuildNondeterministic`] is returned.
+    /// The timings only cover the first build.
+    ///
+    /// The transactions are only inspected if an inspector is given, otherwise the EVM runs
+    /// without inspection overhead.
+    ///
+    /// A panic during the build is caught and returned as
+    /// [`EthApiError::PendingBuildPanicked`].
+    #[expect(clippy::type_complexity, clippy::too_many_arguments)]
+    fn build_block_with_timings<I>(
+        &self,
+        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+        attributes: <Self::Evm as ConfigureEvmEnv>::NextBlockEnvCtx,
+        snapshot: Option<&PendingPoolSnapshot<<Self::Pool as TransactionPool>::Transaction>>,
+        listener: Option<&mpsc::UnboundedSender<Recovered<ProviderTx<Self::Provider>>>>,
+        mut inspector: Option<I>,
+        config: PendingBlockConfig,
+        timings: &mut PendingBuildTimings,
+    ) -> Result<
+        (
+            RecoveredBlock<ProviderBlock<Self::Provider>>,
+            Vec<ProviderReceipt<Self::Provider>>,
+            BundleState,
+            Vec<PendingExclusion>,
+        ),
+        Self::Error,
+    >
+    where
+        EthApiError: From<ProviderError>,
+        I: for<'a> InspectorFor<&'a mut State<StateProviderDatabase<StateProviderBox>>, Self::Evm>,
+    {
+        // both builds must see the same transactions, so a snapshot is taken if none is given
+        let owned_snapshot =
+            (config.verify_determinism && snapshot.is_none()).then(|| self.snapshot_pool());
+        let snapshot = snapshot.or(owned_snapshot.as_ref());
+
+        // a panic during the build, e.g. in a custom hook or inspector, must not take down the
+        // request, so it's turned into an error
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            let (block, receipts, bundle_state, excluded) = self.execute_pending_block(
+                parent,
+                attributes.clone(),
+                snapshot,
+                listener,
+                &[],
+                inspector.as_mut(),
+                &config,
+                timings,
+            )?;
+
+            // the transactions of a snapshot are fixed and the listener was already notified about
+            // the included transactions, so only plain pool builds are repeated
+            if config.include_replacements && snapshot.is_none() && listener.is_none() {
+                let replaced = block.transactions_with_sender().any(|(sender, tx)| {
+                    self.pool()
+                        .get_transaction_by_sender_and_nonce(*sender, tx.nonce())
+                        .is_some_and(|pool_tx| pool_tx.hash() != tx.tx_hash())
+                });
+                if replaced {
+                    debug!(target: "rpc", "Rebuilding pending block with replaced transactions");
+                    return self.execute_pending_block(
+                        parent,
+                        attributes,
+                        None,
+                        None,
+                        &[],
+                        inspector.as_mut(),
+                        &config,
+                        timings,
+                    )
+                }
+            }
+
+            if config.verify_determinism {
+                let (second, ..) = self.execute_pending_block(
+                    parent,
+                    attributes,
+                    snapshot,
+                    None,
+                    &[],
+                    inspector.as_mut(),
+                    &config,
+                    &mut PendingBuildTimings::default(),
+                )?;
+                if alloy_rlp::encode(block.sealed_block().clone_block()) !=
+                    alloy_rlp::encode(second.sealed_block().clone_block())
+                {
+                    warn!(
+                        target: "rpc",
+                        parent_number = parent.number(),
+                        first = %block.hash(),
+                        second = %second.hash(),
+                        "Pending block builds diverged"
+                    );
+                    return Err(Self::Error::from_eth_err(
+                        EthApiError::PendingBuildNondeterministic {
+                            first: block.hash(),
+                            second: second.hash(),
+                        },
+                    ))
+                }
+            }
+
+            Ok((block, receipts, bundle_state, excluded))
+        }))
+        .unwrap_or_else(|payload| {
+            let message = panic_message(&*payload);
+            warn!(
+                target: "rpc",
+                parent_number = parent.number(),
+                parent_hash = %parent.hash(),
+                %message,
+                "Pending block build panicked"
+            );
+            Err(Self::Error::from_eth_err(EthApiError::PendingBuildPanicked(message)))
+        })
+    }
+
+    /// Builds a pending block like [`LoadPendingBlock::build_block`], but executes the given
+    /// bundle of transactions in order at the top of the block, before the transactions of the
+    /// pool.
+    ///
+    /// This allows simulating a bundle on top of the pending state. Reverted bundle transactions
+    /// are included with a failure status, a bundle transaction that can't be executed at all,
+    /// e.g. because of an invalid nonce, fails the whole build. Returns the block and its
+    /// receipts together with the outcome of every bundle transaction.
+    #[expect(clippy::type_complexity)]
+    fn build_block_with_bundle(
+        &self,
+        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+        bundle: &[Recovered<ProviderTx<Self::Provider>>],
+    ) -> Result<
+        (
+            RecoveredBlock<ProviderBlock<Self::Provider>>,
+            Vec<ProviderReceipt<Self::Provider>>,
+            Vec<PendingBundleTransactionResult>,
+        ),
+        Self::Error,
+    >
+    where
+        EthApiError: From<ProviderError>,
+    {
+        let (block, receipts, ..) = self.execute_pending_block(
+            parent,
+            self.next_env_attributes(parent)?,
+            None,
+            None,
+            bundle,
+            None::<NoOpInspector>,
+            self.pending_block_config(),
+            &mut PendingBuildTimings::default(),
+        )?;
+
+        let mut prev_cumulative_gas_used = 0;
+        let results = bundle
+            .iter()
+            .zip(&receipts)
+            .map(|(tx, receipt)| {
+                let gas_used = receipt.cumulative_gas_used() - prev_cumulative_gas_used;
+                prev_cumulative_gas_used = receipt.cumulative_gas_used();
+                PendingBundleTransactionResult {
+                    hash: *tx.tx_hash(),
+                    success: receipt.status(),
+                    gas_used,
+                }
+            })
+            .collect();
+
+        Ok((block, receipts, results))
+    }
+
+    /// Executes a single build of the pending block, see
+    /// [`LoadPendingBlock::build_block_with_timings`].
+    ///
+    /// The given bundle transactions are executed first, in order and without any of the
+    /// selection checks applied to pool transactions. All transactions are executed with the given
+    /// inspector attached, if any. Returns the block and its receipts together with the changes it
+    /// makes to the state of the parent and the pool transactions it excludes although they would
+    /// have fit.
+    #[expect(clippy::type_complexity, clippy::too_many_arguments)]
+    fn execute_pending_block<I>(
+        &self,
+        parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+        attributes: <Self::Evm as ConfigureEvmEnv>::NextBlockEnvCtx,
+        snapshot: Option<&PendingPoolSnapshot<<Self::Pool as TransactionPool>::Transaction>>,
+        listener: Option<&mpsc::UnboundedSender<Recovered<ProviderTx<Self::Provider>>>>,
+        bundle: &[Recovered<ProviderTx<Self::Provider>>],
+        inspector: Option<I>,
+        config: &PendingBlockConfig,
+        timings: &mut PendingBuildTimings,
+    ) -> Result<
+        (
+            RecoveredBlock<ProviderBlock<Self::Provider>>,
+            Vec<ProviderReceipt<Self::Provider>>,
+            BundleState,
+            Vec<PendingExclusion>,
+        ),
+        Self::Error,
+    >
+    where
+        EthApiError: From<ProviderError>,
+        I: for<'a> InspectorFor<&'a mut State<StateProviderDatabase<StateProviderBox>>, Self::Evm>,
+    {
+        let build_span = debug_span!(
+            target: "rpc::eth::pending",
+            "build_pending_block",
+            parent_hash = %parent.hash(),
+            parent_number = parent.number(),
+            tx_count = field::Empty,
+        )
+        .entered();
+
+        let parent_state_error =
+            |err| Self::Error::from_eth_err(pending_parent_state_error(parent.number(), err));
+
+        // reading pruned state fails deep inside the execution with an opaque error, so we check
+        // the prune checkpoints of the parent state upfront
+        ensure_parent_state_available(self.provider(), parent.number())
+            .map_err(parent_state_error)?;
+        let state_provider =
+            self.provider().history_by_block_hash(parent.hash()).map_err(parent_state_error)?;
+        let state = StateProviderDatabase::new(state_provider);
+        let mut db = State::builder().with_database(state).with_bundle_update().build();
+
+        let evm_env = self.next_pending_evm_env(parent, &attributes)?;
+        // the header of the pending block is assembled from this env, so it must always be the
+        // child of the parent, regardless of how the EVM config derives the env
+        debug_assert_eq!(
+            evm_env.block_env.number,
+            parent.number() + 1,
+            "pending block number must follow its parent"
+        );
+
+        // the inspector hooks slow down every executed instruction, so the EVM only inspects if an
+        // inspector is given
+        let (mut result, executed_txs, block_env, excluded) = match inspector {
+            Some(inspector) => {
+                let evm = self.evm_config().evm_with_env_and_inspector(&mut db, evm_env, inspector);
+                self.execute_pending_transactions(
+                    parent, attributes, evm, snapshot, listener, bundle, config, timings,
+                )?
+            }
+            None => {
+                let evm = self.evm_config().evm_with_env(&mut db, evm_env);
+                self.execute_pending_transactions(
+                    parent, attributes, evm, snapshot, listener, bundle, config, timings,
+                )?
+            }
+        };
+        build_span.record("tx_count", executed_txs.len());
+
+        let post_execution_started = Instant::now();
+        self.apply_pending_state_changes(&mut db, &block_env, &result, &executed_txs)?;
+
+        // merge all transitions into bundle state.
+        db.merge_transitions(BundleRetention::PlainState);
+        timings.execution += post_execution_started.elapsed();
+
+        let state_root_span = debug_span!(target: "rpc::eth::pending", "state_root").entered();
+        let state_root_started = Instant::now();
+
+        let bundle_state = db.take_bundle();
+        let hashed_state = db.database.hashed_post_state(&bundle_state);
+
+        // calculate the state root
+        let state_root = db.database.state_root(hashed_state).map_err(parent_state_error)?;
+
+        timings.state_root += state_root_started.elapsed();
+        drop(state_root_span);
+        let _seal_span = debug_span!(target: "rpc::eth::pending", "seal_block").entered();
+        let sealing_started = Instant::now();
+
+        let senders = executed_txs.iter().map(|tx| tx.signer()).collect();
+
+        self.post_process_pending_receipts(&mut result.receipts);
+        let block = self.assemble_block(&block_env, &result, parent, state_root, executed_txs)?;
+        let block = RecoveredBlock::new_unhashed(block, senders);
+        timings.sealing += sealing_started.elapsed();
+
+        Ok((block, result.receipts, bundle_state, excluded))
+    }
+
+    /// Executes the bundle and pool transactions of a single pending build with the given EVM,
+    /// see [`LoadPendingBlock::execute_pending_block`].
+    ///
+    /// Returns the result of the execution including the post execution changes, the executed
+    /// transactions, the env of the block and the valid pool transactions that were excluded
+    /// although they fit into the gas left at the end of the selection.
+    #[expect(clippy::type_complexity, clippy::too_many_arguments)]
+    fn execute_pending_transactions<'a, I>(
+        &'a self,
+        parent: &'a SealedHeader<ProviderHeader<Self::Provider>>,
+        attributes: <Self::Evm as ConfigureEvmEnv>::NextBlockEnvCtx,
+        evm: EvmFor<Self::Evm, &'a mut State<StateProviderDatabase<StateProviderBox>>, I>,
+        snapshot: Option<&PendingPoolSnapshot<<Self::Pool as TransactionPool>::Transaction>>,
+        listener: Option<&mpsc::UnboundedSender<Recovered<ProviderTx<Self::Provider>>>>,
+        bundle: &[Recovered<ProviderTx<Self::Provider>>],
+        config: &PendingBlockConfig,
+        timings: &mut PendingBuildTimings,
+    ) -> Result<
+        (
+            BlockExecutionResult<ProviderReceipt<Self::Provider>>,
+            Vec<Recovered<ProviderTx<Self::Provider>>>,
+            BlockEnv,
+            Vec<PendingExclusion>,
+        ),
+        Self::Error,
+    >
+    where
+        EthApiError: From<ProviderError>,
+        I: InspectorFor<&'a mut State<StateProviderDatabase<StateProviderBox>>, Self::Evm> + 'a,
+    {
+        let parent_state_error =
+            |err| Self::Error::from_eth_err(pending_parent_state_error(parent.number(), err));
+
+        let ctx = self.evm_config().context_for_next_block(parent, attributes);
+        let mut strategy = self.evm_config().create_strategy(evm, ctx);
+
+        let pre_execution_started = Instant::now();
+        strategy.apply_pre_execution_changes().map_err(Self::Error::from_eth_err)?;
+        let block_env = strategy.evm_mut().block().clone();
+        self.apply_pending_pre_execution_changes(&mut **strategy.evm_mut().db_mut(), &block_env)?;
+        timings.execution += pre_execution_started.elapsed();
+
+        let mut cumulative_gas_used = 0;
+        // gas used by the system transactions of the bundle, which don't pay the base fee
+        let mut system_gas_used = 0;
+        // the limits only apply to the pool transactions, not to the bundle at the top
+        let mut pool_transactions = 0;
+        let mut pool_gas_used = 0;
+        let mut sum_blob_gas_used = 0;
+        let mut sum_blob_count = 0;
+        let mut sum_calldata = 0;
+        let block_gas_limit: u64 = block_env.gas_limit;
+
+        let limits = config.build_limits(block_gas_limit);
+        let gas_target = limits.gas;
+        let chain_spec = self.provider().chain_spec();
+
+        let selection_span =
+            debug_span!(target: "rpc::eth::pending", "select_transactions").entered();
+        let selection_started = Instant::now();
+        let tx_age_now = config.pending_tx_clock_now();
+        let mut execution = Duration::ZERO;
+
+        let mut executed_txs = Vec::new();
+        // pool transactions excluded by a limit, with their gas limit
+        let mut excluded = Vec::new();
+        let mut selection = PendingBlockSelection::default();
+        // distinct senders of the included transactions
+        let mut included_senders = HashSet::new();
+
+        // bundle transactions must all be included at the top of the block, so any of them that
+        // can't be executed fails the build, reverted ones are included with a failure status
+        for tx in bundle {
+            let execution_started = Instant::now();
+            let gas_used = strategy
+                .execute_transaction(tx.as_recovered_ref())
+                .map_err(Self::Error::from_eth_err)?;
+            execution += execution_started.elapsed();
+
+            cumulative_gas_used += gas_used;
+            if self.is_system_transaction(tx.tx()) {
+                system_gas_used += gas_used;
+            }
+            sum_calldata += tx.input().len();
+            if let Some(tx_blob_gas) = tx.blob_gas_used() {
+                sum_blob_gas_used += tx_blob_gas;
+                sum_blob_count +=
+                    tx.blob_versioned_hashes().map_or(0, |hashes| hashes.len() as u64);
+            }
+
+            selection.insert(*tx.tx_hash());
+            included_senders.insert(tx.signer());
+            executed_txs.push(tx.clone());
+        }
+
+        // the system transactions at the top of the block are placed, so their gas is known
+        let selection_base_fee = self.pending_selection_base_fee(&block_env, system_gas_used);
+
+        let mut deferred = DeferredTransactions::default();
+        // a deferred transaction whose predecessor of the same sender was just included
+        let mut ready = None;
+        let mut best_txs: BestTransactionsFor<Self::Pool> = match snapshot {
+            Some(snapshot) => Box::new(snapshot.best_transactions()),
+            None => self.pool().best_transactions_with_attributes(BestTransactionsAttributes::new(
+                selection_base_fee,
+                block_env.blob_gasprice().map(|gasprice| gasprice as u64),
+            )),
+        };
+
+        while let Some(pool_tx) = ready.take().or_else(|| best_txs.next()) {
+            // stop once any of the configured limits is reached
+            if let Some(limit) =
+                limits.reached(pool_transactions, pool_gas_used, selection_started.elapsed())
+            {
+                trace!(target: "rpc::eth::pending", ?limit, "Pending block limit reached");
+                // this and the transactions the pool still yields are excluded by the limit, only a
+                // bounded number of them is inspected to not prolong the build on a full pool
+                let remaining_gas = gas_target
+                    .saturating_sub(pool_gas_used)
+                    .min(block_gas_limit.saturating_sub(cumulative_gas_used));
+                excluded.extend(
+                    std::iter::once(pool_tx)
+                        .chain(best_txs.by_ref())
+                        .take(MAX_PENDING_EXCLUSIONS)
+                        .filter(|tx| tx.gas_limit() <= remaining_gas)
+                        .map(|tx| {
+                            let exclusion = PendingExclusion {
+                                hash: *tx.hash(),
+                                reason: PendingExclusionReason::Limit(limit),
+                            };
+                            (exclusion, tx.gas_limit())
+                        }),
+                );
+                break
+            }
+
+            // ensure we still have capacity for this transaction, a transaction that exactly fills
+            // the remaining gas still fits. The gas of the bundle is covered by the deposit gas
+            // reserve of the gas target, so only the block gas limit applies to it
+            if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
+                best_txs.mark_invalid(
+                    &pool_tx,
+                    InvalidPoolTransactionError::ExceedsGasLimit(
+                        pool_tx.gas_limit(),
+                        block_gas_limit,
+                    ),
+                );
+                continue
+            }
+            if pool_gas_used + pool_tx.gas_limit() > gas_target {
+                // we can't fit this transaction into the block, so we need to mark it as invalid
+                // which also removes all dependent transaction from the iterator before we can
+                // continue
+                best_txs.mark_invalid(
+                    &pool_tx,
+                    InvalidPoolTransactionError::ExceedsGasLimit(pool_tx.gas_limit(), gas_target),
+                );
+                continue
+            }
+
+            if pool_tx.origin.is_private() {
+                // we don't want to leak any state changes made by private transactions, so we mark
+                // them as invalid here which removes all dependent transactions from the iterator
+                // before we can continue
+                best_txs.mark_invalid(
+                    &pool_tx,
+                    InvalidPoolTransactionError::Consensus(
+                        InvalidTransactionError::TxTypeNotSupported,
+                    ),
+                );
+                continue
+            }
+
+            // the pool shouldn't yield transactions with a priority fee above their max fee, but
+            // if it does we must not include them, their effective priority fee would be negative
+            if pool_tx.max_priority_fee_per_gas().is_some_and(|tip| tip > pool_tx.max_fee_per_gas())
+            {
+                best_txs.mark_invalid(
+                    &pool_tx,
+                    InvalidPoolTransactionError::Consensus(InvalidTransactionError::TipAboveFeeCap),
+                );
+                continue
+            }
+
+            // the transactions may have been ordered against a lower base fee than the one of the
+            // pending block, e.g. by a snapshot taken before the base fee rose, so transactions
+            // that can't pay it anymore are skipped
+            if pool_tx.max_fee_per_gas() < selection_base_fee as u128 {
+                best_txs.mark_invalid(&pool_tx, InvalidPoolTransactionError::Underpriced);
+                continue
+            }
+
+            // skip transactions of senders that are not allowlisted, this also removes their
+            // dependent transactions from the iterator
+            if !config.is_sender_allowed(&pool_tx.sender()) {
+                best_txs.mark_invalid(
+                    &pool_tx,
+                    InvalidPoolTransactionError::Consensus(
+                        InvalidTransactionError::TxTypeNotSupported,
+                    ),
+                );
+                continue
+            }
+
+            // skip transactions that have been queued in the pool for too long, they are likely
+            // stuck, this also removes their dependent transactions from the iterator
+            if config.exceeds_max_pending_tx_age(pool_tx.timestamp, tx_age_now) {
+                best_txs.mark_invalid(
+                    &pool_tx,
+                    InvalidPoolTransactionError::Consensus(
+                        InvalidTransactionError::TxTypeNotSupported,
+                    ),
+                );
+                continue
+            }
+
+            // skip transactions of new senders once the limit of distinct senders is reached,
+            // this also removes their dependent transactions from the iterator
+            if config.exceeds_max_unique_senders(&included_senders, &pool_tx.sender()) {
+                best_txs.mark_invalid(
+                    &pool_tx,
+                    InvalidPoolTransactionError::Consensus(
+                        InvalidTransactionError::TxTypeNotSupported,
+                    ),
+                );
+                continue
+            }
+
+            // skip no-op self transfers if configured, this also removes their dependent
+            // transactions from the iterator
+            if config.drops_noop_transfer(pool_tx.sender(), &pool_tx.transaction) {
+                best_txs.mark_invalid(
+                    &pool_tx,
+                    InvalidPoolTransactionError::Consensus(
+                        InvalidTransactionError::TxTypeNotSupported,
+                    ),
+                );
+                continue
+            }
+
+            // skip transactions that exceed the configured size limit, this also removes their
+            // dependent transactions from the iterator
+            let tx_size = pool_tx.encoded_length();
+            if config.exceeds_max_tx_size(tx_size) {
+                best_txs.mark_invalid(
+                    &pool_tx,
+                    InvalidPoolTransactionError::OversizedData(
+                        tx_size,
+                        config.max_tx_size_bytes.unwrap_or_default(),
+                    ),
+                );
+                continue
+            }
+
+            // skip transactions whose calldata doesn't fit into the remaining calldata budget, this
+            // also removes their dependent transactions from the iterator
+            let tx_calldata = pool_tx.transaction.input().len();
+            if config.exceeds_max_calldata(sum_calldata, tx_calldata) {
+                excluded.push((
+                    PendingExclusion {
+                        hash: *pool_tx.hash(),
+                        reason: PendingExclusionReason::Calldata,
+                    },
+                    pool_tx.gas_limit(),
+                ));
+                best_txs.mark_invalid(
+                    &pool_tx,
+                    InvalidPoolTransactionError::OversizedData(
+                        sum_calldata + tx_calldata,
+                        config.max_calldata_bytes.unwrap_or_default(),
+                    ),
+                );
+                continue
+            }
+
+            // skip transactions whose gas limit doesn't cover their intrinsic gas, the EVM would
+            // reject them, this also removes their dependent transactions from the iterator
+            if pool_tx.gas_limit() <
+                intrinsic_gas(
+                    &*chain_spec,
+                    block_env.number,
+                    block_env.timestamp,
+                    &pool_tx.transaction,
+                )
+            {
+                best_txs.mark_invalid(&pool_tx, InvalidPoolTransactionError::IntrinsicGasTooLow);
+                continue
+            }
+
+            // the pool should never yield the same transaction twice, but if it does we must not
+            // include it again, otherwise the block would be invalid
+            if selection.is_included(pool_tx.hash()) {
+                warn!(target: "rpc", hash=%pool_tx.hash(), "Skipping duplicate transaction in pending block");
+                continue
+            }
+
+            // transactions of a sender are executed in increasing nonce order without gaps, a
+            // nonce below the sender's current nonce was already used and a nonce above it is
+            // deferred until the transactions in between are included
+            let sender_nonce = strategy
+                .evm_mut()
+                .db_mut()
+                .basic(pool_tx.sender())
+                .map_err(parent_state_error)?
+                .map_or(0, |account| account.nonce);
+            match pool_tx.nonce().cmp(&sender_nonce) {
+                Ordering::Less => continue,
+                Ordering::Greater => {
+                    deferred.defer(pool_tx);
+                    continue
+                }
+                Ordering::Equal => {}
+            }
+
+            // convert tx to a signed transaction. The sender was already recovered when the
+            // transaction was validated by the pool, so the converted transaction is not
+            // recovered again
+            let tx = match self.try_to_consensus(&pool_tx.transaction) {
+                Ok(tx) => tx,
+                Err(err) if config.conversion_failure == PendingConversionFailure::Fail => {
+                    return Err(err)
+                }
+                Err(err) => {
+                    warn!(target: "rpc", hash=%pool_tx.hash(), %err, "Skipping pending transaction that failed to convert");
+                    best_txs.mark_invalid(
+                        &pool_tx,
+                        InvalidPoolTransactionError::Consensus(
+                            InvalidTransactionError::TxTypeNotSupported,
+                        ),
+                    );
+                    continue
+                }
+            };
+
+            // skip transactions rejected by the network specific checks, this also removes their
+            // dependent transactions from the iterator
+            if !self.is_pending_transaction_allowed(&block_env, &tx) {
+                best_txs.mark_invalid(
+                    &pool_tx,
+                    InvalidPoolTransactionError::Consensus(
+                        InvalidTransactionError::TxTypeNotSupported,
+                    ),
+                );
+                continue
+            }
+
+            // skip transactions that aren't valid at the timestamp of the pending block, this
+            // also removes their dependent transactions from the iterator
+            if !self.validity_check(&tx, block_env.timestamp) {
+                best_txs.mark_invalid(
+                    &pool_tx,
+                    InvalidPoolTransactionError::Consensus(
+                        InvalidTransactionError::TxTypeNotSupported,
+                    ),
+                );
+                continue
+            }
+
+            // There's only limited amount of blob space available per block, so we need to check if
+            // the EIP-4844 can still fit in the block
+            if let Some(tx_blob_gas) = tx.blob_gas_used() {
+                if sum_blob_gas_used + tx_blob_gas > MAX_DATA_GAS_PER_BLOCK {
+                    // we can't fit this _blob_ transaction into the block, so we mark it as
+                    // invalid, which removes its dependent transactions from
+                    // the iterator. This is similar to the gas limit condition
+                    // for regular transactions above.
+                    best_txs.mark_invalid(
+                        &pool_tx,
+                        InvalidPoolTransactionError::ExceedsGasLimit(
+                            tx_blob_gas,
+                            MAX_DATA_GAS_PER_BLOCK,
+                        ),
+                    );
+                    continue
+                }
+            }
+
+            // the configured blob limit can be stricter than the blob gas limit
+            let tx_blob_count = tx.blob_versioned_hashes().map_or(0, |hashes| hashes.len() as u64);
+            if tx_blob_count > 0 && config.exceeds_max_blobs(sum_blob_count, tx_blob_count) {
+                best_txs.mark_invalid(
+                    &pool_tx,
+                    InvalidPoolTransactionError::Eip4844(
+                        Eip4844PoolTransactionError::TooManyEip4844Blobs {
+                            have: sum_blob_count + tx_blob_count,
+                            permitted: config.max_blobs_per_block.unwrap_or_default(),
+                        },
+                    ),
+                );
+                continue
+            }
+
+            // reverted transactions are included as well, their receipts have a failure status
+            let execution_started = Instant::now();
+            let execution_result =
+                trace_span!(target: "rpc::eth::pending", "execute_transaction", hash = %pool_tx.hash())
+                    .in_scope(|| strategy.execute_transaction(tx.as_recovered_ref()));
+            execution += execution_started.elapsed();
+
+            let gas_used = match execution_result {
+                Ok(gas_used) => gas_used,
+                Err(BlockExecutionError::Validation(BlockValidationError::InvalidTx {
+                    error,
+                    ..
+                })) => {
+                    if error.is_nonce_too_low() {
+                        // if the nonce is too low, we can skip this transaction
+                    } else {
+                        // if the transaction is invalid, e.g. because the sender can't afford
+                        // its gas together with network specific fees like the L1 data fee on
+                        // OP, we can skip it and all of its descendants
+                        best_txs.mark_invalid(
+                            &pool_tx,
+                            InvalidPoolTransactionError::Consensus(
+                                InvalidTransactionError::TxTypeNotSupported,
+                            ),
+                        );
+                    }
+                    continue
+                }
+                // any other validation error only affects this transaction, its state changes were
+                // not committed, so we skip it and all of its descendants and continue with the
+                // rest
+                Err(BlockExecutionError::Validation(err)) => {
+                    warn!(target: "rpc", hash=%pool_tx.hash(), %err, "Skipping transaction that failed to execute in pending block");
+                    best_txs.mark_invalid(
+                        &pool_tx,
+                        InvalidPoolTransactionError::Consensus(
+                            InvalidTransactionError::TxTypeNotSupported,
+                        ),
+                    );
+                    continue
+                }
+                // this is an error that we should treat as fatal for this attempt, e.g. a database
+                // error, the rest of the block can't be trusted either
+                Err(err) => return Err(Self::Error::from_eth_err(err)),
+            };
+
+            // add to the total blob gas used if the transaction successfully executed
+            if let Some(tx_blob_gas) = tx.blob_gas_used() {
+                sum_blob_gas_used += tx_blob_gas;
+
+                sum_blob_count += tx_blob_count;
+
+                // if we've reached the max data gas per block or the configured blob limit, we can
+                // skip blob txs entirely
+                if sum_blob_gas_used == MAX_DATA_GAS_PER_BLOCK ||
+                    config.is_blob_limit_reached(sum_blob_count)
+                {
+                    best_txs.skip_blobs();
+                }
+            }
+
+            // add gas used by the transaction to cumulative gas used, before creating the receipt
+            cumulative_gas_used += gas_used;
+            pool_transactions += 1;
+            pool_gas_used += gas_used;
+            sum_calldata += tx_calldata;
+
+            // notify the listener about the included transaction
+            if let Some(listener) = listener {
+                let _ = listener.send(tx.clone());
+            }
+
+            // append transaction to the list of executed transactions
+            selection.insert(*pool_tx.hash());
+            included_senders.insert(pool_tx.sender());
+            executed_txs.push(tx);
+
+            // the next transaction of the sender can be executed now, if it was deferred
+            ready = deferred.take(&pool_tx.sender(), pool_tx.nonce() + 1);
+        }
+
+        if !deferred.is_empty() {
+            debug!(target: "rpc", count = deferred.len(), "Skipped transactions with a nonce gap in pending block");
+        }
+
+        // only the excluded transactions that fit into the gas left at the end are reported
+        let remaining_gas = gas_target
+            .saturating_sub(pool_gas_used)
+            .min(block_gas_limit.saturating_sub(cumulative_gas_used));
+        let excluded = excluded
+            .into_iter()
+            .filter(|(exclusion, gas_limit)| {
+                *gas_limit <= remaining_gas && !selection.is_included(&exclusion.hash)
+            })
+            .map(|(exclusion, _)| exclusion)
+            .take(MAX_PENDING_EXCLUSIONS)
+            .collect();
+
+        drop(selection_span);
+        timings.selection += selection_started.elapsed().saturating_sub(execution);
+
+        let post_execution_started = Instant::now();
+        let result = strategy.apply_post_execution_changes().map_err(Self::Error::from_eth_err)?;
+        timings.execution += execution + post_execution_started.elapsed();
 
-        Ok((RecoveredBlock::new_unhashed(block, senders), result.receipts))
+        Ok((result, executed_txs, block_env, excluded))
     }
 }
@@ -17,6 +17,7 @@ use reth_provider::{
 };
 use reth_rpc_eth_types::{EthApiError, PendingBlockEnv, RpcInvalidTransactionError};
 use reth_transaction_pool::TransactionPool;
+use reth_trie_common::TrieInput;
 
 /// Helper methods for `eth_` methods relating to state (accounts).
 pub trait EthState: LoadState + SpawnBlocking {
@@ -40,7 +41,10 @@ pub trait EthState: LoadState + SpawnBlocking {
         &self,
         address: Address,
         block_id: Option<BlockId>,
-    ) -> impl Future<Output = Result<Bytes, Self::Error>> + Send {
+    ) -> impl Future<Output = Result<Bytes, Self::Error>> + Send
+    where
+        Self: LoadPendingBlock,
+    {
         LoadState::get_code(self, address, block_id)
     }
 
@@ -88,7 +92,7 @@ pub trait EthState: LoadState + SpawnBlocking {
         Self::Error,
     >
     where
-        Self: EthApiSpec,
+        Self: EthApiSpec + LoadPendingBlock,
     {
         Ok(async move {
             let _permit = self
@@ -100,6 +104,29 @@ pub trait EthState: LoadState + SpawnBlocking {
             let chain_info = self.chain_info().map_err(Self::Error::from_eth_err)?;
             let block_id = block_id.unwrap_or_default();
 
+            // without a pending block from the CL, prove against the state of the local pending
+            // block, on top of the state of its parent
+            if block_id.is_pending() &&
+                self.provider()
+                    .pending_block_num_hash()
+                    .map_err(Self::Error::from_eth_err)?
+                    .is_none()
+            {
+                if let Some((parent_hash, hashed_state)) = self.local_pending_state().await? {
+                    return self
+                        .spawn_blocking_io(move |this| {
+                            let state = this.state_at_hash(parent_hash)?;
+                            let storage_keys =
+                                keys.iter().map(|key| key.as_b256()).collect::<Vec<_>>();
+                            let proof = state
+                                .proof(TrieInput::from_state(hashed_state), address, &storage_keys)
+                                .map_err(Self::Error::from_eth_err)?;
+                            Ok(proof.into_eip1186_response(keys))
+                        })
+                        .await
+                }
+            }
+
             // Check whether the distance to the block exceeds the maximum configured window.
             let block_number = self
                 .provider()
@@ -321,21 +348,77 @@ pub trait LoadState:
     }
 
     /// Returns code of given account, at the given identifier.
+    ///
+    /// If this is [`BlockNumberOrTag::Pending`](alloy_eips::BlockNumberOrTag) and there's no
+    /// pending block from the CL, this returns the code as of the local pending block, so that
+    /// contracts deployed or destroyed by pending transactions are reflected.
     fn get_code(
         &self,
         address: Address,
         block_id: Option<BlockId>,
     ) -> impl Future<Output = Result<Bytes, Self::Error>> + Send
     where
-        Self: SpawnBlocking,
+        Self: LoadPendingBlock + SpawnBlocking,
     {
-        self.spawn_blocking_io(move |this| {
-            Ok(this
-                .state_at_block_id_or_latest(block_id)?
-                .account_code(&address)
-                .map_err(Self::Error::from_eth_err)?
-                .unwrap_or_default()
-                .original_bytes())
-        })
+        async move {
+            if block_id.is_some_and(|block_id| block_id.is_pending()) &&
+                self.provider()
+                    .pending_block_num_hash()
+                    .map_err(Self::Error::from_eth_err)?
+                    .is_none()
+            {
+                if let Some((parent_hash, bundle_state)) = self.local_pending_bundle_state().await?
+                {
+                    let Some(account) = bundle_state.account(&address) else {
+                        // not touched by the pending block, so the code is the one of the parent
+                        return self
+                            .spawn_blocking_io(move |this| {
+                                Ok(this
+                                    .state_at_hash(parent_hash)?
+                                    .account_code(&address)
+                                    .map_err(Self::Error::from_eth_err)?
+                                    .unwrap_or_default()
+                                    .original_bytes())
+                            })
+                            .await
+                    };
+
+                    // the account was destroyed by the pending block
+                    let Some(info) = account.info.as_ref() else { return Ok(Bytes::new()) };
+                    if info.code_hash == KECCAK_EMPTY {
+                        return Ok(Bytes::new())
+                    }
+
+                    // code deployed by the pending block is part of the bundle
+                    if let Some(code) =
+                        info.code.clone().or_else(|| bundle_state.bytecode(&info.code_hash))
+                    {
+                        return Ok(code.original_bytes())
+                    }
+
+                    let code_hash = info.code_hash;
+                    return self
+                        .spawn_blocking_io(move |this| {
+                            Ok(this
+                                .state_at_hash(parent_hash)?
+                                .bytecode_by_hash(&code_hash)
+                                .map_err(Self::Error::from_eth_err)?
+                                .unwrap_or_default()
+                                .original_bytes())
+                        })
+                        .await
+                }
+            }
+
+            self.spawn_blocking_io(move |this| {
+                Ok(this
+                    .state_at_block_id_or_latest(block_id)?
+                    .account_code(&address)
+                    .map_err(Self::Error::from_eth_err)?
+                    .unwrap_or_default()
+                    .original_bytes())
+            })
+            .await
+        }
     }
 }
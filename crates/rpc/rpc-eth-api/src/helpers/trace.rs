@@ -312,6 +312,14 @@ pub trait Trace:
                 return Ok(Some(Vec::new()))
             }
 
+            // the locally built pending block must be replayed with the env it was built with,
+            // which can differ from a freshly derived pending env, e.g. the randomness
+            let evm_env = if block_id.is_pending() {
+                self.evm_config().evm_env(block.header())
+            } else {
+                evm_env
+            };
+
             // replay all transactions of the block
             self.spawn_tracing(move |this| {
                 // we need to get the state of the parent block because we're replaying this block
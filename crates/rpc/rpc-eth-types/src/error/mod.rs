@@ -3,7 +3,7 @@
 pub mod api;
 use crate::error::api::FromEvmHalt;
 use alloy_eips::BlockId;
-use alloy_primitives::{Address, Bytes, U256};
+use alloy_primitives::{Address, Bytes, B256, U256};
 use alloy_rpc_types_eth::{error::EthRpcErrorCode, request::TransactionInputError, BlockError};
 use alloy_sol_types::{ContractError, RevertReason};
 pub use api::{AsEthApiError, FromEthApiError, FromEvmError, IntoEthApiError};
@@ -77,6 +77,44 @@ pub enum EthApiError {
     /// `excess_blob_gas` is not set for Cancun and above
     #[error("excess blob gas missing in the EVM's environment after Cancun")]
     ExcessBlobGasNotSet,
+    /// The chain spec reports a fork as active at a timestamp at which a preceding fork is not
+    /// active yet
+    #[error("conflicting fork activation at timestamp {timestamp}: {active} is active but {inactive} is not")]
+    ConflictingForkActivation {
+        /// Timestamp the forks were checked at.
+        timestamp: u64,
+        /// Name of the fork that is active.
+        active: &'static str,
+        /// Name of the preceding fork that is inactive.
+        inactive: &'static str,
+    },
+    /// Thrown when the configured gas limit of the pending block is outside of the bounds
+    /// consensus allows relative to the gas limit of its parent
+    #[error(
+        "pending gas limit {gas_limit} is out of bounds for parent gas limit {parent_gas_limit}"
+    )]
+    PendingGasLimitOutOfBounds {
+        /// Gas limit of the parent block.
+        parent_gas_limit: u64,
+        /// Configured gas limit of the pending block.
+        gas_limit: u64,
+    },
+    /// Thrown when building the pending block twice from the same pool snapshot yielded different
+    /// blocks, see `PendingBlockConfig::verify_determinism`
+    #[error("pending block builds diverged: {first} != {second}")]
+    PendingBuildNondeterministic {
+        /// Hash of the block of the first build.
+        first: B256,
+        /// Hash of the block of the second build.
+        second: B256,
+    },
+    /// Thrown when the state of the block the pending block is built on is unavailable, because
+    /// it was pruned
+    #[error("state of pending block parent #{0} is unavailable, it has been pruned")]
+    PendingParentStatePruned(u64),
+    /// Thrown when building the pending block panicked, e.g. in a custom hook
+    #[error("building the pending block panicked: {0}")]
+    PendingBuildPanicked(String),
     /// Thrown when a call or transaction request (`eth_call`, `eth_estimateGas`,
     /// `eth_sendTransaction`) contains conflicting fields (legacy, EIP-1559)
     #[error("both gasPrice and (maxFeePerGas or maxPriorityFeePerGas) specified")]
@@ -188,13 +226,17 @@ impl From<EthApiError> for jsonrpsee_types::error::ErrorObject<'static> {
             EthApiError::PoolError(err) => err.into(),
             EthApiError::PrevrandaoNotSet |
             EthApiError::ExcessBlobGasNotSet |
+            EthApiError::ConflictingForkActivation { .. } |
+            EthApiError::PendingGasLimitOutOfBounds { .. } |
+            EthApiError::PendingBuildPanicked(_) |
+            EthApiError::PendingBuildNondeterministic { .. } |
             EthApiError::InvalidBlockData(_) |
             EthApiError::Internal(_) |
             EthApiError::TransactionNotFound |
             EthApiError::EvmCustom(_) |
             EthApiError::EvmPrecompile(_) |
             EthApiError::InvalidRewardPercentiles => internal_rpc_err(error.to_string()),
-            EthApiError::UnknownBlockOrTxIndex => {
+            EthApiError::UnknownBlockOrTxIndex | EthApiError::PendingParentStatePruned(_) => {
                 rpc_error_with_code(EthRpcErrorCode::ResourceNotFound.code(), error.to_string())
             }
             // TODO(onbjerg): We rewrite the error message here because op-node does string matching
@@ -645,19 +687,27 @@ impl RevertError {
     pub const fn error_code(&self) -> i32 {
         EthRpcErrorCode::ExecutionError.code()
     }
+
+    /// Returns the revert reason decoded from the output, e.g. the message of a standard
+    /// `Error(string)` revert.
+    ///
+    /// Returns `None` if the output is empty or can't be decoded.
+    pub fn reason(&self) -> Option<String> {
+        let reason = RevertReason::decode(self.output.as_ref()?)?;
+        let error = reason.to_string();
+        if matches!(reason, RevertReason::ContractError(ContractError::Revert(_))) {
+            // we strip redundant `revert: ` prefix from the revert reason
+            return Some(error.trim_start_matches("revert: ").to_string())
+        }
+        Some(error)
+    }
 }
 
 impl std::fmt::Display for RevertError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("execution reverted")?;
-        if let Some(reason) = self.output.as_ref().and_then(|out| RevertReason::decode(out)) {
-            let error = reason.to_string();
-            let mut error = error.as_str();
-            if matches!(reason, RevertReason::ContractError(ContractError::Revert(_))) {
-                // we strip redundant `revert: ` prefix from the revert reason
-                error = error.trim_start_matches("revert: ");
-            }
-            write!(f, ": {error}")?;
+        if let Some(reason) = self.reason() {
+            write!(f, ": {reason}")?;
         }
         Ok(())
     }
@@ -33,6 +33,17 @@ pub use gas_oracle::{
     GasCap, GasPriceOracle, GasPriceOracleConfig, GasPriceOracleResult, RPC_DEFAULT_GAS_CAP,
 };
 pub use id_provider::EthSubscriptionIdProvider;
-pub use pending_block::{PendingBlock, PendingBlockEnv, PendingBlockEnvOrigin};
+pub use pending_block::{
+    cumulative_blob_gas, ensure_fork_ordering, ensure_parent_state_available, intrinsic_gas,
+    panic_message, pending_parent_state_error, priority_fee_revenue, AccountDiff,
+    DeferredTransactions, InvalidExtraDataStamp, InvalidTargetGasFraction, PendingAccuracy,
+    PendingBlock, PendingBlockConfig, PendingBlockDiff, PendingBlockEnv, PendingBlockEnvOrigin,
+    PendingBlockPolicy, PendingBlockSelection, PendingBlockStatus, PendingBlockSummary,
+    PendingBuildLimit, PendingBuildLimits, PendingBuildTimings, PendingBundleTransactionResult,
+    PendingClock, PendingConversionFailure, PendingExclusion, PendingExclusionReason,
+    PendingPoolSnapshot, PendingStateDiff, PendingTransactionFee, PendingTransactionOrdering,
+    PendingTransactionProof, PendingValidityCheck, SnapshotBestTransactions,
+    MAX_PENDING_EXCLUSIONS,
+};
 pub use receipt::EthReceiptBuilder;
 pub use transaction::TransactionSource;
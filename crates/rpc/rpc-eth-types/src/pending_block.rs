@@ -2,15 +2,47 @@
 //!
 //! Types used in block building.
 
-use std::time::Instant;
+use std::{
+    any::Any,
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    fmt,
+    num::NonZeroU128,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use alloy_consensus::BlockHeader;
-use alloy_eips::{BlockId, BlockNumberOrTag};
-use alloy_primitives::B256;
+use crate::EthApiError;
+
+use alloy_consensus::{constants::MAXIMUM_EXTRA_DATA_SIZE, BlockHeader, Transaction, TxReceipt};
+use alloy_eips::{eip2718::Encodable2718, BlockId, BlockNumberOrTag};
+use alloy_primitives::{keccak256, Address, Bytes, TxHash, B256, U256};
+use alloy_trie::{
+    proof::{verify_proof, ProofRetainer, ProofVerificationError},
+    root::adjust_index_for_rlp,
+    HashBuilder, Nibbles,
+};
 use derive_more::Constructor;
+use futures::{Stream, StreamExt};
+use reth_chain_state::CanonStateNotification;
+use reth_chainspec::{EthereumHardfork, EthereumHardforks};
+use reth_errors::ProviderError;
 use reth_evm::EvmEnv;
-use reth_primitives::{Receipt, RecoveredBlock, SealedHeader};
-use reth_primitives_traits::Block;
+use reth_metrics::{metrics::Gauge, Metrics};
+use reth_primitives::{NodePrimitives, Receipt, RecoveredBlock, SealedHeader};
+use reth_primitives_traits::{
+    constants::{GAS_LIMIT_BOUND_DIVISOR, MINIMUM_GAS_LIMIT},
+    Block, BlockBody,
+};
+use reth_prune_types::PruneSegment;
+use reth_storage_api::{BlockNumReader, PruneCheckpointReader};
+use reth_transaction_pool::{
+    error::InvalidPoolTransactionError, BestTransactions, PoolTransaction, ValidPoolTransaction,
+};
+use revm::{interpreter::gas::calculate_initial_tx_gas, specification::hardfork::SpecId};
+use revm_database::{BundleAccount, BundleState};
+use tokio::sync::Mutex;
+use tracing::trace;
 
 /// Configured [`EvmEnv`] for a pending block.
 #[derive(Debug, Clone, Constructor)]
@@ -74,12 +106,2012 @@ impl<B: Block, R> PendingBlockEnvOrigin<B, R> {
 }
 
 /// Locally built pending block for `pending` tag.
-#[derive(Debug, Constructor)]
+#[derive(Debug)]
 pub struct PendingBlock<B: Block, R> {
+    /// Timestamp when the pending block was built.
+    pub built_at: Instant,
     /// Timestamp when the pending block is considered outdated.
     pub expires_at: Instant,
     /// The locally built pending block.
     pub block: RecoveredBlock<B>,
     /// The receipts for the pending block
     pub receipts: Vec<R>,
+    /// The changes the pending block makes to the state of its parent.
+    pub bundle_state: BundleState,
+    /// Pool transactions the pending block excludes, although they would have fit.
+    pub excluded: Vec<PendingExclusion>,
+    /// Digest of the composition of the pending block, see [`PendingBlock::digest_of`].
+    pub digest: B256,
+}
+
+impl<B: Block, R> PendingBlock<B, R> {
+    /// Creates a new [`PendingBlock`] and computes its digest.
+    pub fn new(
+        built_at: Instant,
+        expires_at: Instant,
+        block: RecoveredBlock<B>,
+        receipts: Vec<R>,
+        bundle_state: BundleState,
+        excluded: Vec<PendingExclusion>,
+    ) -> Self {
+        let digest = Self::digest_of(&block);
+        Self { built_at, expires_at, block, receipts, bundle_state, excluded, digest }
+    }
+
+    /// Returns a digest of the parent, number, beneficiary, gas used and transactions of the given
+    /// block.
+    ///
+    /// Unlike the block hash, the digest doesn't depend on fields that change with every build,
+    /// like the timestamp, so it only changes if the composition of the block changes.
+    pub fn digest_of(block: &RecoveredBlock<B>) -> B256 {
+        let header = block.header();
+        let mut buf = Vec::with_capacity(32 + 8 + 20 + 8 + 32 * block.body().transaction_count());
+        buf.extend_from_slice(header.parent_hash().as_slice());
+        buf.extend_from_slice(&header.number().to_be_bytes());
+        buf.extend_from_slice(header.beneficiary().as_slice());
+        buf.extend_from_slice(&header.gas_used().to_be_bytes());
+        for hash in block.body().transaction_hashes_iter() {
+            buf.extend_from_slice(hash.as_slice());
+        }
+        keccak256(buf)
+    }
+
+    /// Returns the [`PendingBlockStatus`] of the pending block, given the hash of the current
+    /// `latest` block.
+    pub fn status(&self, latest_hash: B256, now: Instant) -> PendingBlockStatus {
+        let parent_hash = self.block.header().parent_hash();
+        PendingBlockStatus {
+            built_at: self.built_at,
+            parent_hash,
+            is_stale: parent_hash != latest_hash || now > self.expires_at,
+        }
+    }
+}
+
+/// Status of a cached [`PendingBlock`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingBlockStatus {
+    /// Timestamp when the pending block was built.
+    pub built_at: Instant,
+    /// Hash of the block the pending block was built on.
+    pub parent_hash: B256,
+    /// Whether the pending block is outdated, because either the `latest` block changed or the
+    /// pending block expired.
+    pub is_stale: bool,
+}
+
+/// Configuration for building the local pending block.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PendingBlockConfig {
+    /// Transactions with an encoded size above this limit are not included in the pending block.
+    pub max_tx_size_bytes: Option<usize>,
+    /// Maximum total calldata size of the transactions in the pending block, e.g. to model a
+    /// sequencer constrained by data availability.
+    ///
+    /// Transactions whose calldata doesn't fit into the remaining budget are not included.
+    pub max_calldata_bytes: Option<usize>,
+    /// Maximum number of blobs included in the pending block, independent of the blob gas limit.
+    pub max_blobs_per_block: Option<u64>,
+    /// Gas reserved for deposit transactions prepended to the pending block, which pool
+    /// transactions can't consume.
+    ///
+    /// The gas the deposits actually use is covered by the reserve, so it doesn't count against
+    /// the gas target of the pool transactions again.
+    pub deposit_gas_reserve: Option<u64>,
+    /// L1 base fee the L1 fees of the transactions in the pending block are computed from,
+    /// instead of the last observed one, e.g. to model rising L1 fees.
+    ///
+    /// Only used by OP.
+    pub speculative_l1_base_fee: Option<u128>,
+    /// Whether transactions of the previous pending block that are still in the pool are
+    /// considered first when the pending block is rebuilt, regardless of the pool's current
+    /// ordering.
+    ///
+    /// This keeps the pending block stable for clients that poll it.
+    pub sticky_pending: bool,
+    /// Gas limit of the pending block, instead of the gas limit of its parent.
+    ///
+    /// See [`PendingBlockConfig::next_gas_limit`] for the allowed values.
+    pub gas_limit: Option<u64>,
+    /// Seconds between the latest block and the pending block, instead of the block time of the
+    /// network.
+    ///
+    /// This determines the timestamp of the pending block and with it the hardforks active in
+    /// the pending env.
+    pub block_time: Option<u64>,
+    /// If set, only transactions of these senders are included in the pending block.
+    pub sender_allowlist: Option<HashSet<Address>>,
+    /// Maximum number of distinct senders whose transactions are included in the pending block,
+    /// modeling the account diversity limit some sequencers apply.
+    ///
+    /// Once the limit is reached, further transactions of the included senders still qualify.
+    pub max_unique_senders: Option<usize>,
+    /// Maximum time a transaction may have been queued in the pool to be included in the pending
+    /// block, older transactions are likely stuck and skipped.
+    pub max_pending_tx_age: Option<Duration>,
+    /// Clock the age of pool transactions is measured with, instead of the system clock.
+    pub pending_tx_clock: Option<PendingClock>,
+    /// Whether the pending block is rebuilt in the background whenever a transaction enters the
+    /// pending pool, so RPC requests hit a fresh cached block.
+    ///
+    /// This is disabled by default, so nodes that don't serve pending block requests don't pay
+    /// for builds nobody requests.
+    pub rebuild_on_pool_events: bool,
+    /// EIP-1559 base fee max change denominator used to compute the base fee of the pending
+    /// block, instead of the one of the chain spec.
+    pub base_fee_max_change_denominator: Option<NonZeroU128>,
+    /// Base fee of a pending block with London active whose parent has no base fee, e.g. the
+    /// London activation block.
+    ///
+    /// If not set, the base fee of the genesis block is used, or the EIP-1559 initial base fee if
+    /// the genesis block doesn't have one either.
+    pub fallback_base_fee: Option<u64>,
+    /// Whether zero value transfers of a sender to itself without calldata are excluded from the
+    /// pending block, since they only spam it.
+    pub drop_noop_transfers: bool,
+    /// Whether the pending block is built once more if the pool received a replacement for one
+    /// of its transactions during the build, so that the replacement is included instead.
+    ///
+    /// Builds from a snapshot of the pool or with a listener are never repeated.
+    pub include_replacements: bool,
+    /// Maximum amount of gas pool transactions in the pending block may use, e.g. to simulate a
+    /// soft target.
+    ///
+    /// Unlike [`PendingBlockConfig::gas_limit`], this doesn't change the gas limit of the header.
+    pub pending_gas_cap: Option<u64>,
+    /// Maximum number of pool transactions included in the pending block.
+    pub max_transactions: Option<usize>,
+    /// Maximum time spent selecting and executing the transactions of the pending block, after
+    /// which no more transactions are included.
+    pub build_time_budget: Option<Duration>,
+    /// Maximum time to wait for the transactions of the pool, e.g. while the pool is locked for
+    /// a large update, after which the pending block is built without any pool transactions.
+    ///
+    /// If set, the pending block is always built from a snapshot of the pool. If not set, the
+    /// build waits for the pool.
+    pub pool_timeout: Option<Duration>,
+    /// Checks transactions with time-based validity against the timestamp of the pending block.
+    ///
+    /// If not set, all transactions are considered valid.
+    pub validity_check: Option<PendingValidityCheck>,
+    /// What happens to a pool transaction that can't be converted into the transaction type of
+    /// the pending block.
+    pub conversion_failure: PendingConversionFailure,
+    /// Whether a pending block is built locally even if there are neither deposits nor pool
+    /// transactions to include, for chains that produce blocks on a fixed cadence.
+    ///
+    /// The pending block is then an empty block on top of the latest block at the next
+    /// timestamp. If disabled, chains that don't build pending blocks locally, like OP, return
+    /// the latest block as pending instead. Chains that always build pending blocks locally
+    /// already produce empty blocks regardless.
+    pub produce_empty_blocks: bool,
+    /// Whether every pending block is built a second time from the same pool snapshot to verify
+    /// that the build is deterministic, for debugging custom hooks.
+    ///
+    /// If the two builds differ, the build fails. This doubles the cost of each build.
+    pub verify_determinism: bool,
+    /// Whether the logs of the pending block are sorted by transaction and log index before they
+    /// are returned, so that indexers get them in canonical order regardless of how they were
+    /// collected.
+    pub sort_pending_logs: bool,
+    /// Fraction of the block gas limit after which no more transactions are included in the
+    /// pending block, e.g. to leave headroom for sequencer transactions.
+    ///
+    /// Always in `(0, 1]`.
+    target_gas_fraction: Option<f64>,
+    /// Client version the extra data of the pending header is stamped with, together with the
+    /// block timestamp, for debugging. Overrides the extra data the header would have otherwise.
+    ///
+    /// The stamp always fits into the extra data size limit. Not applied to OP pending blocks,
+    /// whose extra data carries the EIP-1559 parameters since Holocene.
+    extra_data_stamp: Option<String>,
+}
+
+impl PendingBlockConfig {
+    /// Sets the maximum encoded size of a transaction included in the pending block.
+    pub const fn with_max_tx_size_bytes(mut self, max_tx_size_bytes: Option<usize>) -> Self {
+        self.max_tx_size_bytes = max_tx_size_bytes;
+        self
+    }
+
+    /// Sets the maximum total calldata size of the transactions in the pending block.
+    pub const fn with_max_calldata_bytes(mut self, max_calldata_bytes: Option<usize>) -> Self {
+        self.max_calldata_bytes = max_calldata_bytes;
+        self
+    }
+
+    /// Sets the maximum number of blobs included in the pending block.
+    pub const fn with_max_blobs_per_block(mut self, max_blobs_per_block: Option<u64>) -> Self {
+        self.max_blobs_per_block = max_blobs_per_block;
+        self
+    }
+
+    /// Sets the gas reserved for deposit transactions in the pending block.
+    pub const fn with_deposit_gas_reserve(mut self, deposit_gas_reserve: Option<u64>) -> Self {
+        self.deposit_gas_reserve = deposit_gas_reserve;
+        self
+    }
+
+    /// Sets the speculative L1 base fee of the pending block.
+    pub const fn with_speculative_l1_base_fee(
+        mut self,
+        speculative_l1_base_fee: Option<u128>,
+    ) -> Self {
+        self.speculative_l1_base_fee = speculative_l1_base_fee;
+        self
+    }
+
+    /// Sets whether transactions of the previous pending block are carried forward.
+    pub const fn with_sticky_pending(mut self, sticky_pending: bool) -> Self {
+        self.sticky_pending = sticky_pending;
+        self
+    }
+
+    /// Sets the gas limit of the pending block.
+    pub const fn with_gas_limit(mut self, gas_limit: Option<u64>) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    /// Sets the block time the timestamp of the pending block is derived from.
+    pub const fn with_block_time(mut self, block_time: Option<u64>) -> Self {
+        self.block_time = block_time;
+        self
+    }
+
+    /// Sets the EIP-1559 base fee max change denominator the pending base fee is computed with.
+    pub const fn with_base_fee_max_change_denominator(
+        mut self,
+        base_fee_max_change_denominator: Option<NonZeroU128>,
+    ) -> Self {
+        self.base_fee_max_change_denominator = base_fee_max_change_denominator;
+        self
+    }
+
+    /// Sets the base fee of a pending block whose parent has no base fee.
+    pub const fn with_fallback_base_fee(mut self, fallback_base_fee: Option<u64>) -> Self {
+        self.fallback_base_fee = fallback_base_fee;
+        self
+    }
+
+    /// Sets whether no-op self transfers are excluded from the pending block.
+    pub const fn with_drop_noop_transfers(mut self, drop_noop_transfers: bool) -> Self {
+        self.drop_noop_transfers = drop_noop_transfers;
+        self
+    }
+
+    /// Sets whether the pending block is rebuilt if an included transaction was replaced in the
+    /// pool during the build.
+    pub const fn with_include_replacements(mut self, include_replacements: bool) -> Self {
+        self.include_replacements = include_replacements;
+        self
+    }
+
+    /// Sets the maximum amount of gas pool transactions in the pending block may use.
+    pub const fn with_pending_gas_cap(mut self, pending_gas_cap: Option<u64>) -> Self {
+        self.pending_gas_cap = pending_gas_cap;
+        self
+    }
+
+    /// Sets the maximum number of pool transactions included in the pending block.
+    pub const fn with_max_transactions(mut self, max_transactions: Option<usize>) -> Self {
+        self.max_transactions = max_transactions;
+        self
+    }
+
+    /// Sets the maximum time spent selecting and executing the transactions of the pending block.
+    pub const fn with_build_time_budget(mut self, build_time_budget: Option<Duration>) -> Self {
+        self.build_time_budget = build_time_budget;
+        self
+    }
+
+    /// Sets whether the pending block is rebuilt whenever a transaction enters the pending pool.
+    pub const fn with_rebuild_on_pool_events(mut self, rebuild_on_pool_events: bool) -> Self {
+        self.rebuild_on_pool_events = rebuild_on_pool_events;
+        self
+    }
+
+    /// Sets whether an empty pending block is built if there is nothing to include.
+    pub const fn with_produce_empty_blocks(mut self, produce_empty_blocks: bool) -> Self {
+        self.produce_empty_blocks = produce_empty_blocks;
+        self
+    }
+
+    /// Sets whether pending blocks are built twice to verify that the build is deterministic.
+    pub const fn with_verify_determinism(mut self, verify_determinism: bool) -> Self {
+        self.verify_determinism = verify_determinism;
+        self
+    }
+
+    /// Sets the maximum time to wait for the transactions of the pool.
+    pub const fn with_pool_timeout(mut self, pool_timeout: Option<Duration>) -> Self {
+        self.pool_timeout = pool_timeout;
+        self
+    }
+
+    /// Sets whether the logs of the pending block are returned sorted by transaction and log index.
+    pub const fn with_sort_pending_logs(mut self, sort_pending_logs: bool) -> Self {
+        self.sort_pending_logs = sort_pending_logs;
+        self
+    }
+
+    /// Sets the senders whose transactions are included in the pending block exclusively.
+    pub fn with_sender_allowlist(mut self, sender_allowlist: Option<HashSet<Address>>) -> Self {
+        self.sender_allowlist = sender_allowlist;
+        self
+    }
+
+    /// Sets the maximum number of distinct senders in the pending block.
+    pub const fn with_max_unique_senders(mut self, max_unique_senders: Option<usize>) -> Self {
+        self.max_unique_senders = max_unique_senders;
+        self
+    }
+
+    /// Sets the maximum time a transaction may have been queued in the pool to be included.
+    pub const fn with_max_pending_tx_age(mut self, max_pending_tx_age: Option<Duration>) -> Self {
+        self.max_pending_tx_age = max_pending_tx_age;
+        self
+    }
+
+    /// Sets the clock the age of pool transactions is measured with.
+    pub fn with_pending_tx_clock(mut self, pending_tx_clock: Option<PendingClock>) -> Self {
+        self.pending_tx_clock = pending_tx_clock;
+        self
+    }
+
+    /// Sets the check for transactions with time-based validity.
+    pub fn with_validity_check(mut self, validity_check: Option<PendingValidityCheck>) -> Self {
+        self.validity_check = validity_check;
+        self
+    }
+
+    /// Sets what happens to a pool transaction that can't be converted into the transaction type
+    /// of the pending block.
+    pub const fn with_conversion_failure(
+        mut self,
+        conversion_failure: PendingConversionFailure,
+    ) -> Self {
+        self.conversion_failure = conversion_failure;
+        self
+    }
+
+    /// Returns true if transactions of the given sender can be included in the pending block.
+    pub fn is_sender_allowed(&self, sender: &Address) -> bool {
+        self.sender_allowlist.as_ref().is_none_or(|allowlist| allowlist.contains(sender))
+    }
+
+    /// Returns true if transactions of the given sender must not be included, because the limit
+    /// of distinct senders is reached by the given senders that were already included.
+    pub fn exceeds_max_unique_senders(
+        &self,
+        included: &HashSet<Address>,
+        sender: &Address,
+    ) -> bool {
+        self.max_unique_senders.is_some_and(|max| included.len() >= max) &&
+            !included.contains(sender)
+    }
+
+    /// Returns true if a transaction that was added to the pool at the given time must not be
+    /// included, because it has been queued for longer than the configured maximum at `now`.
+    pub fn exceeds_max_pending_tx_age(&self, added_at: Instant, now: Instant) -> bool {
+        self.max_pending_tx_age
+            .is_some_and(|max_age| now.saturating_duration_since(added_at) > max_age)
+    }
+
+    /// Returns the current time of the configured [`PendingBlockConfig::pending_tx_clock`], or of
+    /// the system clock if none is configured.
+    pub fn pending_tx_clock_now(&self) -> Instant {
+        self.pending_tx_clock.as_ref().map_or_else(Instant::now, PendingClock::now)
+    }
+
+    /// Returns true if the given transaction of the given sender must not be included, because it
+    /// is a no-op transfer: a zero value transfer to the sender itself without calldata.
+    pub fn drops_noop_transfer<T: Transaction>(&self, sender: Address, tx: &T) -> bool {
+        self.drop_noop_transfers &&
+            tx.to() == Some(sender) &&
+            tx.value().is_zero() &&
+            tx.input().is_empty()
+    }
+
+    /// Returns the gas limit of a pending block built on a parent with the given gas limit.
+    ///
+    /// The configured gas limit must differ from the parent gas limit by less than the parent gas
+    /// limit divided by [`GAS_LIMIT_BOUND_DIVISOR`] and must not be below [`MINIMUM_GAS_LIMIT`],
+    /// like consensus requires. Otherwise an error is returned, since the pending block could never
+    /// become canonical.
+    pub fn next_gas_limit(&self, parent_gas_limit: u64) -> Result<u64, EthApiError> {
+        let Some(gas_limit) = self.gas_limit else { return Ok(parent_gas_limit) };
+
+        if gas_limit.abs_diff(parent_gas_limit) >= parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR ||
+            gas_limit < MINIMUM_GAS_LIMIT
+        {
+            return Err(EthApiError::PendingGasLimitOutOfBounds { parent_gas_limit, gas_limit })
+        }
+
+        Ok(gas_limit)
+    }
+
+    /// Sets the fraction of the block gas limit the pending block is filled up to.
+    ///
+    /// Returns an error if the fraction is not in `(0, 1]`.
+    pub fn with_target_gas_fraction(
+        mut self,
+        target_gas_fraction: Option<f64>,
+    ) -> Result<Self, InvalidTargetGasFraction> {
+        if let Some(fraction) = target_gas_fraction {
+            if !(fraction > 0.0 && fraction <= 1.0) {
+                return Err(InvalidTargetGasFraction(fraction))
+            }
+        }
+        self.target_gas_fraction = target_gas_fraction;
+        Ok(self)
+    }
+
+    /// Returns the configured fraction of the block gas limit the pending block is filled up to.
+    pub const fn target_gas_fraction(&self) -> Option<f64> {
+        self.target_gas_fraction
+    }
+
+    /// Sets the client version the extra data of the pending header is stamped with.
+    ///
+    /// Returns an error if the stamp, i.e. the 8 byte block timestamp followed by the client
+    /// version, exceeds the extra data size limit.
+    pub fn with_extra_data_stamp(
+        mut self,
+        client_version: Option<String>,
+    ) -> Result<Self, InvalidExtraDataStamp> {
+        if let Some(version) = &client_version {
+            let len = size_of::<u64>() + version.len();
+            if len > MAXIMUM_EXTRA_DATA_SIZE {
+                return Err(InvalidExtraDataStamp(len))
+            }
+        }
+        self.extra_data_stamp = client_version;
+        Ok(self)
+    }
+
+    /// Returns the client version the extra data of the pending header is stamped with.
+    pub fn extra_data_stamp(&self) -> Option<&str> {
+        self.extra_data_stamp.as_deref()
+    }
+
+    /// Returns the extra data to stamp the header of a pending block with the given timestamp
+    /// with, if configured: the timestamp as 8 big endian bytes followed by the client version.
+    ///
+    /// The stamp only depends on the block, so that rebuilding it yields the same header.
+    pub fn stamped_extra_data(&self, timestamp: u64) -> Option<Bytes> {
+        let version = self.extra_data_stamp.as_ref()?;
+        let mut stamp = Vec::with_capacity(size_of::<u64>() + version.len());
+        stamp.extend_from_slice(&timestamp.to_be_bytes());
+        stamp.extend_from_slice(version.as_bytes());
+        Some(stamp.into())
+    }
+
+    /// Returns the amount of gas pool transactions in the pending block may use, given the block
+    /// gas limit.
+    pub fn gas_target(&self, block_gas_limit: u64) -> u64 {
+        let gas_target = match self.target_gas_fraction {
+            Some(fraction) => (block_gas_limit as f64 * fraction) as u64,
+            None => block_gas_limit,
+        };
+        gas_target
+            .min(block_gas_limit.saturating_sub(self.deposit_gas_reserve.unwrap_or_default()))
+            .min(self.pending_gas_cap.unwrap_or(u64::MAX))
+    }
+
+    /// Returns the limits at which the selection of transactions for the pending block stops,
+    /// given the block gas limit.
+    pub fn build_limits(&self, block_gas_limit: u64) -> PendingBuildLimits {
+        PendingBuildLimits {
+            max_transactions: self.max_transactions,
+            gas: self.gas_target(block_gas_limit),
+            time_budget: self.build_time_budget,
+        }
+    }
+
+    /// Returns true if a transaction with the given number of blobs must not be included, given
+    /// the number of blobs that were already included.
+    pub const fn exceeds_max_blobs(&self, included: u64, blobs: u64) -> bool {
+        match self.max_blobs_per_block {
+            Some(max) => included + blobs > max,
+            None => false,
+        }
+    }
+
+    /// Returns true if no more blobs can be included, given the number of blobs that were already
+    /// included.
+    pub const fn is_blob_limit_reached(&self, included: u64) -> bool {
+        match self.max_blobs_per_block {
+            Some(max) => included >= max,
+            None => false,
+        }
+    }
+
+    /// Returns true if a transaction with the given calldata size must not be included, given the
+    /// calldata size of the transactions that were already included.
+    pub const fn exceeds_max_calldata(&self, included: usize, calldata: usize) -> bool {
+        match self.max_calldata_bytes {
+            Some(max) => included + calldata > max,
+            None => false,
+        }
+    }
+
+    /// Returns true if a transaction with the given encoded size must not be included.
+    pub const fn exceeds_max_tx_size(&self, size: usize) -> bool {
+        match self.max_tx_size_bytes {
+            Some(max) => size > max,
+            None => false,
+        }
+    }
+}
+
+/// Source of the current time, e.g. to measure the age of pool transactions against a time that
+/// doesn't advance on its own.
+#[derive(Clone)]
+pub struct PendingClock(Arc<dyn Fn() -> Instant + Send + Sync>);
+
+impl PendingClock {
+    /// Creates a new clock from a function that returns the current time.
+    pub fn new(now: impl Fn() -> Instant + Send + Sync + 'static) -> Self {
+        Self(Arc::new(now))
+    }
+
+    /// Returns the current time.
+    pub fn now(&self) -> Instant {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for PendingClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PendingClock").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for PendingClock {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Checks whether a transaction with time-based validity, e.g. an account abstraction bundle with
+/// a validity window, is valid at the timestamp of the pending block.
+#[derive(Clone)]
+pub struct PendingValidityCheck(Arc<dyn Fn(&dyn Transaction, u64) -> bool + Send + Sync>);
+
+impl PendingValidityCheck {
+    /// Creates a new check from a function that returns whether the given transaction is valid at
+    /// the given pending block timestamp.
+    pub fn new(check: impl Fn(&dyn Transaction, u64) -> bool + Send + Sync + 'static) -> Self {
+        Self(Arc::new(check))
+    }
+
+    /// Returns true if the given transaction is valid at the given pending block timestamp.
+    pub fn is_valid(&self, tx: &dyn Transaction, pending_timestamp: u64) -> bool {
+        (self.0)(tx, pending_timestamp)
+    }
+}
+
+impl fmt::Debug for PendingValidityCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PendingValidityCheck").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for PendingValidityCheck {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Error returned when configuring a target gas fraction outside of `(0, 1]`.
+#[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
+#[error("target gas fraction must be in (0, 1], got {0}")]
+pub struct InvalidTargetGasFraction(pub f64);
+
+/// Error returned when configuring an extra data stamp that exceeds the extra data size limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("extra data stamp of {0} bytes exceeds the {MAXIMUM_EXTRA_DATA_SIZE} byte limit")]
+pub struct InvalidExtraDataStamp(pub usize);
+
+/// Keeps track of the transactions that are selected for a locally built pending block.
+#[derive(Debug, Default)]
+pub struct PendingBlockSelection {
+    /// Hashes of all transactions that were included so far.
+    included: HashSet<TxHash>,
+}
+
+impl PendingBlockSelection {
+    /// Returns true if a transaction with the given hash was already included.
+    pub fn is_included(&self, hash: &TxHash) -> bool {
+        self.included.contains(hash)
+    }
+
+    /// Records the transaction with the given hash as included.
+    ///
+    /// Returns `false` if a transaction with the same hash was already included.
+    pub fn insert(&mut self, hash: TxHash) -> bool {
+        self.included.insert(hash)
+    }
+
+    /// Returns the number of included transactions.
+    pub fn len(&self) -> usize {
+        self.included.len()
+    }
+
+    /// Returns true if no transaction was included yet.
+    pub fn is_empty(&self) -> bool {
+        self.included.is_empty()
+    }
+}
+
+/// Pool transactions that were yielded before a transaction of the same sender with a lower nonce,
+/// and are deferred until that transaction is included in the pending block.
+#[derive(Debug)]
+pub struct DeferredTransactions<T: PoolTransaction> {
+    /// Deferred transactions by sender and nonce.
+    by_sender: HashMap<Address, BTreeMap<u64, Arc<ValidPoolTransaction<T>>>>,
+}
+
+impl<T: PoolTransaction> Default for DeferredTransactions<T> {
+    fn default() -> Self {
+        Self { by_sender: HashMap::default() }
+    }
+}
+
+impl<T: PoolTransaction> DeferredTransactions<T> {
+    /// Defers the given transaction until the transaction of its sender with the previous nonce
+    /// is included.
+    pub fn defer(&mut self, transaction: Arc<ValidPoolTransaction<T>>) {
+        self.by_sender
+            .entry(transaction.sender())
+            .or_default()
+            .insert(transaction.nonce(), transaction);
+    }
+
+    /// Removes and returns the deferred transaction of the given sender with the given nonce.
+    pub fn take(&mut self, sender: &Address, nonce: u64) -> Option<Arc<ValidPoolTransaction<T>>> {
+        let deferred = self.by_sender.get_mut(sender)?;
+        let transaction = deferred.remove(&nonce);
+        if deferred.is_empty() {
+            self.by_sender.remove(sender);
+        }
+        transaction
+    }
+
+    /// Returns the number of deferred transactions.
+    pub fn len(&self) -> usize {
+        self.by_sender.values().map(BTreeMap::len).sum()
+    }
+
+    /// Returns true if no transaction is deferred.
+    pub fn is_empty(&self) -> bool {
+        self.by_sender.is_empty()
+    }
+}
+
+/// A fixed set of pool transactions, in the order they were yielded by the pool, to build pending
+/// blocks from.
+///
+/// Building several pending blocks against the same snapshot yields identical blocks, regardless
+/// of transactions that arrive in the pool afterwards. The snapshot owns its transactions, so
+/// transactions that are evicted from the pool after the snapshot was taken are still considered.
+#[derive(Debug)]
+pub struct PendingPoolSnapshot<T: PoolTransaction> {
+    transactions: Vec<Arc<ValidPoolTransaction<T>>>,
+}
+
+impl<T: PoolTransaction> Clone for PendingPoolSnapshot<T> {
+    fn clone(&self) -> Self {
+        Self { transactions: self.transactions.clone() }
+    }
+}
+
+impl<T: PoolTransaction> PendingPoolSnapshot<T> {
+    /// Takes a snapshot of all transactions yielded by the given iterator.
+    pub fn new(best_transactions: impl Iterator<Item = Arc<ValidPoolTransaction<T>>>) -> Self {
+        Self { transactions: best_transactions.collect() }
+    }
+
+    /// Returns the transactions of the snapshot.
+    pub fn transactions(&self) -> &[Arc<ValidPoolTransaction<T>>] {
+        &self.transactions
+    }
+
+    /// Returns a copy of the snapshot that yields the transactions with the given hashes first, in
+    /// the given order, followed by all other transactions in their original order.
+    ///
+    /// Hashes of transactions that are not part of the snapshot are ignored.
+    pub fn prioritized(&self, hashes: &[TxHash]) -> Self {
+        let mut rest = self.transactions.clone();
+        let mut transactions = Vec::with_capacity(rest.len());
+        for hash in hashes {
+            if let Some(idx) = rest.iter().position(|tx| tx.hash() == hash) {
+                transactions.push(rest.remove(idx));
+            }
+        }
+        transactions.extend(rest);
+        Self { transactions }
+    }
+
+    /// Returns a copy of the snapshot where consecutive transactions with the same effective tip
+    /// at the given base fee are ordered by hash.
+    ///
+    /// The pool breaks such ties by submission order, so the same set of transactions can be
+    /// yielded in a different order by different pools. Transactions of the same sender keep
+    /// their nonce order.
+    pub fn with_hash_tie_break(&self, base_fee: u64) -> Self {
+        let mut transactions = Vec::with_capacity(self.transactions.len());
+        for run in self
+            .transactions
+            .chunk_by(|a, b| a.effective_tip_per_gas(base_fee) == b.effective_tip_per_gas(base_fee))
+        {
+            // the pending transactions of each sender in the run, in nonce order
+            let mut by_sender: Vec<VecDeque<_>> = Vec::new();
+            for tx in run {
+                match by_sender.iter_mut().find(|txs| txs[0].sender_ref() == tx.sender_ref()) {
+                    Some(txs) => txs.push_back(tx.clone()),
+                    None => by_sender.push(VecDeque::from([tx.clone()])),
+                }
+            }
+
+            while let Some(idx) = (0..by_sender.len()).min_by_key(|&idx| *by_sender[idx][0].hash())
+            {
+                transactions.extend(by_sender[idx].pop_front());
+                if by_sender[idx].is_empty() {
+                    by_sender.swap_remove(idx);
+                }
+            }
+        }
+        Self { transactions }
+    }
+
+    /// Returns a copy of the snapshot with its transactions in the given order.
+    ///
+    /// The base fee and gas budget of the block are only used by
+    /// [`PendingTransactionOrdering::MaxFee`].
+    pub fn ordered_by(
+        &self,
+        ordering: PendingTransactionOrdering,
+        base_fee: u64,
+        gas_budget: u64,
+    ) -> Self {
+        let mut transactions = self.transactions.clone();
+        match ordering {
+            PendingTransactionOrdering::Pool => {}
+            PendingTransactionOrdering::Submission => {
+                transactions.sort_by_key(|tx| tx.timestamp);
+            }
+            PendingTransactionOrdering::MaxFee => {
+                let fee_per_gas =
+                    |tx: &ValidPoolTransaction<T>| tx.effective_tip_per_gas(base_fee).unwrap_or(0);
+                let fee = |tx: &ValidPoolTransaction<T>| {
+                    fee_per_gas(tx).saturating_mul(tx.gas_limit() as u128)
+                };
+
+                // the weight of a transaction is its gas limit, so the fee per gas is its value
+                // density. A transaction that is moved ahead of a transaction of the same sender
+                // with a lower nonce is deferred by the builder until that one is included.
+                transactions.sort_by_key(|tx| Reverse(fee_per_gas(tx)));
+
+                let mut gas_left = gas_budget;
+                let mut greedy_fees = 0u128;
+                for tx in &transactions {
+                    if let Some(left) = gas_left.checked_sub(tx.gas_limit()) {
+                        gas_left = left;
+                        greedy_fees = greedy_fees.saturating_add(fee(tx));
+                    }
+                }
+
+                // the greedy selection can pay arbitrarily less than the single most valuable
+                // transaction that fits, the better of both pays at least half of the optimum.
+                // Only the first transaction of a sender can be executed on its own.
+                let mut lowest_nonces = HashMap::<Address, u64>::default();
+                for tx in &transactions {
+                    let nonce = lowest_nonces.entry(tx.sender()).or_insert(tx.nonce());
+                    *nonce = (*nonce).min(tx.nonce());
+                }
+                let most_valuable = transactions
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, tx)| {
+                        tx.gas_limit() <= gas_budget &&
+                            lowest_nonces.get(tx.sender_ref()) == Some(&tx.nonce())
+                    })
+                    .max_by_key(|(_, tx)| fee(tx));
+                if let Some((idx, tx)) = most_valuable {
+                    if fee(tx) > greedy_fees {
+                        let tx = transactions.remove(idx);
+                        transactions.insert(0, tx);
+                    }
+                }
+            }
+            PendingTransactionOrdering::Dependencies => {
+                transactions = Self::dependencies_first(transactions);
+            }
+        }
+        Self { transactions }
+    }
+
+    /// Orders the given transactions so that transactions calling a contract deployed by another
+    /// of the transactions come after the deployment, keeping the given order otherwise.
+    ///
+    /// Only deployments by CREATE transactions are detected, not contracts that are created by
+    /// other contracts.
+    fn dependencies_first(
+        transactions: Vec<Arc<ValidPoolTransaction<T>>>,
+    ) -> Vec<Arc<ValidPoolTransaction<T>>> {
+        let deployers = transactions
+            .iter()
+            .filter(|tx| tx.transaction.is_create())
+            .map(|tx| (tx.sender().create(tx.nonce()), *tx.hash()))
+            .collect::<HashMap<_, _>>();
+
+        let mut included = HashSet::with_capacity(transactions.len());
+        // transactions waiting for the deployment of the contract they call, by deployment
+        let mut waiting = HashMap::<TxHash, Vec<_>>::default();
+        let mut ordered = Vec::with_capacity(transactions.len());
+        for tx in transactions {
+            if let Some(deployer) = tx.to().and_then(|to| deployers.get(&to)) {
+                if !included.contains(deployer) {
+                    waiting.entry(*deployer).or_default().push(tx);
+                    continue
+                }
+            }
+
+            // a deployment releases the transactions waiting for it, calls never deploy anything
+            // so they can't release other transactions
+            included.insert(*tx.hash());
+            let released = waiting.remove(tx.hash()).unwrap_or_default();
+            ordered.push(tx);
+            for tx in released {
+                included.insert(*tx.hash());
+                ordered.push(tx);
+            }
+        }
+        ordered
+    }
+
+    /// Returns an iterator over the transactions of the snapshot, in their original order.
+    pub fn best_transactions(&self) -> SnapshotBestTransactions<T> {
+        SnapshotBestTransactions {
+            transactions: self.transactions.clone().into_iter(),
+            invalid_senders: HashSet::default(),
+            skip_blobs: false,
+        }
+    }
+}
+
+/// [`BestTransactions`] iterator over the transactions of a [`PendingPoolSnapshot`].
+#[derive(Debug)]
+pub struct SnapshotBestTransactions<T: PoolTransaction> {
+    transactions: std::vec::IntoIter<Arc<ValidPoolTransaction<T>>>,
+    /// Senders with a transaction that was marked invalid, all their subsequent transactions
+    /// depend on it.
+    invalid_senders: HashSet<Address>,
+    skip_blobs: bool,
+}
+
+impl<T: PoolTransaction> Iterator for SnapshotBestTransactions<T> {
+    type Item = Arc<ValidPoolTransaction<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.transactions.find(|tx| {
+            !self.invalid_senders.contains(tx.sender_ref()) && !(self.skip_blobs && tx.is_eip4844())
+        })
+    }
+}
+
+impl<T: PoolTransaction> BestTransactions for SnapshotBestTransactions<T> {
+    fn mark_invalid(&mut self, transaction: &Self::Item, _kind: InvalidPoolTransactionError) {
+        self.invalid_senders.insert(transaction.sender());
+    }
+
+    fn no_updates(&mut self) {}
+
+    fn set_skip_blobs(&mut self, skip_blobs: bool) {
+        self.skip_blobs = skip_blobs;
+    }
+}
+
+/// What happens to a pool transaction that can't be converted into the transaction type of the
+/// pending block, e.g. a custom pool transaction holding a transaction it can't decode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PendingConversionFailure {
+    /// The transaction and its descendants are skipped with a warning and the build continues.
+    #[default]
+    Skip,
+    /// The build fails with the conversion error.
+    Fail,
+}
+
+/// Order in which transactions are considered for a candidate pending block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PendingTransactionOrdering {
+    /// The order of the pool, highest priority fee first.
+    #[default]
+    Pool,
+    /// The order in which the transactions were submitted to the pool, oldest first.
+    Submission,
+    /// An order that approximates the maximum total priority fees of the block within its gas
+    /// budget.
+    ///
+    /// This is a greedy heuristic for the underlying knapsack problem: transactions are ordered
+    /// by priority fee per gas, estimating the gas each one uses by its gas limit, unless the
+    /// single most valuable transaction pays more than that greedy selection, in which case it's
+    /// considered first. It is not guaranteed to find the most profitable block.
+    MaxFee,
+    /// The order of the pool, except that transactions calling a contract deployed by another
+    /// transaction are moved behind the deployment, so they don't execute against empty code.
+    ///
+    /// The nonce order of each sender is always preserved by the builder, this extends it to
+    /// dependencies between senders where they are detectable.
+    Dependencies,
+}
+
+/// Policy a candidate pending block is built under.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PendingBlockPolicy {
+    /// Order in which transactions are considered.
+    pub ordering: PendingTransactionOrdering,
+    /// Limits the transactions are selected with.
+    pub config: PendingBlockConfig,
+}
+
+/// Composition of a candidate pending block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingBlockSummary {
+    /// Hash of the block.
+    pub hash: B256,
+    /// Number of the block.
+    pub number: u64,
+    /// Gas used by the block.
+    pub gas_used: u64,
+    /// Hashes of the included transactions, in block order.
+    pub transactions: Vec<TxHash>,
+}
+
+impl PendingBlockSummary {
+    /// Summarizes the given block.
+    pub fn new<B: Block>(block: &RecoveredBlock<B>) -> Self {
+        Self {
+            hash: block.hash(),
+            number: block.header().number(),
+            gas_used: block.header().gas_used(),
+            transactions: block.body().transaction_hashes_iter().copied().collect(),
+        }
+    }
+}
+
+/// Merkle proof that a transaction is included in the transactions trie of the pending block.
+///
+/// The proof can be verified against the transactions root of the pending header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingTransactionProof {
+    /// Index of the transaction in the block.
+    pub index: usize,
+    /// EIP-2718 encoding of the transaction, the value of its leaf.
+    pub transaction: Bytes,
+    /// Nodes on the path from the root to the leaf of the transaction, root first.
+    pub proof: Vec<Bytes>,
+}
+
+impl PendingTransactionProof {
+    /// Creates the proof for the transaction at the given index of the given block transactions.
+    ///
+    /// This rebuilds the trie the same way the transactions root of the header is computed.
+    /// Returns `None` if the index is not below the number of transactions.
+    pub fn new<T: Encodable2718>(transactions: &[T], index: usize) -> Option<Self> {
+        let transaction = transactions.get(index)?.encoded_2718().into();
+
+        let target = Self::key(index);
+        let mut hash_builder =
+            HashBuilder::default().with_proof_retainer(ProofRetainer::new(vec![target.clone()]));
+        let mut value = Vec::new();
+        for i in 0..transactions.len() {
+            let index = adjust_index_for_rlp(i, transactions.len());
+            value.clear();
+            transactions[index].encode_2718(&mut value);
+            hash_builder.add_leaf(Self::key(index), &value);
+        }
+        hash_builder.root();
+
+        let proof = hash_builder
+            .take_proof_nodes()
+            .matching_nodes_sorted(&target)
+            .into_iter()
+            .map(|(_, node)| node)
+            .collect();
+        Some(Self { index, transaction, proof })
+    }
+
+    /// Verifies the proof against the given transactions root.
+    pub fn verify(&self, transactions_root: B256) -> Result<(), ProofVerificationError> {
+        verify_proof(
+            transactions_root,
+            Self::key(self.index),
+            Some(self.transaction.to_vec()),
+            &self.proof,
+        )
+    }
+
+    /// Returns the key of the transaction at the given index in the transactions trie.
+    fn key(index: usize) -> Nibbles {
+        Nibbles::unpack(alloy_rlp::encode_fixed_size(&index))
+    }
+}
+
+/// Fee a transaction of the pending block would pay at the current pending base fee, which can
+/// be above the base fee of the pending block if the block was built before the base fee rose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingTransactionFee {
+    /// Hash of the transaction.
+    pub hash: TxHash,
+    /// Priority fee per gas the transaction would pay, zero if it's underpriced.
+    pub priority_fee: u128,
+    /// Whether the max fee of the transaction is below the current pending base fee, i.e. it's
+    /// only included because it was selected before the base fee rose.
+    pub underpriced: bool,
+}
+
+/// Outcome of a bundle transaction that was executed ahead of the pool transactions of a
+/// pending block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingBundleTransactionResult {
+    /// Hash of the transaction.
+    pub hash: TxHash,
+    /// Whether the transaction executed successfully, reverted transactions are still included.
+    pub success: bool,
+    /// Gas used by the transaction.
+    pub gas_used: u64,
+}
+
+/// Limits at which the selection of pool transactions for the pending block stops, whichever is
+/// reached first.
+///
+/// Transactions that are forced into the top of the block, like a bundle or deposits on OP, don't
+/// count towards the limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingBuildLimits {
+    /// Maximum number of included pool transactions, if limited.
+    pub max_transactions: Option<usize>,
+    /// Maximum amount of gas the included pool transactions may use.
+    pub gas: u64,
+    /// Maximum time spent selecting and executing transactions, if limited.
+    pub time_budget: Option<Duration>,
+}
+
+impl PendingBuildLimits {
+    /// Returns the limit that is reached, given the number of included pool transactions, the gas
+    /// they used and the time spent on the selection so far.
+    pub fn reached(
+        &self,
+        transactions: usize,
+        gas_used: u64,
+        elapsed: Duration,
+    ) -> Option<PendingBuildLimit> {
+        if self.max_transactions.is_some_and(|max| transactions >= max) {
+            Some(PendingBuildLimit::Transactions)
+        } else if gas_used >= self.gas {
+            Some(PendingBuildLimit::Gas)
+        } else if self.time_budget.is_some_and(|budget| elapsed >= budget) {
+            Some(PendingBuildLimit::Time)
+        } else {
+            None
+        }
+    }
+}
+
+/// A limit of [`PendingBuildLimits`] that stopped the selection of transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingBuildLimit {
+    /// The maximum number of transactions was included.
+    Transactions,
+    /// The transactions used the available gas.
+    Gas,
+    /// The time budget was spent.
+    Time,
+}
+
+/// Maximum number of [`PendingExclusion`]s recorded during the build of a pending block.
+///
+/// Once a limit stops the selection, at most this many of the remaining pool transactions are
+/// inspected, so that a full pool doesn't prolong every build.
+pub const MAX_PENDING_EXCLUSIONS: usize = 64;
+
+/// A valid pool transaction that the pending block doesn't include, although it would have fit
+/// into the gas left at the end of the build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingExclusion {
+    /// Hash of the excluded transaction.
+    pub hash: TxHash,
+    /// Why the transaction was excluded.
+    pub reason: PendingExclusionReason,
+}
+
+/// Why a pool transaction that would have fit was excluded from the pending block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingExclusionReason {
+    /// The selection stopped at this limit before the transaction was reached.
+    Limit(PendingBuildLimit),
+    /// The calldata of the transaction exceeded the remaining calldata budget.
+    Calldata,
+}
+
+/// Time spent in each phase of a pending block build.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PendingBuildTimings {
+    /// Time spent selecting transactions from the pool, excluding their execution.
+    pub selection: Duration,
+    /// Time spent executing the selected transactions and applying the pre and post block
+    /// execution changes.
+    pub execution: Duration,
+    /// Time spent calculating the state root.
+    pub state_root: Duration,
+    /// Time spent assembling and sealing the block.
+    pub sealing: Duration,
+}
+
+impl PendingBuildTimings {
+    /// Returns the total time spent in all phases.
+    pub fn total(&self) -> Duration {
+        self.selection + self.execution + self.state_root + self.sealing
+    }
+}
+
+/// Changes an account would experience if the pending block were applied on top of the latest
+/// state.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountDiff {
+    /// Balance in the latest and in the pending state, if it changed.
+    pub balance: Option<(U256, U256)>,
+    /// Nonce in the latest and in the pending state, if it changed.
+    pub nonce: Option<(u64, u64)>,
+    /// Changed storage slots, with their values in the latest and in the pending state.
+    pub storage: BTreeMap<U256, (U256, U256)>,
+}
+
+impl AccountDiff {
+    /// Creates the diff of an account that was touched by the pending block.
+    pub fn from_bundle_account(account: &BundleAccount) -> Self {
+        let original = account.original_info.clone().unwrap_or_default();
+        let present = account.info.clone().unwrap_or_default();
+
+        Self {
+            balance: (original.balance != present.balance)
+                .then_some((original.balance, present.balance)),
+            nonce: (original.nonce != present.nonce).then_some((original.nonce, present.nonce)),
+            storage: account
+                .storage
+                .iter()
+                .filter(|(_, slot)| slot.is_changed())
+                .map(|(key, slot)| (*key, (slot.previous_or_original_value, slot.present_value)))
+                .collect(),
+        }
+    }
+
+    /// Returns true if the account is not changed by the pending block.
+    pub fn is_empty(&self) -> bool {
+        self.balance.is_none() && self.nonce.is_none() && self.storage.is_empty()
+    }
+}
+
+/// Changes of all accounts that would be modified if the pending block were applied on top of
+/// the latest state.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PendingStateDiff {
+    /// Diffs of the changed accounts, accounts that were only read are omitted.
+    pub accounts: BTreeMap<Address, AccountDiff>,
+}
+
+impl PendingStateDiff {
+    /// Creates the diff of all accounts changed by the post state of the pending block.
+    pub fn from_bundle_state(bundle: &BundleState) -> Self {
+        Self {
+            accounts: bundle
+                .state
+                .iter()
+                .map(|(address, account)| (*address, AccountDiff::from_bundle_account(account)))
+                .filter(|(_, diff)| !diff.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Returns the diff of the given account, if it's changed by the pending block.
+    pub fn account(&self, address: &Address) -> Option<&AccountDiff> {
+        self.accounts.get(address)
+    }
+
+    /// Returns true if the pending block doesn't change any account.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+}
+
+/// Report on how accurately a locally built pending block predicted the canonical block that was
+/// subsequently sealed at the same height.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingBlockDiff {
+    /// Number of the compared blocks.
+    pub number: u64,
+    /// Number of transactions that are included in both blocks.
+    pub included: usize,
+    /// Transactions of the canonical block that were missing from the pending block.
+    pub missing: Vec<TxHash>,
+    /// Transactions of the pending block that didn't make it into the canonical block.
+    pub unexpected: Vec<TxHash>,
+    /// Gas used by the canonical block minus the gas used by the pending block.
+    pub gas_used_delta: i128,
+    /// Whether both blocks have the same base fee.
+    pub base_fee_match: bool,
+}
+
+impl PendingBlockDiff {
+    /// Compares the pending block against the canonical block.
+    pub fn new<B: Block>(pending: &RecoveredBlock<B>, canonical: &RecoveredBlock<B>) -> Self {
+        let pending_hashes =
+            pending.body().transaction_hashes_iter().copied().collect::<HashSet<_>>();
+        let canonical_hashes =
+            canonical.body().transaction_hashes_iter().copied().collect::<HashSet<_>>();
+
+        let missing = canonical
+            .body()
+            .transaction_hashes_iter()
+            .filter(|hash| !pending_hashes.contains(*hash))
+            .copied()
+            .collect();
+        let unexpected = pending
+            .body()
+            .transaction_hashes_iter()
+            .filter(|hash| !canonical_hashes.contains(*hash))
+            .copied()
+            .collect();
+
+        Self {
+            number: canonical.header().number(),
+            included: pending_hashes.intersection(&canonical_hashes).count(),
+            missing,
+            unexpected,
+            gas_used_delta: canonical.header().gas_used() as i128 -
+                pending.header().gas_used() as i128,
+            base_fee_match: canonical.header().base_fee_per_gas() ==
+                pending.header().base_fee_per_gas(),
+        }
+    }
+
+    /// Returns true if the pending block contained exactly the transactions of the canonical
+    /// block.
+    pub fn is_exact(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty()
+    }
+}
+
+/// Default number of recent heights tracked by [`PendingAccuracy`].
+pub const DEFAULT_PENDING_ACCURACY_HISTORY: usize = 64;
+
+/// Rolling accuracy of locally built pending blocks over the most recent heights.
+///
+/// The accuracy is the share of the transactions of the pending blocks that landed in the
+/// canonical blocks sealed at the same heights.
+#[derive(Debug)]
+pub struct PendingAccuracy {
+    /// Number, predicted and included transactions of each recorded height, oldest first.
+    history: VecDeque<(u64, usize, usize)>,
+    /// Maximum number of recorded heights.
+    capacity: usize,
+    /// Metrics for the rolling accuracy.
+    metrics: PendingAccuracyMetrics,
+}
+
+impl PendingAccuracy {
+    /// Creates an empty history that keeps the given number of recent heights.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            history: VecDeque::with_capacity(capacity),
+            capacity,
+            metrics: PendingAccuracyMetrics::default(),
+        }
+    }
+
+    /// Records the comparison of a pending block against the canonical block at its height.
+    ///
+    /// A height that is recorded again, e.g. after a reorg, replaces its previous record.
+    pub fn record(&mut self, diff: &PendingBlockDiff) {
+        let sample = (diff.number, diff.included + diff.unexpected.len(), diff.included);
+        match self.history.iter_mut().find(|(number, ..)| *number == diff.number) {
+            Some(recorded) => *recorded = sample,
+            None => {
+                if self.history.len() == self.capacity {
+                    self.history.pop_front();
+                }
+                self.history.push_back(sample);
+            }
+        }
+
+        if let Some(accuracy) = self.accuracy() {
+            self.metrics.prediction_accuracy.set(accuracy);
+        }
+    }
+
+    /// Returns the share of the predicted transactions of the recorded heights that were
+    /// included in the canonical blocks.
+    ///
+    /// Returns `None` if no transactions were predicted.
+    pub fn accuracy(&self) -> Option<f64> {
+        let (predicted, included) = self
+            .history
+            .iter()
+            .fold((0, 0), |(predicted, included), (_, p, i)| (predicted + p, included + i));
+        (predicted > 0).then(|| included as f64 / predicted as f64)
+    }
+
+    /// Returns the number of recorded heights.
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Returns true if no height was recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+}
+
+impl Default for PendingAccuracy {
+    fn default() -> Self {
+        Self::new(DEFAULT_PENDING_ACCURACY_HISTORY)
+    }
+}
+
+/// Awaits new canonical blocks and records how many transactions of the locally built pending
+/// block at the same height landed in them.
+pub async fn pending_accuracy_task<St, N>(
+    pending_block: Arc<Mutex<Option<PendingBlock<N::Block, N::Receipt>>>>,
+    accuracy: Arc<parking_lot::Mutex<PendingAccuracy>>,
+    mut events: St,
+) where
+    St: Stream<Item = CanonStateNotification<N>> + Unpin + 'static,
+    N: NodePrimitives,
+{
+    while let Some(event) = events.next().await {
+        let canonical = event.tip();
+        let diff = match pending_block.lock().await.as_ref() {
+            Some(pending) if pending.block.number() == canonical.number() => {
+                PendingBlockDiff::new(&pending.block, canonical)
+            }
+            _ => continue,
+        };
+        trace!(target: "rpc::eth", number = diff.number, included = diff.included, unexpected = diff.unexpected.len(), "Recorded pending block accuracy");
+        accuracy.lock().record(&diff);
+    }
+}
+
+/// Metrics for the accuracy of locally built pending blocks.
+#[derive(Metrics)]
+#[metrics(scope = "rpc.eth_pending_block")]
+struct PendingAccuracyMetrics {
+    /// Share of the transactions of recent pending blocks that landed in the canonical block at
+    /// the same height.
+    prediction_accuracy: Gauge,
+}
+
+/// Converts an error that occurred while accessing the state of the parent of the pending block.
+///
+/// If the parent state was pruned, this returns [`EthApiError::PendingParentStatePruned`] instead
+/// of an opaque internal error.
+pub fn pending_parent_state_error(parent_number: u64, error: ProviderError) -> EthApiError {
+    match error {
+        ProviderError::StateAtBlockPruned(_) => {
+            EthApiError::PendingParentStatePruned(parent_number)
+        }
+        err => err.into(),
+    }
+}
+
+/// Returns [`ProviderError::StateAtBlockPruned`] if the history the state of the pending block's
+/// parent is read from was pruned, according to the prune checkpoints of the provider.
+///
+/// The state of the latest block is always available. The state of an older parent is read from
+/// the account and storage history after it.
+pub fn ensure_parent_state_available<P>(
+    provider: &P,
+    parent_number: u64,
+) -> Result<(), ProviderError>
+where
+    P: BlockNumReader + PruneCheckpointReader,
+{
+    if parent_number >= provider.best_block_number()? {
+        return Ok(())
+    }
+
+    for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+        // the checkpoint is the highest pruned block, so the history after the parent is only
+        // available if the parent isn't below it
+        let pruned =
+            provider.get_prune_checkpoint(segment)?.and_then(|checkpoint| checkpoint.block_number);
+        if pruned.is_some_and(|pruned| pruned > parent_number) {
+            return Err(ProviderError::StateAtBlockPruned(parent_number))
+        }
+    }
+    Ok(())
+}
+
+/// Returns the priority fees the given transactions of a block with the given base fee pay, using
+/// the gas used according to their receipts.
+///
+/// System transactions, e.g. deposits on OP, don't pay a priority fee and contribute nothing.
+pub fn priority_fee_revenue<'a, T, R>(
+    base_fee: u64,
+    transactions: impl IntoIterator<Item = (&'a T, &'a R)>,
+    is_system_transaction: impl Fn(&T) -> bool,
+) -> U256
+where
+    T: Transaction + 'a,
+    R: TxReceipt + 'a,
+{
+    let mut revenue = U256::ZERO;
+    let mut cumulative_gas_used = 0;
+    for (tx, receipt) in transactions {
+        let gas_used = receipt.cumulative_gas_used() - cumulative_gas_used;
+        cumulative_gas_used = receipt.cumulative_gas_used();
+        if is_system_transaction(tx) {
+            continue
+        }
+        let tip = tx.effective_tip_per_gas(base_fee).unwrap_or_default();
+        revenue += U256::from(gas_used) * U256::from(tip);
+    }
+    revenue
+}
+
+/// Returns the blob gas used by the given transactions of a block and all transactions before
+/// them, one entry per transaction.
+///
+/// Transactions without blobs use no blob gas, so their entry equals the one before.
+pub fn cumulative_blob_gas<'a, T>(transactions: impl IntoIterator<Item = &'a T>) -> Vec<u64>
+where
+    T: Transaction + 'a,
+{
+    let mut cumulative_blob_gas_used = 0;
+    transactions
+        .into_iter()
+        .map(|tx| {
+            cumulative_blob_gas_used += tx.blob_gas_used().unwrap_or_default();
+            cumulative_blob_gas_used
+        })
+        .collect()
+}
+
+/// Returns the message of a panic caught while building the pending block, or an empty string if
+/// the panic has no message.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_default()
+}
+
+/// Ensures that the forks the chain spec reports as active at the given timestamp are activated in
+/// order, so that the header fields derived from them are consistent.
+pub fn ensure_fork_ordering(
+    chain_spec: &impl EthereumHardforks,
+    timestamp: u64,
+) -> Result<(), EthApiError> {
+    let forks = [
+        ("Shanghai", chain_spec.is_shanghai_active_at_timestamp(timestamp)),
+        ("Cancun", chain_spec.is_cancun_active_at_timestamp(timestamp)),
+        ("Prague", chain_spec.is_prague_active_at_timestamp(timestamp)),
+    ];
+
+    for (&(inactive, is_preceding_active), &(active, is_active)) in forks.iter().zip(&forks[1..]) {
+        if is_active && !is_preceding_active {
+            return Err(EthApiError::ConflictingForkActivation { timestamp, active, inactive })
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the gas the given transaction needs at least in the block with the given number and
+/// timestamp: its intrinsic gas, or since Prague its calldata floor if that is higher.
+///
+/// A transaction with a lower gas limit is rejected by the EVM.
+pub fn intrinsic_gas<T: Transaction>(
+    chain_spec: &impl EthereumHardforks,
+    number: u64,
+    timestamp: u64,
+    transaction: &T,
+) -> u64 {
+    // only the forks that changed the intrinsic gas matter
+    let spec = if chain_spec.is_prague_active_at_timestamp(timestamp) {
+        SpecId::PRAGUE
+    } else if chain_spec.is_shanghai_active_at_timestamp(timestamp) {
+        SpecId::SHANGHAI
+    } else if chain_spec.is_ethereum_fork_active_at_block(EthereumHardfork::Berlin, number) {
+        SpecId::BERLIN
+    } else if chain_spec.is_ethereum_fork_active_at_block(EthereumHardfork::Istanbul, number) {
+        SpecId::ISTANBUL
+    } else if chain_spec.is_homestead_active_at_block(number) {
+        SpecId::HOMESTEAD
+    } else {
+        SpecId::FRONTIER
+    };
+
+    let access_list = transaction.access_list();
+    let gas = calculate_initial_tx_gas(
+        spec,
+        transaction.input(),
+        transaction.is_create(),
+        access_list.map(|list| list.len()).unwrap_or_default() as u64,
+        access_list
+            .map(|list| list.iter().map(|item| item.storage_keys.len()).sum::<usize>())
+            .unwrap_or_default() as u64,
+        transaction.authorization_list().map(|list| list.len()).unwrap_or_default() as u64,
+    );
+    gas.initial_gas.max(gas.floor_gas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{Header, TxEip2930, TxEip4844, TxLegacy};
+    use alloy_eips::{
+        eip2930::{AccessList, AccessListItem},
+        eip4844::DATA_GAS_PER_BLOB,
+    };
+    use alloy_primitives::{Address, Bytes, PrimitiveSignature as Signature, TxKind};
+    use reth_chainspec::{ChainSpecBuilder, EthereumHardfork};
+    use reth_primitives::{BlockBody, Transaction, TransactionSigned};
+    use reth_primitives_traits::SignedTransaction;
+    use std::time::Duration;
+
+    fn transaction(nonce: u64) -> TransactionSigned {
+        TransactionSigned::new_unhashed(
+            Transaction::Legacy(TxLegacy { nonce, ..Default::default() }),
+            Signature::test_signature(),
+        )
+    }
+
+    fn block(
+        transactions: Vec<TransactionSigned>,
+        gas_used: u64,
+        base_fee: u64,
+    ) -> RecoveredBlock<reth_primitives::Block> {
+        let senders = vec![Address::ZERO; transactions.len()];
+        RecoveredBlock::new_unhashed(
+            reth_primitives::Block {
+                header: Header {
+                    number: 1,
+                    gas_used,
+                    base_fee_per_gas: Some(base_fee),
+                    ..Default::default()
+                },
+                body: BlockBody { transactions, ..Default::default() },
+            },
+            senders,
+        )
+    }
+
+    #[test]
+    fn status_is_stale_after_parent_changed() {
+        let parent_hash = B256::random();
+        let built_at = Instant::now();
+        let pending = PendingBlock::<_, Receipt>::new(
+            built_at,
+            built_at + Duration::from_secs(1),
+            RecoveredBlock::new_unhashed(
+                reth_primitives::Block {
+                    header: Header { parent_hash, ..Default::default() },
+                    body: Default::default(),
+                },
+                Vec::new(),
+            ),
+            Vec::new(),
+            BundleState::default(),
+            Vec::new(),
+        );
+
+        let status = pending.status(parent_hash, built_at);
+        assert_eq!(status.built_at, built_at);
+        assert_eq!(status.parent_hash, parent_hash);
+        assert!(!status.is_stale);
+
+        // a new block was added on top of the parent
+        assert!(pending.status(B256::random(), built_at).is_stale);
+
+        // the pending block expired
+        assert!(pending.status(parent_hash, built_at + Duration::from_secs(2)).is_stale);
+    }
+
+    #[test]
+    fn config_max_tx_size() {
+        let config = PendingBlockConfig::default();
+        assert!(!config.exceeds_max_tx_size(usize::MAX));
+
+        let config = config.with_max_tx_size_bytes(Some(128));
+        assert!(!config.exceeds_max_tx_size(128));
+        assert!(config.exceeds_max_tx_size(129));
+    }
+
+    #[test]
+    fn caught_panic_message() {
+        let payload = std::panic::catch_unwind(|| panic!("undecodable")).unwrap_err();
+        assert_eq!(panic_message(&*payload), "undecodable");
+
+        let payload = std::panic::catch_unwind(|| panic!("undecodable {}", 1)).unwrap_err();
+        assert_eq!(panic_message(&*payload), "undecodable 1");
+
+        let payload = std::panic::catch_unwind(|| std::panic::panic_any(1)).unwrap_err();
+        assert_eq!(panic_message(&*payload), "");
+    }
+
+    #[test]
+    fn config_max_calldata() {
+        let config = PendingBlockConfig::default();
+        assert!(!config.exceeds_max_calldata(usize::MAX - 6, 6));
+
+        let config = config.with_max_calldata_bytes(Some(1_000));
+        assert!(!config.exceeds_max_calldata(600, 400));
+        assert!(config.exceeds_max_calldata(600, 401));
+    }
+
+    #[test]
+    fn config_max_blobs() {
+        let config = PendingBlockConfig::default();
+        assert!(!config.exceeds_max_blobs(u64::MAX - 6, 6));
+        assert!(!config.is_blob_limit_reached(u64::MAX));
+
+        let config = config.with_max_blobs_per_block(Some(3));
+        assert!(!config.exceeds_max_blobs(1, 2));
+        assert!(config.exceeds_max_blobs(2, 2));
+        assert!(!config.is_blob_limit_reached(2));
+        assert!(config.is_blob_limit_reached(3));
+    }
+
+    #[test]
+    fn config_extra_data_stamp() {
+        let config = PendingBlockConfig::default();
+        assert_eq!(config.stamped_extra_data(1_700_000_000), None);
+
+        let config = config.with_extra_data_stamp(Some("reth/v1.3.0".to_string())).unwrap();
+        let stamp = config.stamped_extra_data(1_700_000_000).unwrap();
+        assert_eq!(stamp.len(), 8 + "reth/v1.3.0".len());
+        assert_eq!(&stamp[..8], 1_700_000_000u64.to_be_bytes());
+        assert_eq!(&stamp[8..], b"reth/v1.3.0");
+
+        // 8 bytes of timestamp leave room for a 24 byte client version
+        assert!(config.clone().with_extra_data_stamp(Some("a".repeat(24))).is_ok());
+        assert_eq!(
+            config.with_extra_data_stamp(Some("a".repeat(25))).unwrap_err(),
+            InvalidExtraDataStamp(33)
+        );
+    }
+
+    #[test]
+    fn build_limits() {
+        let unlimited = PendingBlockConfig::default().build_limits(30_000_000);
+        assert_eq!(unlimited.reached(usize::MAX, 29_999_999, Duration::MAX), None);
+
+        let config = PendingBlockConfig::default()
+            .with_max_transactions(Some(2))
+            .with_build_time_budget(Some(Duration::from_millis(100)));
+        let limits = config.build_limits(30_000_000);
+        assert_eq!(limits.gas, 30_000_000);
+        assert_eq!(limits.reached(1, 21_000, Duration::ZERO), None);
+
+        // each limit stops the selection on its own
+        assert_eq!(
+            limits.reached(2, 42_000, Duration::ZERO),
+            Some(PendingBuildLimit::Transactions)
+        );
+        assert_eq!(limits.reached(1, 30_000_000, Duration::ZERO), Some(PendingBuildLimit::Gas));
+        assert_eq!(
+            limits.reached(1, 21_000, Duration::from_millis(100)),
+            Some(PendingBuildLimit::Time)
+        );
+    }
+
+    #[test]
+    fn config_target_gas_fraction() {
+        let config = PendingBlockConfig::default();
+        assert_eq!(config.gas_target(30_000_000), 30_000_000);
+
+        let config = config.with_target_gas_fraction(Some(0.5)).unwrap();
+        assert_eq!(config.gas_target(30_000_000), 15_000_000);
+        assert_eq!(
+            config.clone().with_target_gas_fraction(Some(1.0)).unwrap().gas_target(100),
+            100
+        );
+
+        for invalid in [0.0, -0.5, 1.5, f64::NAN] {
+            assert!(config.clone().with_target_gas_fraction(Some(invalid)).is_err());
+        }
+        assert_eq!(config.with_target_gas_fraction(None).unwrap().target_gas_fraction(), None);
+    }
+
+    #[test]
+    fn config_deposit_gas_reserve() {
+        let config = PendingBlockConfig::default().with_deposit_gas_reserve(Some(1_000_000));
+        assert_eq!(config.gas_target(30_000_000), 29_000_000);
+        assert_eq!(config.gas_target(500_000), 0);
+
+        // the reserve is taken from the block gas limit, not from the target
+        let config = config.with_target_gas_fraction(Some(0.5)).unwrap();
+        assert_eq!(config.gas_target(30_000_000), 15_000_000);
+        assert_eq!(config.gas_target(1_500_000), 500_000);
+    }
+
+    #[test]
+    fn config_pending_gas_cap() {
+        let config = PendingBlockConfig::default().with_pending_gas_cap(Some(10_000_000));
+        assert_eq!(config.gas_target(30_000_000), 10_000_000);
+        assert_eq!(config.gas_target(5_000_000), 5_000_000);
+
+        // the strictest limit applies
+        let config = config.with_deposit_gas_reserve(Some(25_000_000));
+        assert_eq!(config.gas_target(30_000_000), 5_000_000);
+    }
+
+    #[test]
+    fn intrinsic_gas_of_forks() {
+        let tx = TxLegacy {
+            to: TxKind::Call(Address::ZERO),
+            input: Bytes::from(vec![1; 10]),
+            ..Default::default()
+        };
+        let create = TxLegacy { to: TxKind::Create, ..tx.clone() };
+
+        let chain_spec = ChainSpecBuilder::mainnet().build();
+        // frontier charges 68 gas per non-zero calldata byte
+        assert_eq!(intrinsic_gas(&chain_spec, 1, 0, &tx), 21_680);
+        assert_eq!(intrinsic_gas(&chain_spec, 1, 0, &create), 21_680);
+        // homestead charges creations
+        assert_eq!(intrinsic_gas(&chain_spec, 1_150_000, 0, &create), 53_680);
+        // istanbul charges 16 gas per non-zero calldata byte
+        assert_eq!(intrinsic_gas(&chain_spec, 9_069_000, 0, &tx), 21_160);
+
+        // berlin charges the entries of the access list
+        let access_list = AccessList(vec![AccessListItem {
+            address: Address::ZERO,
+            storage_keys: vec![B256::ZERO; 2],
+        }]);
+        let access_list_tx =
+            TxEip2930 { to: TxKind::Call(Address::ZERO), access_list, ..Default::default() };
+        assert_eq!(intrinsic_gas(&chain_spec, 9_069_000, 0, &access_list_tx), 21_000);
+        assert_eq!(intrinsic_gas(&chain_spec, 12_244_000, 0, &access_list_tx), 27_200);
+
+        // the calldata floor of prague is higher than the intrinsic gas
+        let chain_spec = ChainSpecBuilder::mainnet().prague_activated().build();
+        assert_eq!(intrinsic_gas(&chain_spec, 0, 0, &tx), 21_400);
+    }
+
+    #[test]
+    fn fork_ordering() {
+        let chain_spec = ChainSpecBuilder::mainnet().prague_activated().build();
+        assert!(ensure_fork_ordering(&chain_spec, 0).is_ok());
+
+        let chain_spec = ChainSpecBuilder::mainnet().shanghai_activated().build();
+        assert!(ensure_fork_ordering(&chain_spec, 0).is_ok());
+
+        // Prague without Shanghai
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .prague_activated()
+            .without_fork(EthereumHardfork::Shanghai)
+            .build();
+        let err = ensure_fork_ordering(&chain_spec, 0).unwrap_err();
+        assert!(matches!(
+            err,
+            EthApiError::ConflictingForkActivation {
+                timestamp: 0,
+                active: "Cancun",
+                inactive: "Shanghai"
+            }
+        ));
+    }
+
+    #[test]
+    fn diff_pending_against_canonical() {
+        let shared = transaction(0);
+        let only_pending = transaction(1);
+        let only_canonical = transaction(2);
+
+        let pending = block(vec![shared.clone(), only_pending.clone()], 42_000, 7);
+        let canonical = block(vec![shared, only_canonical.clone()], 63_000, 7);
+
+        let diff = PendingBlockDiff::new(&pending, &canonical);
+        assert_eq!(diff.number, 1);
+        assert_eq!(diff.included, 1);
+        assert_eq!(diff.missing, vec![*only_canonical.tx_hash()]);
+        assert_eq!(diff.unexpected, vec![*only_pending.tx_hash()]);
+        assert_eq!(diff.gas_used_delta, 21_000);
+        assert!(diff.base_fee_match);
+        assert!(!diff.is_exact());
+    }
+
+    #[test]
+    fn pending_accuracy_over_recent_heights() {
+        let mut accuracy = PendingAccuracy::new(2);
+        assert_eq!(accuracy.accuracy(), None);
+
+        let at = |number, pending: Vec<_>, canonical: Vec<_>| {
+            let mut diff = PendingBlockDiff::new(&block(pending, 0, 0), &block(canonical, 0, 0));
+            diff.number = number;
+            diff
+        };
+
+        // all 2 predicted transactions landed
+        accuracy.record(&at(
+            1,
+            vec![transaction(0), transaction(1)],
+            vec![transaction(0), transaction(1)],
+        ));
+        assert_eq!(accuracy.accuracy(), Some(1.0));
+
+        // 1 of 2 predicted transactions landed, a missing one doesn't count as predicted
+        accuracy.record(&at(
+            2,
+            vec![transaction(2), transaction(3)],
+            vec![transaction(2), transaction(4)],
+        ));
+        assert_eq!(accuracy.accuracy(), Some(0.75));
+
+        // the oldest height is dropped, none of the 2 predicted transactions landed
+        accuracy.record(&at(3, vec![transaction(5), transaction(6)], vec![]));
+        assert_eq!(accuracy.len(), 2);
+        assert_eq!(accuracy.accuracy(), Some(0.25));
+
+        // a reorged height replaces its previous record
+        accuracy.record(&at(
+            3,
+            vec![transaction(5), transaction(6)],
+            vec![transaction(5), transaction(6)],
+        ));
+        assert_eq!(accuracy.len(), 2);
+        assert_eq!(accuracy.accuracy(), Some(0.75));
+    }
+
+    #[test]
+    fn config_sender_allowlist() {
+        let allowed = Address::random();
+        let config = PendingBlockConfig::default();
+        assert!(config.is_sender_allowed(&allowed));
+
+        let config = config.with_sender_allowlist(Some(HashSet::from([allowed])));
+        assert!(config.is_sender_allowed(&allowed));
+        assert!(!config.is_sender_allowed(&Address::random()));
+    }
+
+    #[test]
+    fn config_max_unique_senders() {
+        let (first, second) = (Address::random(), Address::random());
+        let included = HashSet::from([first]);
+        assert!(!PendingBlockConfig::default().exceeds_max_unique_senders(&included, &second));
+
+        let config = PendingBlockConfig::default().with_max_unique_senders(Some(1));
+        assert!(!config.exceeds_max_unique_senders(&HashSet::new(), &second));
+        assert!(!config.exceeds_max_unique_senders(&included, &first));
+        assert!(config.exceeds_max_unique_senders(&included, &second));
+    }
+
+    #[test]
+    fn config_max_pending_tx_age() {
+        let now = Instant::now();
+        let added_at = now - Duration::from_secs(10);
+        assert!(!PendingBlockConfig::default().exceeds_max_pending_tx_age(added_at, now));
+
+        let config =
+            PendingBlockConfig::default().with_max_pending_tx_age(Some(Duration::from_secs(5)));
+        assert!(config.exceeds_max_pending_tx_age(added_at, now));
+        assert!(!config.exceeds_max_pending_tx_age(now - Duration::from_secs(5), now));
+        // transactions added after `now` have no age
+        assert!(!config.exceeds_max_pending_tx_age(now + Duration::from_secs(1), now));
+
+        let later = now + Duration::from_secs(60);
+        let config = config.with_pending_tx_clock(Some(PendingClock::new(move || later)));
+        assert_eq!(config.pending_tx_clock_now(), later);
+    }
+
+    #[test]
+    fn config_drop_noop_transfers() {
+        let sender = Address::random();
+        let noop = TxLegacy { to: TxKind::Call(sender), ..Default::default() };
+        assert!(!PendingBlockConfig::default().drops_noop_transfer(sender, &noop));
+
+        let config = PendingBlockConfig::default().with_drop_noop_transfers(true);
+        assert!(config.drops_noop_transfer(sender, &noop));
+        assert!(!config.drops_noop_transfer(Address::random(), &noop));
+        assert!(
+            !config.drops_noop_transfer(sender, &TxLegacy { value: U256::from(1), ..noop.clone() })
+        );
+        assert!(!config.drops_noop_transfer(
+            sender,
+            &TxLegacy { input: Bytes::from_static(&[1]), ..noop.clone() }
+        ));
+    }
+
+    #[test]
+    fn config_conversion_failure() {
+        // unconvertible transactions are skipped unless the build should fail on them
+        let config = PendingBlockConfig::default();
+        assert_eq!(config.conversion_failure, PendingConversionFailure::Skip);
+
+        let config = config.with_conversion_failure(PendingConversionFailure::Fail);
+        assert_eq!(config.conversion_failure, PendingConversionFailure::Fail);
+    }
+
+    #[test]
+    fn config_gas_limit_bounds() {
+        let parent_gas_limit = 30_000_000;
+        // 30_000_000 / 1024
+        let max_delta = 29_296;
+
+        assert_eq!(
+            PendingBlockConfig::default().next_gas_limit(parent_gas_limit).unwrap(),
+            parent_gas_limit
+        );
+
+        for gas_limit in [parent_gas_limit + max_delta - 1, parent_gas_limit - max_delta + 1] {
+            let config = PendingBlockConfig::default().with_gas_limit(Some(gas_limit));
+            assert_eq!(config.next_gas_limit(parent_gas_limit).unwrap(), gas_limit);
+        }
+
+        for gas_limit in [parent_gas_limit + max_delta, parent_gas_limit - max_delta] {
+            let config = PendingBlockConfig::default().with_gas_limit(Some(gas_limit));
+            assert!(matches!(
+                config.next_gas_limit(parent_gas_limit),
+                Err(EthApiError::PendingGasLimitOutOfBounds { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn pruned_parent_state() {
+        let err = pending_parent_state_error(10, ProviderError::StateAtBlockPruned(10));
+        assert!(matches!(err, EthApiError::PendingParentStatePruned(10)));
+        assert_eq!(
+            err.to_string(),
+            "state of pending block parent #10 is unavailable, it has been pruned"
+        );
+
+        let err = pending_parent_state_error(10, ProviderError::BestBlockNotFound);
+        assert!(matches!(err, EthApiError::HeaderNotFound(_)));
+    }
+
+    #[test]
+    fn selection_skips_duplicate_hashes() {
+        let mut selection = PendingBlockSelection::default();
+        let hash = TxHash::random();
+
+        let included = [hash, TxHash::random(), hash]
+            .into_iter()
+            .filter(|hash| selection.insert(*hash))
+            .collect::<Vec<_>>();
+
+        assert_eq!(included.len(), 2);
+        assert_eq!(included.iter().filter(|included| **included == hash).count(), 1);
+        assert!(selection.is_included(&hash));
+        assert_eq!(selection.len(), 2);
+    }
+
+    #[test]
+    fn cumulative_blob_gas_of_block() {
+        let blob_transaction = |blobs: usize| {
+            TransactionSigned::new_unhashed(
+                Transaction::Eip4844(TxEip4844 {
+                    blob_versioned_hashes: vec![B256::ZERO; blobs],
+                    ..Default::default()
+                }),
+                Signature::test_signature(),
+            )
+        };
+        let transactions =
+            [blob_transaction(2), transaction(0), blob_transaction(1), blob_transaction(3)];
+
+        let cumulative = cumulative_blob_gas(&transactions);
+        assert_eq!(
+            cumulative,
+            vec![2, 2, 3, 6].into_iter().map(|blobs| blobs * DATA_GAS_PER_BLOB).collect::<Vec<_>>()
+        );
+        assert!(cumulative.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(cumulative_blob_gas::<TransactionSigned>(&[]).is_empty());
+    }
 }
@@ -1,6 +1,6 @@
 //! utilities for working with revm
 
-use alloy_primitives::{Address, B256, U256};
+use alloy_primitives::{Address, Bytes, B256, U256};
 use alloy_rpc_types_eth::{
     state::{AccountOverride, StateOverride},
     BlockOverrides,
@@ -53,6 +53,19 @@ where
         .saturating_to())
 }
 
+/// The outcome of a call that ran to completion or reverted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallOutcome {
+    /// The return data of the call, or the revert data if it reverted.
+    pub output: Bytes,
+    /// The gas used by the call.
+    pub gas_used: u64,
+    /// Whether the call reverted.
+    pub reverted: bool,
+    /// The revert reason decoded from the output, if the call reverted with a known reason.
+    pub revert_reason: Option<String>,
+}
+
 /// Helper type for representing the fees of a `TransactionRequest`
 #[derive(Debug)]
 pub struct CallFees {
@@ -34,10 +34,12 @@ use reth_revm::{
 };
 use reth_rpc_api::DebugApiServer;
 use reth_rpc_eth_api::{
-    helpers::{EthTransactions, TraceExt},
+    helpers::{EthTransactions, LoadPendingBlock, SpawnBlocking, TraceExt},
     EthApiTypes, FromEthApiError, RpcNodeCore,
 };
-use reth_rpc_eth_types::{EthApiError, StateCacheDb};
+use reth_rpc_eth_types::{
+    EthApiError, PendingBlockPolicy, PendingBlockSummary, PendingBuildTimings, StateCacheDb,
+};
 use reth_rpc_server_types::{result::internal_rpc_err, ToRpcResult};
 use reth_tasks::pool::BlockingTaskGuard;
 use revm::{context_interface::Transaction, state::EvmState, DatabaseCommit};
@@ -654,6 +656,34 @@ where
             .await
     }
 
+    /// Builds one candidate pending block on top of the latest block per given policy and returns
+    /// their compositions, see [`LoadPendingBlock::build_candidate_blocks`].
+    ///
+    /// The candidates are only built for inspection and don't replace the pending block.
+    pub async fn debug_pending_block_candidates(
+        &self,
+        policies: Vec<PendingBlockPolicy>,
+    ) -> Result<Vec<(PendingBlockPolicy, PendingBlockSummary)>, Eth::Error> {
+        self.eth_api()
+            .spawn_blocking_io(move |eth_api| {
+                let parent = eth_api
+                    .provider()
+                    .latest_header()
+                    .map_err(Eth::Error::from_eth_err)?
+                    .ok_or(EthApiError::HeaderNotFound(BlockNumberOrTag::Latest.into()))?;
+                eth_api.build_candidate_blocks(&parent, &policies)
+            })
+            .await
+    }
+
+    /// Rebuilds the local pending block and returns the time spent in each phase of the build,
+    /// see [`LoadPendingBlock::rebuild_pending_block_timed`].
+    pub async fn debug_rebuild_pending_block_timed(
+        &self,
+    ) -> Result<PendingBuildTimings, Eth::Error> {
+        self.eth_api().rebuild_pending_block_timed().await
+    }
+
     /// Returns the code associated with a given hash at the specified block ID. If no code is
     /// found, it returns None. If no block ID is provided, it defaults to the latest block.
     pub async fn debug_code_by_hash(
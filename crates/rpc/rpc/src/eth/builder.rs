@@ -8,9 +8,11 @@ use reth_node_api::NodePrimitives;
 use reth_provider::{
     BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, StateProviderFactory,
 };
+use reth_rpc_eth_api::helpers::{LoadPendingBlock, SpawnBlocking};
 use reth_rpc_eth_types::{
-    fee_history::fee_history_cache_new_blocks_task, EthStateCache, FeeHistoryCache,
-    FeeHistoryCacheConfig, GasCap, GasPriceOracle,
+    fee_history::fee_history_cache_new_blocks_task, pending_block::pending_accuracy_task,
+    EthStateCache, FeeHistoryCache, FeeHistoryCacheConfig, GasCap, GasPriceOracle,
+    PendingBlockConfig,
 };
 use reth_rpc_server_types::constants::{
     DEFAULT_ETH_PROOF_WINDOW, DEFAULT_MAX_SIMULATE_BLOCKS, DEFAULT_PROOF_PERMITS,
@@ -36,6 +38,7 @@ where
     eth_proof_window: u64,
     fee_history_cache_config: FeeHistoryCacheConfig,
     proof_permits: usize,
+    pending_block_config: PendingBlockConfig,
     eth_cache: Option<EthStateCache<Provider::Block, Provider::Receipt>>,
     gas_oracle: Option<GasPriceOracle<Provider>>,
     blocking_task_pool: Option<BlockingTaskPool>,
@@ -64,6 +67,7 @@ where
             blocking_task_pool: None,
             fee_history_cache_config: FeeHistoryCacheConfig::default(),
             proof_permits: DEFAULT_PROOF_PERMITS,
+            pending_block_config: PendingBlockConfig::default(),
             task_spawner: TokioTaskExecutor::default().boxed(),
         }
     }
@@ -128,10 +132,19 @@ where
         self
     }
 
+    /// Sets the configuration for building the local pending block.
+    pub fn pending_block_config(mut self, pending_block_config: PendingBlockConfig) -> Self {
+        self.pending_block_config = pending_block_config;
+        self
+    }
+
     /// Builds the [`EthApiInner`] instance.
     ///
     /// If not configured, this will spawn the cache backend: [`EthStateCache::spawn`].
     ///
+    /// This also spawns [`pending_accuracy_task`], which tracks the accuracy of the local pending
+    /// blocks, see [`EthApiInner::pending_accuracy`].
+    ///
     /// # Panics
     ///
     /// This function panics if the blocking task pool cannot be built.
@@ -160,6 +173,7 @@ where
             blocking_task_pool,
             fee_history_cache_config,
             proof_permits,
+            pending_block_config,
             task_spawner,
         } = self;
 
@@ -180,8 +194,8 @@ where
             }),
         );
 
-        EthApiInner::new(
-            provider,
+        let inner = EthApiInner::new(
+            provider.clone(),
             pool,
             network,
             eth_cache,
@@ -196,13 +210,27 @@ where
             evm_config,
             task_spawner,
             proof_permits,
-        )
+            pending_block_config,
+        );
+
+        let new_canonical_blocks = provider.canonical_state_stream();
+        let pending_block = inner.pending_block.clone();
+        let accuracy = inner.pending_accuracy.clone();
+        inner.task_spawner().spawn(Box::pin(async move {
+            pending_accuracy_task(pending_block, accuracy, new_canonical_blocks).await;
+        }));
+
+        inner
     }
 
     /// Builds the [`EthApi`] instance.
     ///
     /// If not configured, this will spawn the cache backend: [`EthStateCache::spawn`].
     ///
+    /// If [`PendingBlockConfig::rebuild_on_pool_events`] is set, this also spawns the task that
+    /// rebuilds the pending block whenever a transaction enters the pending pool, see
+    /// [`LoadPendingBlock::rebuild_pending_block_on_pool_events`].
+    ///
     /// # Panics
     ///
     /// This function panics if the blocking task pool cannot be built.
@@ -217,7 +245,15 @@ where
             + Clone
             + Unpin
             + 'static,
+        EthApi<Provider, Pool, Network, EvmConfig>: LoadPendingBlock + SpawnBlocking,
     {
-        EthApi { inner: Arc::new(self.build_inner()), tx_resp_builder: EthTxBuilder }
+        let eth_api = EthApi { inner: Arc::new(self.build_inner()), tx_resp_builder: EthTxBuilder };
+
+        if eth_api.inner.pending_block_config().rebuild_on_pool_events {
+            let rebuild = eth_api.clone().rebuild_pending_block_on_pool_events();
+            eth_api.inner.task_spawner().spawn(Box::pin(rebuild));
+        }
+
+        eth_api
     }
 }
@@ -10,6 +10,7 @@ use alloy_network::Ethereum;
 use alloy_primitives::{Bytes, U256};
 use derive_more::Deref;
 use reth_node_api::{FullNodeComponents, FullNodeTypes};
+use reth_primitives::RecoveredBlock;
 use reth_provider::{
     BlockReader, BlockReaderIdExt, NodePrimitivesProvider, ProviderBlock, ProviderReceipt,
 };
@@ -19,7 +20,8 @@ use reth_rpc_eth_api::{
     EthApiTypes, RpcNodeCore,
 };
 use reth_rpc_eth_types::{
-    EthApiError, EthStateCache, FeeHistoryCache, GasCap, GasPriceOracle, PendingBlock,
+    EthApiError, EthStateCache, FeeHistoryCache, GasCap, GasPriceOracle, PendingAccuracy,
+    PendingBlock, PendingBlockConfig,
 };
 use reth_tasks::{
     pool::{BlockingTaskGuard, BlockingTaskPool},
@@ -116,6 +118,9 @@ where
     }
 
     /// Creates a new, shareable instance using the default tokio task spawner.
+    ///
+    /// This does not track the accuracy of the local pending blocks, see
+    /// [`EthApiInner::pending_accuracy`]; use [`EthApi::builder`] for that.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         provider: Provider,
@@ -145,6 +150,7 @@ where
             evm_config,
             TokioTaskExecutor::default().boxed(),
             proof_permits,
+            PendingBlockConfig::default(),
         );
 
         Self { inner: Arc::new(inner), tx_resp_builder: EthTxBuilder }
@@ -272,7 +278,11 @@ pub struct EthApiInner<Provider: BlockReader, Pool, Network, EvmConfig> {
     /// The type that can spawn tasks which would otherwise block.
     task_spawner: Box<dyn TaskSpawner>,
     /// Cached pending block if any
-    pending_block: Mutex<Option<PendingBlock<Provider::Block, Provider::Receipt>>>,
+    pub(super) pending_block: Arc<Mutex<Option<PendingBlock<Provider::Block, Provider::Receipt>>>>,
+    /// Rolling accuracy of the locally built pending blocks
+    pub(super) pending_accuracy: Arc<parking_lot::Mutex<PendingAccuracy>>,
+    /// Configuration for building the local pending block
+    pending_block_config: PendingBlockConfig,
     /// A pool dedicated to CPU heavy blocking tasks.
     blocking_task_pool: BlockingTaskPool,
     /// Cache for block fees history
@@ -285,6 +295,8 @@ pub struct EthApiInner<Provider: BlockReader, Pool, Network, EvmConfig> {
 
     /// Transaction broadcast channel
     raw_tx_sender: broadcast::Sender<Bytes>,
+    /// Broadcast channel of the locally built pending blocks
+    pending_block_sender: broadcast::Sender<Arc<RecoveredBlock<Provider::Block>>>,
 }
 
 impl<Provider, Pool, Network, EvmConfig> EthApiInner<Provider, Pool, Network, EvmConfig>
@@ -292,6 +304,9 @@ where
     Provider: BlockReaderIdExt,
 {
     /// Creates a new, shareable instance using the default tokio task spawner.
+    ///
+    /// This does not spawn the task that tracks the accuracy of the local pending blocks, see
+    /// [`EthApiInner::pending_accuracy`].
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         provider: Provider,
@@ -307,6 +322,7 @@ where
         evm_config: EvmConfig,
         task_spawner: Box<dyn TaskSpawner + 'static>,
         proof_permits: usize,
+        pending_block_config: PendingBlockConfig,
     ) -> Self {
         let signers = parking_lot::RwLock::new(Default::default());
         // get the block number of the latest block
@@ -320,6 +336,7 @@ where
         );
 
         let (raw_tx_sender, _) = broadcast::channel(DEFAULT_BROADCAST_CAPACITY);
+        let (pending_block_sender, _) = broadcast::channel(DEFAULT_BROADCAST_CAPACITY);
 
         Self {
             provider,
@@ -334,11 +351,14 @@ where
             starting_block,
             task_spawner,
             pending_block: Default::default(),
+            pending_accuracy: Default::default(),
+            pending_block_config,
             blocking_task_pool,
             fee_history_cache,
             evm_config,
             blocking_task_guard: BlockingTaskGuard::new(proof_permits),
             raw_tx_sender,
+            pending_block_sender,
         }
     }
 }
@@ -361,12 +381,39 @@ where
 
     /// Returns a handle to the pending block.
     #[inline]
-    pub const fn pending_block(
+    pub fn pending_block(
         &self,
     ) -> &Mutex<Option<PendingBlock<Provider::Block, Provider::Receipt>>> {
         &self.pending_block
     }
 
+    /// Returns the sender the locally built pending block is broadcast with each time it's
+    /// rebuilt.
+    #[inline]
+    pub const fn pending_block_sender(
+        &self,
+    ) -> &broadcast::Sender<Arc<RecoveredBlock<Provider::Block>>> {
+        &self.pending_block_sender
+    }
+
+    /// Returns the rolling accuracy of the locally built pending blocks.
+    ///
+    /// This is only updated by instances built with [`EthApiBuilder`], which spawns
+    /// [`pending_accuracy_task`]. Instances created with [`EthApi::new`] or
+    /// [`EthApiInner::new`] never record any accuracy.
+    ///
+    /// [`pending_accuracy_task`]: reth_rpc_eth_types::pending_block::pending_accuracy_task
+    #[inline]
+    pub fn pending_accuracy(&self) -> &parking_lot::Mutex<PendingAccuracy> {
+        &self.pending_accuracy
+    }
+
+    /// Returns the configuration for building the local pending block.
+    #[inline]
+    pub const fn pending_block_config(&self) -> &PendingBlockConfig {
+        &self.pending_block_config
+    }
+
     /// Returns a handle to the task spawner.
     #[inline]
     pub const fn task_spawner(&self) -> &dyn TaskSpawner {
@@ -477,7 +524,10 @@ mod tests {
         BlockReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
         StateProviderFactory,
     };
-    use reth_rpc_eth_api::EthApiServer;
+    use reth_rpc_eth_api::{
+        helpers::{LoadPendingBlock, SpawnBlocking},
+        EthApiServer,
+    };
     use reth_testing_utils::{generators, generators::Rng};
     use reth_transaction_pool::test_utils::{testing_pool, TestPool};
 
@@ -495,7 +545,10 @@ mod tests {
             + 'static,
     >(
         provider: P,
-    ) -> EthApi<P, TestPool, NoopNetwork, EthEvmConfig> {
+    ) -> EthApi<P, TestPool, NoopNetwork, EthEvmConfig>
+    where
+        EthApi<P, TestPool, NoopNetwork, EthEvmConfig>: LoadPendingBlock + SpawnBlocking,
+    {
         EthApiBuilder::new(
             provider.clone(),
             testing_pool(),
@@ -3,8 +3,8 @@
 use alloy_consensus::BlockHeader;
 use alloy_primitives::TxHash;
 use alloy_rpc_types_eth::{
-    BlockNumHash, Filter, FilterBlockOption, FilterChanges, FilterId, FilteredParams, Log,
-    PendingTransactionFilterKind,
+    BlockNumHash, BlockNumberOrTag, Filter, FilterBlockOption, FilterChanges, FilterId,
+    FilteredParams, Log, PendingTransactionFilterKind,
 };
 use async_trait::async_trait;
 use jsonrpsee::{core::RpcResult, server::IdProvider};
@@ -15,6 +15,7 @@ use reth_provider::{
     ProviderError, ProviderReceipt,
 };
 use reth_rpc_eth_api::{
+    helpers::{LoadPendingBlock, SpawnBlocking},
     EthApiTypes, EthFilterApiServer, FullEthApiTypes, RpcNodeCoreExt, RpcTransaction,
     TransactionCompat,
 };
@@ -242,7 +243,10 @@ where
     /// Returns an error if no matching log filter exists.
     ///
     /// Handler for `eth_getFilterLogs`
-    pub async fn filter_logs(&self, id: FilterId) -> Result<Vec<Log>, EthFilterError> {
+    pub async fn filter_logs(&self, id: FilterId) -> Result<Vec<Log>, EthFilterError>
+    where
+        Eth: LoadPendingBlock + SpawnBlocking,
+    {
         let filter = {
             let filters = self.inner.active_filters.inner.lock().await;
             if let FilterKind::Log(ref filter) =
@@ -262,7 +266,11 @@ where
 #[async_trait]
 impl<Eth> EthFilterApiServer<RpcTransaction<Eth::NetworkTypes>> for EthFilter<Eth>
 where
-    Eth: FullEthApiTypes + RpcNodeCoreExt<Provider: BlockIdReader> + 'static,
+    Eth: FullEthApiTypes
+        + RpcNodeCoreExt<Provider: BlockIdReader>
+        + LoadPendingBlock
+        + SpawnBlocking
+        + 'static,
 {
     /// Handler for `eth_newFilter`
     async fn new_filter(&self, filter: Filter) -> RpcResult<FilterId> {
@@ -396,7 +404,10 @@ where
     }
 
     /// Returns logs matching given filter object.
-    async fn logs_for_filter(&self, filter: Filter) -> Result<Vec<Log>, EthFilterError> {
+    async fn logs_for_filter(&self, filter: Filter) -> Result<Vec<Log>, EthFilterError>
+    where
+        Eth: LoadPendingBlock + SpawnBlocking,
+    {
         match filter.block_option {
             FilterBlockOption::AtBlockHash(block_hash) => {
                 // for all matching logs in the block
@@ -434,6 +445,25 @@ where
                 Ok(all_logs)
             }
             FilterBlockOption::Range { from_block, to_block } => {
+                // the pending block is not known to the provider, so its logs are taken from the
+                // local pending block
+                let from_pending = from_block.is_some_and(|num| num.is_pending());
+                if from_pending && to_block.is_some_and(|num| !num.is_pending()) {
+                    // a range must not start after it ends
+                    return Err(EthFilterError::InvalidBlockRangeParams)
+                }
+                let pending_logs = if from_pending || to_block.is_some_and(|num| num.is_pending()) {
+                    self.eth_api
+                        .pending_logs(filter.clone())
+                        .await
+                        .map_err(|err| EthFilterError::Rpc(err.into()))?
+                } else {
+                    None
+                };
+                if from_pending {
+                    return Ok(pending_logs.unwrap_or_default())
+                }
+
                 // compute the range
                 let info = self.provider().chain_info()?;
 
@@ -443,14 +473,21 @@ where
                     .map(|num| self.provider().convert_block_number(num))
                     .transpose()?
                     .flatten();
+                // a range up to the pending block covers all sealed blocks up to the latest
                 let to = to_block
-                    .map(|num| self.provider().convert_block_number(num))
+                    .map(|num| {
+                        let num = if num.is_pending() { BlockNumberOrTag::Latest } else { num };
+                        self.provider().convert_block_number(num)
+                    })
                     .transpose()?
                     .flatten();
                 let (from_block_number, to_block_number) =
                     logs_utils::get_filter_block_range(from, to, start_block, info);
-                self.get_logs_in_block_range(&filter, from_block_number, to_block_number, info)
-                    .await
+                let mut logs = self
+                    .get_logs_in_block_range(&filter, from_block_number, to_block_number, info)
+                    .await?;
+                logs.extend(pending_logs.into_iter().flatten());
+                Ok(logs)
             }
         }
     }
@@ -768,6 +805,9 @@ pub enum EthFilterError {
     /// Error serving request in `eth_` namespace.
     #[error(transparent)]
     EthAPIError(#[from] EthApiError),
+    /// Error of the `eth_` API while building the pending block.
+    #[error("{}", .0.message())]
+    Rpc(jsonrpsee::types::error::ErrorObject<'static>),
     /// Error thrown when a spawned task failed to deliver a response.
     #[error("internal filter error")]
     InternalError,
@@ -784,6 +824,7 @@ impl From<EthFilterError> for jsonrpsee::types::error::ErrorObject<'static> {
                 rpc_error_with_code(jsonrpsee::types::error::INTERNAL_ERROR_CODE, err.to_string())
             }
             EthFilterError::EthAPIError(err) => err.into(),
+            EthFilterError::Rpc(err) => err,
             err @ (EthFilterError::InvalidBlockRangeParams |
             EthFilterError::QueryExceedsMaxBlocks(_) |
             EthFilterError::QueryExceedsMaxResults { .. }) => {
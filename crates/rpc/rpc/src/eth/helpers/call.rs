@@ -145,3 +145,207 @@ where
     Provider: BlockReader,
 {
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::EthApiBuilder;
+    use alloy_consensus::Header;
+    use alloy_eips::{
+        eip4844::{calc_blob_gasprice, TARGET_DATA_GAS_PER_BLOCK},
+        BlockId,
+    };
+    use alloy_primitives::{hex, Address, B256, U256};
+    use alloy_rpc_types::{state::EvmOverrides, BlockOverrides, TransactionRequest};
+    use reth_chainspec::ChainSpecBuilder;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::Block;
+    use reth_provider::{
+        test_utils::{ExtendedAccount, MockEthProvider},
+        ChainSpecProvider,
+    };
+    use reth_rpc_eth_api::helpers::EthCall;
+    use reth_transaction_pool::test_utils::testing_pool;
+
+    #[tokio::test]
+    async fn test_call_batch_isolated_calls() {
+        let provider = MockEthProvider::default();
+        provider.add_block(
+            B256::random(),
+            Block {
+                header: Header { gas_limit: 30_000_000, ..Default::default() },
+                body: Default::default(),
+            },
+        );
+
+        let sender = Address::random();
+        let balance = U256::from(1_000_000u64);
+        provider.add_account(sender, ExtendedAccount::new(0, balance));
+
+        let eth_api = EthApiBuilder::new(
+            provider.clone(),
+            testing_pool(),
+            NoopNetwork::default(),
+            EthEvmConfig::new(provider.chain_spec()),
+        )
+        .build();
+
+        // every call transfers the entire balance of the sender, which only succeeds for all of
+        // them if the calls don't observe each other's state changes
+        let requests = (0..3)
+            .map(|_| {
+                TransactionRequest::default().from(sender).to(Address::random()).value(balance)
+            })
+            .collect();
+
+        let results = eth_api
+            .call_batch(requests, Some(BlockId::pending()), EvmOverrides::default())
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 3);
+        for result in results {
+            assert_eq!(result.error, None);
+            assert_eq!(result.value, Some(Default::default()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_call_pending_blob_base_fee() {
+        let provider = MockEthProvider::default()
+            .with_chain_spec(ChainSpecBuilder::mainnet().cancun_activated().build());
+        let excess_blob_gas = 10_000_000;
+        provider.add_block(
+            B256::random(),
+            Block {
+                header: Header {
+                    gas_limit: 30_000_000,
+                    blob_gas_used: Some(0),
+                    excess_blob_gas: Some(excess_blob_gas),
+                    parent_beacon_block_root: Some(B256::ZERO),
+                    ..Default::default()
+                },
+                body: Default::default(),
+            },
+        );
+
+        // BLOBBASEFEE PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+        let contract = Address::random();
+        provider.add_account(
+            contract,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(hex!("4a60005260206000f3").into()),
+        );
+
+        let eth_api = EthApiBuilder::new(
+            provider.clone(),
+            testing_pool(),
+            NoopNetwork::default(),
+            EthEvmConfig::new(provider.chain_spec()),
+        )
+        .build();
+
+        let output = eth_api
+            .call(
+                TransactionRequest::default().to(contract),
+                Some(BlockId::pending()),
+                EvmOverrides::default(),
+            )
+            .await
+            .unwrap();
+
+        // the parent didn't use any blob gas, so the excess decreases by the target
+        let expected = calc_blob_gasprice(excess_blob_gas - TARGET_DATA_GAS_PER_BLOCK);
+        assert!(expected > 1);
+        assert_eq!(U256::from_be_slice(&output), U256::from(expected));
+    }
+
+    #[tokio::test]
+    async fn test_call_pending_outcome_revert_reason() {
+        let provider = MockEthProvider::default();
+        provider.add_block(
+            B256::random(),
+            Block {
+                header: Header { gas_limit: 30_000_000, ..Default::default() },
+                body: Default::default(),
+            },
+        );
+
+        // reverts with `Error("nope")`
+        let revert_data = hex!(
+            "08c379a0"
+            "0000000000000000000000000000000000000000000000000000000000000020"
+            "0000000000000000000000000000000000000000000000000000000000000004"
+            "6e6f706500000000000000000000000000000000000000000000000000000000"
+        );
+        // PUSH1 100 PUSH1 11 PUSH1 0 CODECOPY PUSH1 100 PUSH1 0 REVERT, followed by the data
+        let code = [hex!("6064600b60003960646000fd").as_slice(), revert_data.as_slice()].concat();
+        let contract = Address::random();
+        provider
+            .add_account(contract, ExtendedAccount::new(0, U256::ZERO).with_bytecode(code.into()));
+
+        let eth_api = EthApiBuilder::new(
+            provider.clone(),
+            testing_pool(),
+            NoopNetwork::default(),
+            EthEvmConfig::new(provider.chain_spec()),
+        )
+        .build();
+
+        let outcome = eth_api
+            .call_pending_outcome(
+                TransactionRequest::default().to(contract),
+                EvmOverrides::default(),
+            )
+            .await
+            .unwrap();
+
+        assert!(outcome.reverted);
+        assert_eq!(outcome.output.as_ref(), revert_data.as_slice());
+        assert_eq!(outcome.revert_reason.as_deref(), Some("nope"));
+        assert!(outcome.gas_used > 21_000);
+    }
+
+    #[tokio::test]
+    async fn test_call_prevrandao_override() {
+        let provider = MockEthProvider::default()
+            .with_chain_spec(ChainSpecBuilder::mainnet().shanghai_activated().build());
+        provider.add_block(
+            B256::random(),
+            Block {
+                header: Header { gas_limit: 30_000_000, ..Default::default() },
+                body: Default::default(),
+            },
+        );
+
+        // PREVRANDAO PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+        let contract = Address::random();
+        provider.add_account(
+            contract,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(hex!("4460005260206000f3").into()),
+        );
+
+        let eth_api = EthApiBuilder::new(
+            provider.clone(),
+            testing_pool(),
+            NoopNetwork::default(),
+            EthEvmConfig::new(provider.chain_spec()),
+        )
+        .build();
+
+        let prevrandao = B256::random();
+        let overrides = EvmOverrides::block(Some(Box::new(BlockOverrides {
+            random: Some(prevrandao),
+            ..Default::default()
+        })));
+
+        let request = TransactionRequest::default().to(contract);
+        let output =
+            eth_api.call(request.clone(), Some(BlockId::pending()), overrides).await.unwrap();
+        assert_eq!(output.as_ref(), prevrandao.as_slice());
+
+        // the override only applies to the call it was provided for
+        let output =
+            eth_api.call(request, Some(BlockId::pending()), EvmOverrides::default()).await.unwrap();
+        assert_ne!(output.as_ref(), prevrandao.as_slice());
+    }
+}
@@ -4,26 +4,28 @@ use alloy_consensus::{
     constants::EMPTY_WITHDRAWALS, transaction::Recovered, BlockHeader, Header, Transaction,
     EMPTY_OMMER_ROOT_HASH,
 };
-use alloy_eips::{eip7685::EMPTY_REQUESTS_HASH, merge::BEACON_NONCE};
-use alloy_primitives::U256;
+use alloy_eips::eip7685::EMPTY_REQUESTS_HASH;
+use alloy_primitives::{Address, U256};
 use reth_chainspec::{EthChainSpec, EthereumHardforks};
 use reth_evm::{execute::BlockExecutionStrategyFactory, NextBlockEnvAttributes};
 use reth_node_api::NodePrimitives;
-use reth_primitives::{logs_bloom, BlockBody, Receipt, SealedHeader};
+use reth_primitives::{logs_bloom, BlockBody, Receipt, RecoveredBlock, SealedHeader};
 use reth_primitives_traits::proofs::calculate_transaction_root;
 use reth_provider::{
     BlockExecutionResult, BlockReader, BlockReaderIdExt, ChainSpecProvider, ProviderBlock,
-    ProviderHeader, ProviderReceipt, ProviderTx, StateProviderFactory,
+    ProviderHeader, ProviderReceipt, ProviderTx, PruneCheckpointReader, StateProviderFactory,
 };
 use reth_rpc_eth_api::{
     helpers::{LoadPendingBlock, SpawnBlocking},
     types::RpcTypes,
-    FromEvmError, RpcNodeCore,
+    FromEthApiError, FromEvmError, RpcNodeCore,
 };
-use reth_rpc_eth_types::PendingBlock;
+use reth_rpc_eth_types::{ensure_fork_ordering, PendingBlock, PendingBlockConfig};
 use reth_transaction_pool::{PoolTransaction, TransactionPool};
 use revm::{context::BlockEnv, context_interface::Block};
 use revm_primitives::B256;
+use std::sync::Arc;
+use tokio::sync::broadcast;
 
 use crate::EthApi;
 
@@ -40,7 +42,8 @@ where
                 Receipt = reth_primitives::Receipt,
                 Header = reth_primitives::Header,
             > + ChainSpecProvider<ChainSpec: EthChainSpec + EthereumHardforks>
-                          + StateProviderFactory,
+                          + StateProviderFactory
+                          + PruneCheckpointReader,
             Pool: TransactionPool<
                 Transaction: PoolTransaction<Consensus = ProviderTx<Self::Provider>>,
             >,
@@ -64,16 +67,44 @@ where
         self.inner.pending_block()
     }
 
-    fn next_env_attributes(
+    #[inline]
+    fn pending_block_config(&self) -> &PendingBlockConfig {
+        self.inner.pending_block_config()
+    }
+
+    #[inline]
+    fn pending_block_sender(
+        &self,
+    ) -> Option<&broadcast::Sender<Arc<RecoveredBlock<ProviderBlock<Self::Provider>>>>> {
+        Some(self.inner.pending_block_sender())
+    }
+
+    fn next_env_attributes_with_fee_recipient(
         &self,
         parent: &SealedHeader<ProviderHeader<Self::Provider>>,
+        fee_recipient: Option<Address>,
     ) -> Result<<Self::Evm as reth_evm::ConfigureEvmEnv>::NextBlockEnvCtx, Self::Error> {
+        let block_time = self.pending_block_config().block_time.unwrap_or(12);
+        let timestamp = parent.timestamp().saturating_add(block_time);
+
+        // a pre-Cancun parent, e.g. the genesis block of a chain that activates Cancun later,
+        // doesn't have a beacon block root
+        let parent_beacon_block_root = parent.parent_beacon_block_root().or_else(|| {
+            self.provider()
+                .chain_spec()
+                .is_cancun_active_at_timestamp(timestamp)
+                .then_some(B256::ZERO)
+        });
+
         Ok(NextBlockEnvAttributes {
-            timestamp: parent.timestamp().saturating_add(12),
-            suggested_fee_recipient: parent.beneficiary(),
+            timestamp,
+            suggested_fee_recipient: fee_recipient.unwrap_or_else(|| parent.beneficiary()),
             prev_randao: B256::random(),
-            gas_limit: parent.gas_limit(),
-            parent_beacon_block_root: parent.parent_beacon_block_root(),
+            gas_limit: self
+                .pending_block_config()
+                .next_gas_limit(parent.gas_limit())
+                .map_err(Self::Error::from_eth_err)?,
+            parent_beacon_block_root,
             withdrawals: None,
         })
     }
@@ -85,7 +116,7 @@ where
         parent: &SealedHeader<ProviderHeader<Self::Provider>>,
         state_root: revm_primitives::B256,
         transactions: Vec<Recovered<ProviderTx<Self::Provider>>>,
-    ) -> reth_provider::ProviderBlock<Self::Provider> {
+    ) -> Result<reth_provider::ProviderBlock<Self::Provider>, Self::Error> {
         let chain_spec = self.provider().chain_spec();
 
         let transactions_root = calculate_transaction_root(&transactions);
@@ -94,6 +125,8 @@ where
         let logs_bloom = logs_bloom(result.receipts.iter().flat_map(|r| &r.logs));
 
         let timestamp = block_env.timestamp;
+        ensure_fork_ordering(&*chain_spec, timestamp).map_err(Self::Error::from_eth_err)?;
+
         let is_shanghai = chain_spec.is_shanghai_active_at_timestamp(timestamp);
         let is_cancun = chain_spec.is_cancun_active_at_timestamp(timestamp);
         let is_prague = chain_spec.is_prague_active_at_timestamp(timestamp);
@@ -109,7 +142,7 @@ where
             logs_bloom,
             timestamp: block_env.timestamp,
             mix_hash: block_env.prevrandao.unwrap_or_default(),
-            nonce: BEACON_NONCE.into(),
+            nonce: chain_spec.block_nonce().into(),
             base_fee_per_gas: Some(block_env.basefee),
             number: block_env.number,
             gas_limit: block_env.gas_limit,
@@ -119,19 +152,2419 @@ where
                 transactions.iter().map(|tx| tx.blob_gas_used().unwrap_or_default()).sum::<u64>()
             }),
             excess_blob_gas: block_env.blob_excess_gas(),
-            extra_data: Default::default(),
+            extra_data: self
+                .pending_block_config()
+                .stamped_extra_data(block_env.timestamp)
+                .unwrap_or_default(),
             parent_beacon_block_root: is_cancun.then_some(B256::ZERO),
             requests_hash: is_prague.then_some(EMPTY_REQUESTS_HASH),
         };
 
         // seal the block
-        reth_primitives::Block {
+        Ok(reth_primitives::Block {
             header,
             body: BlockBody {
                 transactions: transactions.into_iter().map(|tx| tx.into_tx()).collect(),
                 ommers: vec![],
-                withdrawals: None,
+                withdrawals: is_shanghai.then(Default::default),
             },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EthApi, EthApiBuilder, EthFilter};
+    use alloy_consensus::{
+        constants::{EMPTY_ROOT_HASH, EMPTY_WITHDRAWALS, MAXIMUM_EXTRA_DATA_SIZE},
+        Header, Transaction as _,
+    };
+    use alloy_eips::{
+        eip1559::INITIAL_BASE_FEE,
+        eip2930::{AccessList, AccessListItem},
+        eip4844::{
+            BlobAndProofV1, BlobTransactionSidecar, DATA_GAS_PER_BLOB, VERSIONED_HASH_VERSION_KZG,
+        },
+        BlockId,
+    };
+    use alloy_primitives::{hex, keccak256, Address, Bloom, BloomInput, Bytes, TxKind, B256, U256};
+    use alloy_rlp::Decodable;
+    use alloy_rpc_types_eth::{BlockNumberOrTag, Filter};
+    use alloy_serde::JsonStorageKey;
+    use futures::StreamExt;
+    use reth_chainspec::{ChainSpec, ChainSpecBuilder, EthereumHardfork, ForkCondition};
+    use reth_consensus_common::calc::base_block_reward;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::{Account, Block, RecoveredBlock};
+    use reth_primitives_traits::SignedTransaction;
+    use reth_provider::{
+        test_utils::{ExtendedAccount, MockEthProvider},
+        BlockReaderIdExt, ChainSpecProvider, ProviderError, StateProvider,
+    };
+    use reth_prune_types::{PruneCheckpoint, PruneMode, PruneSegment};
+    use reth_rpc_eth_api::{
+        helpers::{EthBlocks, EthCall, EthState, EthTransactions, LoadPendingBlock},
+        EthFilterApiServer,
+    };
+    use reth_rpc_eth_types::{
+        EthApiError, PendingBlockConfig, PendingBlockPolicy, PendingBuildLimit, PendingClock,
+        PendingExclusion, PendingExclusionReason, PendingPoolSnapshot, PendingTransactionFee,
+        PendingTransactionOrdering, PendingValidityCheck, MAX_PENDING_EXCLUSIONS,
+    };
+    use reth_tasks::TokioTaskExecutor;
+    use reth_transaction_pool::{
+        blobstore::{BlobStore, BlobStoreCleanupStat, BlobStoreError, InMemoryBlobStore},
+        noop::MockTransactionValidator,
+        test_utils::{testing_pool, MockOrdering, MockTransaction, TestPool},
+        Pool, PoolTransaction, TransactionOrigin, TransactionPool, TransactionPoolExt,
+    };
+    use reth_trie_common::{AccountProof, StorageProof};
+    use revm::{
+        context_interface::ContextTr,
+        inspector::Inspector,
+        interpreter::{interpreter::EthInterpreter, CallInputs, CallOutcome},
+        Database,
+    };
+    use std::{
+        collections::{BTreeMap, HashMap, HashSet},
+        num::NonZeroU128,
+        sync::{mpsc, Arc, Mutex},
+        time::{Duration, Instant},
+    };
+    use tracing::{
+        span::{Attributes, Id},
+        Subscriber,
+    };
+    use tracing_subscriber::{
+        layer::{Context, SubscriberExt},
+        Layer,
+    };
+
+    type TestEthApi = EthApi<MockEthProvider, TestPool, NoopNetwork, EthEvmConfig>;
+
+    /// Records the names of all spans created for the pending build.
+    #[derive(Clone, Default)]
+    struct PendingBuildSpans(Arc<Mutex<Vec<&'static str>>>);
+
+    impl<S: Subscriber> Layer<S> for PendingBuildSpans {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+            if attrs.metadata().target() == "rpc::eth::pending" {
+                self.0.lock().unwrap().push(attrs.metadata().name());
+            }
+        }
+    }
+
+    fn test_provider() -> MockEthProvider {
+        let provider = MockEthProvider::default();
+        let header = Header { gas_limit: 30_000_000, ..Default::default() };
+        provider.add_block(header.hash_slow(), Block { header, body: Default::default() });
+        provider
+    }
+
+    /// Counts the calls executed by the EVM.
+    #[derive(Debug, Default)]
+    struct CallCounter {
+        calls: usize,
+    }
+
+    impl<CTX> Inspector<CTX, EthInterpreter> for CallCounter {
+        fn call(&mut self, _context: &mut CTX, _inputs: &mut CallInputs) -> Option<CallOutcome> {
+            self.calls += 1;
+            None
+        }
+    }
+
+    /// Fails the execution of all calls made by the given caller with a database error.
+    #[derive(Debug)]
+    struct FailingCaller(Address);
+
+    impl<CTX> Inspector<CTX, EthInterpreter> for FailingCaller
+    where
+        CTX: ContextTr<Db: Database<Error = ProviderError>>,
+    {
+        fn call(&mut self, context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+            if inputs.caller == self.0 {
+                *context.error() = Err(ProviderError::UnsupportedProvider);
+            }
+            None
+        }
+    }
+
+    /// Panics on every call executed by the EVM, like a buggy custom hook.
+    #[derive(Debug)]
+    struct PanickingInspector;
+
+    impl<CTX> Inspector<CTX, EthInterpreter> for PanickingInspector {
+        fn call(&mut self, _context: &mut CTX, _inputs: &mut CallInputs) -> Option<CallOutcome> {
+            panic!("inspector bug")
+        }
+    }
+
+    /// Fails the execution of every call after the first with a database error, like a custom
+    /// hook that depends on state outside of the build.
+    #[derive(Debug, Default)]
+    struct FlakyInspector {
+        calls: usize,
+    }
+
+    impl<CTX> Inspector<CTX, EthInterpreter> for FlakyInspector
+    where
+        CTX: ContextTr<Db: Database<Error = ProviderError>>,
+    {
+        fn call(&mut self, context: &mut CTX, _inputs: &mut CallInputs) -> Option<CallOutcome> {
+            self.calls += 1;
+            if self.calls > 1 {
+                *context.error() = Err(ProviderError::UnsupportedProvider);
+            }
+            None
+        }
+    }
+
+    /// Adds a transaction to the pool on the first executed call, like a replacement that arrives
+    /// while the pending block is built.
+    struct ReplaceOnCall {
+        pool: TestPool,
+        replacement: Option<MockTransaction>,
+    }
+
+    impl<CTX> Inspector<CTX, EthInterpreter> for ReplaceOnCall {
+        fn call(&mut self, _context: &mut CTX, _inputs: &mut CallInputs) -> Option<CallOutcome> {
+            if let Some(replacement) = self.replacement.take() {
+                futures::executor::block_on(
+                    self.pool.add_transaction(TransactionOrigin::External, replacement),
+                )
+                .unwrap();
+            }
+            None
+        }
+    }
+
+    /// Returns a provider that only contains the genesis block of the given chain.
+    fn genesis_only_provider(chain_spec: ChainSpec) -> MockEthProvider {
+        let provider = MockEthProvider::default().with_chain_spec(chain_spec);
+        let chain_spec = provider.chain_spec();
+        provider.add_block(
+            chain_spec.genesis_hash(),
+            Block { header: chain_spec.genesis_header().clone(), body: Default::default() },
+        );
+        provider
+    }
+
+    fn transfer() -> MockTransaction {
+        MockTransaction::legacy().with_gas_limit(21_000).with_gas_price(10)
+    }
+
+    /// Returns a blob transaction with the given number of blobs that pays the initial base fee.
+    fn blob_transaction(blobs: usize) -> MockTransaction {
+        let mut tx = MockTransaction::eip4844()
+            .with_gas_limit(21_000)
+            .with_max_fee(2_000_000_000)
+            .with_priority_fee(1_000_000_000);
+        if let MockTransaction::Eip4844 { blob_versioned_hashes, .. } = &mut tx {
+            *blob_versioned_hashes = (0..blobs)
+                .map(|_| {
+                    let mut hash = B256::random();
+                    hash[0] = VERSIONED_HASH_VERSION_KZG;
+                    hash
+                })
+                .collect();
+        }
+        tx
+    }
+
+    async fn add_funded_transaction(
+        provider: &MockEthProvider,
+        pool: &TestPool,
+        tx: MockTransaction,
+    ) {
+        provider.add_account(tx.sender(), ExtendedAccount::new(0, U256::from(1_000_000_000u64)));
+        pool.add_transaction(TransactionOrigin::External, tx).await.unwrap();
+    }
+
+    /// Blob store that blocks every insert until it's released. The pool inserts blobs while it
+    /// holds its lock, so the pool is unavailable while an insert is blocked.
+    #[derive(Debug)]
+    struct BlockingBlobStore {
+        inner: InMemoryBlobStore,
+        entered: Mutex<mpsc::Sender<()>>,
+        release: Mutex<mpsc::Receiver<()>>,
+    }
+
+    impl BlobStore for BlockingBlobStore {
+        fn insert(&self, tx: B256, data: BlobTransactionSidecar) -> Result<(), BlobStoreError> {
+            let _ = self.entered.lock().unwrap().send(());
+            let _ = self.release.lock().unwrap().recv();
+            self.inner.insert(tx, data)
+        }
+
+        fn insert_all(
+            &self,
+            txs: Vec<(B256, BlobTransactionSidecar)>,
+        ) -> Result<(), BlobStoreError> {
+            self.inner.insert_all(txs)
+        }
+
+        fn delete(&self, tx: B256) -> Result<(), BlobStoreError> {
+            self.inner.delete(tx)
+        }
+
+        fn delete_all(&self, txs: Vec<B256>) -> Result<(), BlobStoreError> {
+            self.inner.delete_all(txs)
+        }
+
+        fn cleanup(&self) -> BlobStoreCleanupStat {
+            self.inner.cleanup()
+        }
+
+        fn get(&self, tx: B256) -> Result<Option<Arc<BlobTransactionSidecar>>, BlobStoreError> {
+            self.inner.get(tx)
+        }
+
+        fn contains(&self, tx: B256) -> Result<bool, BlobStoreError> {
+            self.inner.contains(tx)
+        }
+
+        fn get_all(
+            &self,
+            txs: Vec<B256>,
+        ) -> Result<Vec<(B256, Arc<BlobTransactionSidecar>)>, BlobStoreError> {
+            self.inner.get_all(txs)
+        }
+
+        fn get_exact(
+            &self,
+            txs: Vec<B256>,
+        ) -> Result<Vec<Arc<BlobTransactionSidecar>>, BlobStoreError> {
+            self.inner.get_exact(txs)
+        }
+
+        fn get_by_versioned_hashes(
+            &self,
+            versioned_hashes: &[B256],
+        ) -> Result<Vec<Option<BlobAndProofV1>>, BlobStoreError> {
+            self.inner.get_by_versioned_hashes(versioned_hashes)
+        }
+
+        fn data_size_hint(&self) -> Option<usize> {
+            self.inner.data_size_hint()
+        }
+
+        fn blobs_len(&self) -> usize {
+            self.inner.blobs_len()
+        }
+    }
+
+    /// Marks the cached pending block as expired, so that it's rebuilt on the next request.
+    async fn expire_pending_block(eth_api: &TestEthApi) {
+        let mut lock = eth_api.pending_block().lock().await;
+        lock.as_mut().unwrap().expires_at = Instant::now() - Duration::from_secs(1);
+    }
+
+    fn build_eth_api(
+        provider: MockEthProvider,
+        pool: TestPool,
+        config: PendingBlockConfig,
+    ) -> TestEthApi {
+        EthApiBuilder::new(
+            provider.clone(),
+            pool,
+            NoopNetwork::default(),
+            EthEvmConfig::new(provider.chain_spec()),
+        )
+        .pending_block_config(config)
+        .build()
+    }
+
+    /// Returns the eth API on top of [`test_provider`], with the given transactions funded and
+    /// added to the pool.
+    async fn eth_api_with(
+        config: PendingBlockConfig,
+        txs: impl IntoIterator<Item = MockTransaction>,
+    ) -> TestEthApi {
+        let provider = test_provider();
+        let pool = testing_pool();
+        for tx in txs {
+            add_funded_transaction(&provider, &pool, tx).await;
+        }
+        build_eth_api(provider, pool, config)
+    }
+
+    /// Returns the pending block built with the given config from the given transactions, see
+    /// [`eth_api_with`].
+    async fn pending_block_with(
+        config: PendingBlockConfig,
+        txs: impl IntoIterator<Item = MockTransaction>,
+    ) -> RecoveredBlock<Block> {
+        let eth_api = eth_api_with(config, txs).await;
+        eth_api.local_pending_block().await.unwrap().unwrap().0
+    }
+
+    /// Returns the hashes of the transactions of the given block in order.
+    fn tx_hashes(block: &RecoveredBlock<Block>) -> Vec<B256> {
+        block.body().transactions.iter().map(|tx| *tx.tx_hash()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_stream_pending_transactions() {
+        let eth_api = eth_api_with(PendingBlockConfig::default(), (0..3).map(|_| transfer())).await;
+
+        let streamed = eth_api
+            .stream_pending_transactions()
+            .await
+            .unwrap()
+            .map(|tx| *tx.tx_hash())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(streamed.len(), 3);
+
+        // the stream completes after the built block was cached
+        let lock = eth_api.pending_block().lock().await;
+        let pending = lock.as_ref().expect("pending block is cached");
+        assert_eq!(streamed, tx_hashes(&pending.block));
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_skips_oversized_transactions() {
+        let small = transfer().with_size(100);
+        let small_hash = *small.get_hash();
+
+        let config = PendingBlockConfig::default().with_max_tx_size_bytes(Some(1_000));
+        let block = pending_block_with(config, [small, transfer().with_size(10_000)]).await;
+        assert_eq!(tx_hashes(&block), vec![small_hash]);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_extra_data_stamp() {
+        // the stamp must not make the two builds of the determinism check differ
+        let config = PendingBlockConfig::default()
+            .with_extra_data_stamp(Some("reth/v1.3.0".to_string()))
+            .unwrap()
+            .with_verify_determinism(true);
+        let block = pending_block_with(config, [transfer()]).await;
+
+        let extra_data = &block.header().extra_data;
+        assert!(extra_data.len() <= MAXIMUM_EXTRA_DATA_SIZE);
+        let timestamp = u64::from_be_bytes(extra_data[..8].try_into().unwrap());
+        assert_eq!(timestamp, block.header().timestamp);
+        assert_eq!(&extra_data[8..], b"reth/v1.3.0");
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_respects_max_calldata() {
+        let heavy = (0..3)
+            .map(|_| transfer().with_gas_limit(100_000).with_input(Bytes::from(vec![0x01; 1_000])));
+        let small = transfer();
+        let small_sender = small.sender();
+
+        // only two of the calldata heavy transactions fit, the transfer without calldata still does
+        let config = PendingBlockConfig::default().with_max_calldata_bytes(Some(2_500));
+        let block = pending_block_with(config, heavy.chain([small])).await;
+        let calldata: usize = block.body().transactions.iter().map(|tx| tx.input().len()).sum();
+        assert_eq!(block.body().transactions.len(), 3);
+        assert_eq!(calldata, 2_000);
+        assert!(block.senders().contains(&small_sender));
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_build_limits() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        for _ in 0..3 {
+            add_funded_transaction(&provider, &pool, transfer()).await;
+        }
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        // each limit on its own stops the selection
+        for (config, expected) in [
+            (PendingBlockConfig::default().with_max_transactions(Some(2)), 2),
+            (PendingBlockConfig::default().with_pending_gas_cap(Some(21_000)), 1),
+            // 0.2% of the 30M gas limit leaves room for two transfers only
+            (PendingBlockConfig::default().with_target_gas_fraction(Some(0.002)).unwrap(), 2),
+            (PendingBlockConfig::default().with_build_time_budget(Some(Duration::ZERO)), 0),
+            (PendingBlockConfig::default(), 3),
+        ] {
+            let eth_api = build_eth_api(provider.clone(), pool.clone(), config);
+            let (block, _) = eth_api.build_block(&parent).unwrap();
+            assert_eq!(block.body().transactions.len(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pending_excluded_fitting_transactions() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        let included = transfer().with_gas_price(30);
+        let fitting = transfer().with_gas_price(20);
+        let too_large = transfer().with_gas_price(10).with_gas_limit(50_000);
+        let (included_hash, fitting_hash) = (*included.get_hash(), *fitting.get_hash());
+        for tx in [included, fitting, too_large] {
+            add_funded_transaction(&provider, &pool, tx).await;
+        }
+
+        // only one transaction is included, which leaves room for another transfer
+        let config = PendingBlockConfig::default()
+            .with_max_transactions(Some(1))
+            .with_pending_gas_cap(Some(60_000));
+        let eth_api = build_eth_api(provider.clone(), pool.clone(), config);
+        let (block, excluded) =
+            eth_api.pending_excluded_fitting_transactions().await.unwrap().unwrap();
+
+        assert_eq!(block.body().transactions.len(), 1);
+        assert_eq!(*block.body().transactions[0].tx_hash(), included_hash);
+        let expected = vec![PendingExclusion {
+            hash: fitting_hash,
+            reason: PendingExclusionReason::Limit(PendingBuildLimit::Transactions),
+        }];
+        assert_eq!(excluded, expected);
+
+        // the report is the one recorded during the build of the cached pending block, so
+        // transactions that enter the pool afterwards are not part of it
+        add_funded_transaction(&provider, &pool, transfer().with_gas_price(20)).await;
+        let (_, excluded) = eth_api.pending_excluded_fitting_transactions().await.unwrap().unwrap();
+        assert_eq!(excluded, expected);
+    }
+
+    #[tokio::test]
+    async fn test_pending_excluded_transactions_are_capped() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        for _ in 0..MAX_PENDING_EXCLUSIONS + 2 {
+            add_funded_transaction(&provider, &pool, transfer()).await;
+        }
+
+        let config = PendingBlockConfig::default().with_max_transactions(Some(1));
+        let eth_api = build_eth_api(provider, pool, config);
+        let (block, excluded) =
+            eth_api.pending_excluded_fitting_transactions().await.unwrap().unwrap();
+
+        // the limit stops the selection after the first transfer and only a bounded number of
+        // the remaining ones is reported
+        assert_eq!(block.body().transactions.len(), 1);
+        assert_eq!(excluded.len(), MAX_PENDING_EXCLUSIONS);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_sender_allowlist() {
+        let allowed = transfer();
+        let allowed_sender = allowed.sender();
+
+        let config = PendingBlockConfig::default()
+            .with_sender_allowlist(Some(HashSet::from([allowed_sender])));
+        let block =
+            pending_block_with(config, [allowed, transfer(), transfer().with_gas_price(20)]).await;
+        assert_eq!(block.senders(), &[allowed_sender]);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_max_unique_senders() {
+        // the first sender pays the most, so it's included before the limit is reached
+        let first = transfer().with_gas_price(30);
+        let next = first.next().with_gas_price(10);
+        let sender = first.sender();
+
+        // the other sender pays more than the second transaction of the first sender, but it's
+        // skipped as a new sender, while the first sender's subsequent transaction still qualifies
+        let config = PendingBlockConfig::default().with_max_unique_senders(Some(1));
+        let block = pending_block_with(config, [first, next, transfer().with_gas_price(20)]).await;
+        assert_eq!(block.senders(), &[sender, sender]);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_max_pending_tx_age() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        // the old transaction pays more, so it would be included first
+        let old = transfer().with_gas_price(20);
+        let fresh = transfer();
+        let fresh_hash = *fresh.get_hash();
+        let old_hash = *old.get_hash();
+        add_funded_transaction(&provider, &pool, old).await;
+        let old_added_at = pool.get(&old_hash).unwrap().timestamp;
+        // the pool stamps the fresh transaction strictly later
+        while Instant::now() <= old_added_at {}
+        add_funded_transaction(&provider, &pool, fresh).await;
+        let fresh_added_at = pool.get(&fresh_hash).unwrap().timestamp;
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        let eth_api = build_eth_api(provider.clone(), pool.clone(), PendingBlockConfig::default());
+        let (block, _) = eth_api.build_block(&parent).unwrap();
+        assert_eq!(block.body().transactions.len(), 2);
+
+        // at the time of the clock, the fresh transaction is exactly as old as allowed
+        let max_age = Duration::from_secs(60);
+        let now = fresh_added_at + max_age;
+        let config = PendingBlockConfig::default()
+            .with_max_pending_tx_age(Some(max_age))
+            .with_pending_tx_clock(Some(PendingClock::new(move || now)));
+        let eth_api = build_eth_api(provider, pool, config);
+        let (block, _) = eth_api.build_block(&parent).unwrap();
+        assert_eq!(block.body().transactions.len(), 1);
+        assert_eq!(*block.body().transactions[0].tx_hash(), fresh_hash);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_drop_noop_transfers() {
+        let mut noop = transfer();
+        let sender = noop.sender();
+        if let MockTransaction::Legacy { to, .. } = &mut noop {
+            *to = TxKind::Call(sender);
+        }
+
+        // a self transfer with value isn't a no-op
+        let mut self_transfer = transfer();
+        let self_sender = self_transfer.sender();
+        if let MockTransaction::Legacy { to, value, .. } = &mut self_transfer {
+            *to = TxKind::Call(self_sender);
+            *value = U256::from(1);
+        }
+        let self_transfer_hash = *self_transfer.get_hash();
+
+        let config = PendingBlockConfig::default().with_drop_noop_transfers(true);
+        let block = pending_block_with(config, [noop, self_transfer]).await;
+        assert_eq!(tx_hashes(&block), vec![self_transfer_hash]);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_validity_check() {
+        // transactions that encode the timestamp they are valid until in their calldata
+        let valid_until = |timestamp: u64| {
+            transfer()
+                .with_gas_limit(30_000)
+                .with_input(Bytes::copy_from_slice(&timestamp.to_be_bytes()))
+        };
+        let valid = valid_until(100);
+        let valid_hash = *valid.get_hash();
+
+        let check = PendingValidityCheck::new(|tx, pending_timestamp| {
+            tx.input().get(..8).is_none_or(|until| {
+                u64::from_be_bytes(until.try_into().unwrap()) >= pending_timestamp
+            })
+        });
+        let config = PendingBlockConfig::default().with_validity_check(Some(check));
+        let block = pending_block_with(config, [valid, valid_until(5)]).await;
+
+        // the pending block is built at timestamp 12
+        assert_eq!(block.header().timestamp, 12);
+        assert_eq!(tx_hashes(&block), vec![valid_hash]);
+    }
+
+    #[tokio::test]
+    async fn test_pending_empty_block() {
+        let provider = test_provider();
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        let config = PendingBlockConfig::default().with_produce_empty_blocks(true);
+        let eth_api = build_eth_api(provider, testing_pool(), config);
+
+        // neither deposits nor pool transactions, but still a new block instead of the latest
+        let (block, receipts) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_ne!(block.hash(), parent.hash());
+        assert_eq!(block.header().parent_hash, parent.hash());
+        assert_eq!(block.header().number, parent.number + 1);
+        assert_eq!(block.header().timestamp, parent.timestamp + 12);
+        assert_eq!(block.header().gas_used, 0);
+        assert_eq!(block.header().transactions_root, EMPTY_ROOT_HASH);
+        assert_eq!(block.header().receipts_root, EMPTY_ROOT_HASH);
+        assert!(block.body().transactions.is_empty());
+        assert!(receipts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pending_base_fee_max_change_denominator() {
+        let provider = MockEthProvider::default()
+            .with_chain_spec(ChainSpecBuilder::mainnet().shanghai_activated().build());
+        let header = Header {
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        provider.add_block(header.hash_slow(), Block { header, body: Default::default() });
+
+        // the empty parent decreases the base fee by 1/8 with the mainnet denominator
+        let eth_api =
+            build_eth_api(provider.clone(), testing_pool(), PendingBlockConfig::default());
+        let env = eth_api.pending_block_env_and_cfg().unwrap();
+        assert_eq!(env.evm_env.block_env.basefee, 875_000_000);
+
+        let config = PendingBlockConfig::default()
+            .with_base_fee_max_change_denominator(NonZeroU128::new(16));
+        let eth_api = build_eth_api(provider, testing_pool(), config);
+        let env = eth_api.pending_block_env_and_cfg().unwrap();
+        assert_eq!(env.evm_env.block_env.basefee, 937_500_000);
+
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.header().base_fee_per_gas, Some(937_500_000));
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_skips_intrinsic_gas_too_low() {
+        // the calldata costs gas on top of the base cost of a transfer
+        let underfunded = transfer().with_input(Bytes::from_static(&[1]));
+        let funded = transfer();
+        let funded_hash = *funded.get_hash();
+
+        let block = pending_block_with(PendingBlockConfig::default(), [underfunded, funded]).await;
+        assert_eq!(tx_hashes(&block), vec![funded_hash]);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_skips_underpriced_after_base_fee_increase() {
+        let provider = MockEthProvider::default()
+            .with_chain_spec(ChainSpecBuilder::mainnet().shanghai_activated().build());
+        let header = Header {
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        provider.add_block(header.hash_slow(), Block { header, body: Default::default() });
+        let pool = testing_pool();
+
+        // the pool orders the transactions against a lower base fee than the pending one of
+        // 875_000_000
+        let mut block_info = pool.block_info();
+        block_info.pending_basefee = 800_000_000;
+        pool.set_block_info(block_info);
+
+        let borderline = transfer().with_gas_price(850_000_000);
+        let priced = transfer().with_gas_price(900_000_000);
+        let priced_hash = *priced.get_hash();
+        for tx in [borderline, priced] {
+            provider.add_account(tx.sender(), ExtendedAccount::new(0, U256::from(u64::MAX)));
+            pool.add_transaction(TransactionOrigin::External, tx).await.unwrap();
+        }
+
+        let eth_api = build_eth_api(provider.clone(), pool, PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+        let snapshot = eth_api.snapshot_pool();
+        assert_eq!(snapshot.transactions().len(), 2);
+
+        let (block, _) = eth_api.build_block_with_snapshot(&parent, &snapshot).unwrap();
+        assert_eq!(block.header().base_fee_per_gas, Some(875_000_000));
+        assert_eq!(tx_hashes(&block), vec![priced_hash]);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_gas_limit_override() {
+        let provider = test_provider();
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        // just inside of the allowed adjustment of 30_000_000 / 1024
+        let config = PendingBlockConfig::default().with_gas_limit(Some(30_029_295));
+        let eth_api = build_eth_api(provider.clone(), testing_pool(), config);
+        assert_eq!(eth_api.next_env_attributes(&parent).unwrap().gas_limit, 30_029_295);
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.header().gas_limit, 30_029_295);
+
+        // just outside of the allowed adjustment
+        let config = PendingBlockConfig::default().with_gas_limit(Some(30_029_296));
+        let eth_api = build_eth_api(provider, testing_pool(), config);
+        assert!(matches!(
+            eth_api.next_env_attributes(&parent),
+            Err(EthApiError::PendingGasLimitOutOfBounds { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_keeps_deposit_gas_reserve() {
+        // both reserves leave room for two transfers only, the second one exactly
+        for reserve in [30_000_000 - 50_000, 30_000_000 - 42_000] {
+            let config = PendingBlockConfig::default().with_deposit_gas_reserve(Some(reserve));
+            let block = pending_block_with(config, (0..3).map(|_| transfer())).await;
+            assert_eq!(block.body().transactions.len(), 2);
+            assert_eq!(block.header().gas_used, 42_000);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_access_list_discount() {
+        let provider = MockEthProvider::default()
+            .with_chain_spec(ChainSpecBuilder::mainnet().berlin_activated().build());
+        let header = Header { gas_limit: 30_000_000, ..Default::default() };
+        provider.add_block(header.hash_slow(), Block { header, body: Default::default() });
+        let pool = testing_pool();
+
+        // PUSH20 <account> BALANCE STOP, reading the balance of a cold account costs 2600 gas
+        // and of an account in the access list 100 gas, on top of the 2400 gas for the entry
+        let account = Address::random();
+        let contract = Address::random();
+        provider.add_account(
+            contract,
+            ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode([&[0x73][..], account.as_slice(), &[0x31, 0x00]].concat().into()),
+        );
+
+        let gas_limit = 21_000 + 2_400 + 3 + 100;
+        let mut tx = MockTransaction::eip2930().with_gas_limit(gas_limit).with_gas_price(10);
+        if let MockTransaction::Eip2930 { to, .. } = &mut tx {
+            *to = TxKind::Call(contract);
+        }
+        tx.set_accesslist(AccessList(vec![AccessListItem {
+            address: account,
+            storage_keys: Vec::new(),
+        }]));
+        let hash = *tx.get_hash();
+        add_funded_transaction(&provider, &pool, tx).await;
+
+        let eth_api = build_eth_api(provider, pool, PendingBlockConfig::default());
+        let (block, receipts) = eth_api.local_pending_block().await.unwrap().unwrap();
+
+        // the gas limit only suffices with the access list, and the gas accounted for the block
+        // matches the gas used in execution
+        assert_eq!(*block.body().transactions[0].tx_hash(), hash);
+        assert!(receipts[0].success);
+        assert_eq!(receipts[0].cumulative_gas_used, gas_limit);
+        assert_eq!(block.header().gas_used, gas_limit);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_respects_max_blobs() {
+        let provider =
+            genesis_only_provider(ChainSpecBuilder::mainnet().cancun_activated().build());
+        let pool = testing_pool();
+        for _ in 0..5 {
+            let tx = blob_transaction(1);
+            provider.add_account(tx.sender(), ExtendedAccount::new(0, U256::from(10u128.pow(18))));
+            pool.add_transaction(TransactionOrigin::External, tx).await.unwrap();
+        }
+
+        let config = PendingBlockConfig::default().with_max_blobs_per_block(Some(3));
+        let eth_api = build_eth_api(provider, pool, config);
+
+        let (block, receipts) = eth_api.local_pending_block().await.unwrap().unwrap();
+        let blobs = block
+            .body()
+            .transactions
+            .iter()
+            .map(|tx| tx.blob_count().unwrap_or_default())
+            .sum::<u64>();
+        assert_eq!(blobs, 3);
+        assert_eq!(receipts.len(), 3);
+        assert_eq!(block.header().blob_gas_used, Some(3 * DATA_GAS_PER_BLOB));
+    }
+
+    #[tokio::test]
+    async fn test_pending_cumulative_blob_gas() {
+        let provider =
+            genesis_only_provider(ChainSpecBuilder::mainnet().cancun_activated().build());
+        let pool = testing_pool();
+        for blobs in 1..=3 {
+            let tx = blob_transaction(blobs);
+            provider.add_account(tx.sender(), ExtendedAccount::new(0, U256::from(10u128.pow(18))));
+            pool.add_transaction(TransactionOrigin::External, tx).await.unwrap();
+        }
+
+        let eth_api = build_eth_api(provider, pool, PendingBlockConfig::default());
+
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.body().transactions.len(), 3);
+        let cumulative = eth_api.pending_cumulative_blob_gas().await.unwrap().unwrap();
+        let expected = block
+            .body()
+            .transactions
+            .iter()
+            .scan(0, |sum, tx| {
+                *sum += tx.blob_gas_used().unwrap_or_default();
+                Some(*sum)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(cumulative, expected);
+        assert_eq!(cumulative.last().copied(), Some(6 * DATA_GAS_PER_BLOB));
+        assert_eq!(cumulative.last().copied(), block.header().blob_gas_used);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_skips_tip_above_fee_cap() {
+        let provider =
+            genesis_only_provider(ChainSpecBuilder::mainnet().london_activated().build());
+        let pool = testing_pool();
+
+        let valid = MockTransaction::eip1559()
+            .with_gas_limit(21_000)
+            .with_max_fee(2_000_000_000)
+            .with_priority_fee(1_000_000_000);
+        let malformed = MockTransaction::eip1559()
+            .with_gas_limit(21_000)
+            .with_max_fee(2_000_000_000)
+            .with_priority_fee(3_000_000_000);
+        let (valid_sender, malformed_sender) = (valid.sender(), malformed.sender());
+        for tx in [valid, malformed] {
+            provider.add_account(tx.sender(), ExtendedAccount::new(0, U256::from(10u128.pow(18))));
+            pool.add_transaction(TransactionOrigin::External, tx).await.unwrap();
+        }
+
+        let eth_api = build_eth_api(provider, pool, PendingBlockConfig::default());
+
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.senders(), &[valid_sender]);
+        assert!(!block.senders().contains(&malformed_sender));
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_pending_block_timed() {
+        let eth_api = eth_api_with(PendingBlockConfig::default(), [transfer()]).await;
+        let (cached, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+
+        let timings = eth_api.rebuild_pending_block_timed().await.unwrap();
+        assert!(timings.selection > Duration::ZERO);
+        assert!(timings.execution > Duration::ZERO);
+        assert!(timings.state_root > Duration::ZERO);
+        assert!(timings.sealing > Duration::ZERO);
+        assert_eq!(
+            timings.total(),
+            timings.selection + timings.execution + timings.state_root + timings.sealing
+        );
+
+        // the rebuilt block replaced the cached one
+        let lock = eth_api.pending_block().lock().await;
+        let rebuilt = &lock.as_ref().expect("pending block is cached").block;
+        assert_eq!(rebuilt.body().transactions, cached.body().transactions);
+    }
+
+    #[tokio::test]
+    async fn test_pending_heads_stream() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        add_funded_transaction(&provider, &pool, transfer()).await;
+
+        let eth_api = build_eth_api(provider.clone(), pool.clone(), PendingBlockConfig::default());
+        let mut heads = Box::pin(eth_api.pending_heads_stream().unwrap());
+        let mut blocks = eth_api.subscribe_to_pending_blocks().unwrap();
+
+        let (first, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        let head = heads.next().await.unwrap();
+        assert_eq!(head.hash, first.hash());
+        assert_eq!(head.inner.number, 1);
+        assert_eq!(head.inner, *first.header());
+        // the full block notification comes from the same broadcast
+        assert_eq!(blocks.recv().await.unwrap().hash(), first.hash());
+
+        // a cached pending block is not rebuilt, so it's not sent again
+        eth_api.local_pending_block().await.unwrap().unwrap();
+
+        add_funded_transaction(&provider, &pool, transfer()).await;
+        expire_pending_block(&eth_api).await;
+        let (second, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_ne!(second.hash(), first.hash());
+        assert_eq!(heads.next().await.unwrap().hash, second.hash());
+        assert_eq!(blocks.recv().await.unwrap().hash(), second.hash());
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_pending_block_on_pool_events() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        // without the config the task completes right away
+        let eth_api = build_eth_api(provider.clone(), pool.clone(), PendingBlockConfig::default());
+        eth_api.clone().rebuild_pending_block_on_pool_events().await;
+
+        // the task is spawned when the API is built
+        let config = PendingBlockConfig::default().with_rebuild_on_pool_events(true);
+        let eth_api = build_eth_api(provider.clone(), pool.clone(), config);
+        let mut blocks = eth_api.subscribe_to_pending_blocks().unwrap();
+
+        let tx = transfer();
+        let hash = *tx.get_hash();
+        add_funded_transaction(&provider, &pool, tx).await;
+
+        // the block is rebuilt without a request and cached
+        let block = blocks.recv().await.unwrap();
+        assert_eq!(*block.body().transactions[0].tx_hash(), hash);
+        let cached = eth_api.pending_block().lock().await.as_ref().unwrap().block.hash();
+        assert_eq!(cached, block.hash());
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_rpc_empty_arrays() {
+        let provider =
+            genesis_only_provider(ChainSpecBuilder::mainnet().shanghai_activated().build());
+        let eth_api = build_eth_api(provider, testing_pool(), PendingBlockConfig::default());
+
+        let rpc_block = eth_api.rpc_block(BlockId::pending(), false).await.unwrap().unwrap();
+        let json = serde_json::to_value(&rpc_block).unwrap();
+        assert_eq!(json["uncles"], serde_json::json!([]));
+        assert_eq!(json["withdrawals"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_rlp() {
+        let eth_api = eth_api_with(PendingBlockConfig::default(), [transfer()]).await;
+
+        let rlp = eth_api.pending_block_rlp().await.unwrap().unwrap();
+        let decoded = Block::decode(&mut rlp.as_ref()).unwrap();
+
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(decoded.header, *block.header());
+        assert_eq!(decoded.body.transactions, block.body().transactions);
+        assert_eq!(decoded.body.withdrawals, block.body().withdrawals);
+        assert_eq!(decoded.header.hash_slow(), block.hash());
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_rpc_size() {
+        let eth_api = eth_api_with(PendingBlockConfig::default(), [transfer()]).await;
+
+        let rpc_block = eth_api.rpc_block(BlockId::pending(), false).await.unwrap().unwrap();
+
+        let lock = eth_api.pending_block().lock().await;
+        let pending = lock.as_ref().expect("pending block is cached");
+        let encoded_length = alloy_rlp::encode(pending.block.sealed_block().clone_block()).len();
+
+        assert_ne!(encoded_length, 0);
+        assert_eq!(rpc_block.header.size, Some(U256::from(encoded_length)));
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_from_pool_snapshot() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        let evicted = transfer();
+        let evicted_hash = *evicted.get_hash();
+        add_funded_transaction(&provider, &pool, evicted).await;
+        add_funded_transaction(&provider, &pool, transfer()).await;
+
+        let eth_api = build_eth_api(provider.clone(), pool.clone(), PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        let snapshot = eth_api.snapshot_pool();
+        let (first, _) = eth_api.build_block_with_snapshot(&parent, &snapshot).unwrap();
+        assert_eq!(first.body().transactions.len(), 2);
+
+        // new and evicted transactions don't affect blocks built against the snapshot
+        add_funded_transaction(&provider, &pool, transfer()).await;
+        pool.remove_transactions(vec![evicted_hash]);
+
+        let (second, _) = eth_api.build_block_with_snapshot(&parent, &snapshot).unwrap();
+        assert_eq!(first.body().transactions, second.body().transactions);
+        assert_eq!(first.senders(), second.senders());
+        assert_eq!(first.header().state_root, second.header().state_root);
+        assert_eq!(first.header().gas_used, second.header().gas_used);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_nonce_continuity() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        // nonces 0, 1 and 3 of the same sender, 3 can't be included without 2
+        let first = transfer();
+        let second = first.next();
+        let gapped = second.skip(1);
+        let hashes = [*first.get_hash(), *second.get_hash(), *gapped.get_hash()];
+        for tx in [first, second, gapped] {
+            add_funded_transaction(&provider, &pool, tx).await;
+        }
+
+        let eth_api = build_eth_api(provider.clone(), pool.clone(), PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        // yield the transactions in scrambled order
+        let scrambled = [hashes[1], hashes[2], hashes[0]].map(|hash| pool.get(&hash).unwrap());
+        let snapshot = PendingPoolSnapshot::new(scrambled.into_iter());
+        let (block, _) = eth_api.build_block_with_snapshot(&parent, &snapshot).unwrap();
+        assert_eq!(tx_hashes(&block), hashes[..2]);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_equal_fee_order() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        // submit the transaction with the higher hash first
+        let (a, b) = (transfer(), transfer());
+        let (first, second) = if a.get_hash() > b.get_hash() { (a, b) } else { (b, a) };
+        let expected = vec![*second.get_hash(), *first.get_hash()];
+        add_funded_transaction(&provider, &pool, first).await;
+        add_funded_transaction(&provider, &pool, second).await;
+
+        let eth_api = build_eth_api(provider.clone(), pool, PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        for _ in 0..3 {
+            let (block, _) =
+                eth_api.build_block_with_snapshot(&parent, &eth_api.snapshot_pool()).unwrap();
+            assert_eq!(tx_hashes(&block), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_fee_revenue() {
+        let eth_api = eth_api_with(
+            PendingBlockConfig::default(),
+            [transfer(), transfer().with_gas_price(20)],
+        )
+        .await;
+        let revenue = eth_api.pending_block_fee_revenue().await.unwrap().unwrap();
+
+        // each transfer uses 21000 gas and pays the part of its gas price above the base fee
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        let base_fee = block.header().base_fee_per_gas.unwrap_or_default() as u128;
+        let contributions = block
+            .body()
+            .transactions
+            .iter()
+            .map(|tx| U256::from(21_000 * (tx.max_fee_per_gas() - base_fee)))
+            .collect::<Vec<_>>();
+        assert_eq!(contributions.len(), 2);
+        assert_eq!(revenue, contributions.into_iter().sum::<U256>());
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_fee_preview() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        let cheap = transfer();
+        let expensive = transfer().with_gas_price(20);
+        let (cheap_hash, expensive_hash) = (*cheap.get_hash(), *expensive.get_hash());
+        add_funded_transaction(&provider, &pool, cheap).await;
+        add_funded_transaction(&provider, &pool, expensive).await;
+
+        let eth_api = build_eth_api(provider, pool.clone(), PendingBlockConfig::default());
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.body().transactions.len(), 2);
+
+        // the base fee rises above the gas price of the cheap transfer after the block was built
+        let mut block_info = pool.block_info();
+        block_info.pending_basefee = 15;
+        pool.set_block_info(block_info);
+
+        let (preview, fees) = eth_api.pending_block_fee_preview().await.unwrap().unwrap();
+        assert_eq!(preview.hash(), block.hash());
+        let fee = |hash: B256| *fees.iter().find(|fee| fee.hash == hash).unwrap();
+        assert_eq!(
+            fee(expensive_hash),
+            PendingTransactionFee { hash: expensive_hash, priority_fee: 5, underpriced: false }
+        );
+        assert_eq!(
+            fee(cheap_hash),
+            PendingTransactionFee { hash: cheap_hash, priority_fee: 0, underpriced: true }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pending_reverted_receipt() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        // PUSH1 0 PUSH1 0 REVERT
+        let contract = Address::random();
+        provider.add_account(
+            contract,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(hex!("60006000fd").into()),
+        );
+
+        let succeeding = transfer();
+        let mut reverting = succeeding.next().with_gas_limit(100_000);
+        if let MockTransaction::Legacy { to, .. } = &mut reverting {
+            *to = TxKind::Call(contract);
         }
+        let reverting_hash = *reverting.get_hash();
+        add_funded_transaction(&provider, &pool, succeeding).await;
+        add_funded_transaction(&provider, &pool, reverting).await;
+
+        let eth_api = build_eth_api(provider, pool, PendingBlockConfig::default());
+        let (block, receipts) = eth_api.local_pending_block().await.unwrap().unwrap();
+
+        // the reverted transaction is still included, with a failure status
+        assert_eq!(*block.body().transactions[1].tx_hash(), reverting_hash);
+        assert!(receipts[0].success);
+        assert_eq!(receipts[0].cumulative_gas_used, 21_000);
+        assert!(!receipts[1].success);
+        assert!(receipts[1].cumulative_gas_used > receipts[0].cumulative_gas_used);
+        assert_eq!(receipts[1].cumulative_gas_used, block.header().gas_used);
+
+        let receipt = eth_api.pending_receipt_by_index(1).await.unwrap().unwrap();
+        assert_eq!(receipt, receipts[1]);
+    }
+
+    #[tokio::test]
+    async fn test_get_proof_pending_slot() {
+        let provider = test_provider().with_computed_trie();
+        let pool = testing_pool();
+
+        // PUSH1 1 PUSH1 0 SSTORE STOP
+        let contract = Address::random();
+        provider.add_account(
+            contract,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(hex!("600160005500").into()),
+        );
+
+        let mut tx = transfer().with_gas_limit(100_000);
+        if let MockTransaction::Legacy { to, .. } = &mut tx {
+            *to = TxKind::Call(contract);
+        }
+        add_funded_transaction(&provider, &pool, tx).await;
+
+        let eth_api = build_eth_api(provider, pool, PendingBlockConfig::default());
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+
+        // the slot is only written in the pending state
+        let (_, hashed_state) = eth_api.local_pending_state().await.unwrap().unwrap();
+        let storage = &hashed_state.storages[&keccak256(contract)];
+        assert_eq!(storage.storage.get(&keccak256(B256::ZERO)), Some(&U256::from(1)));
+
+        let proof = eth_api
+            .get_proof(contract, vec![JsonStorageKey::from(B256::ZERO)], Some(BlockId::pending()))
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(proof.address, contract);
+        assert_eq!(proof.storage_proof.len(), 1);
+        let storage_proof = &proof.storage_proof[0];
+        assert_eq!(storage_proof.value, U256::from(1));
+
+        // both proofs verify against the state root of the pending block
+        let account_proof = AccountProof {
+            address: proof.address,
+            info: Some(Account {
+                nonce: proof.nonce,
+                balance: proof.balance,
+                bytecode_hash: Some(proof.code_hash),
+            }),
+            proof: proof.account_proof.clone(),
+            storage_root: proof.storage_hash,
+            storage_proofs: vec![StorageProof {
+                value: storage_proof.value,
+                ..StorageProof::new(B256::ZERO).with_proof(storage_proof.proof.clone())
+            }],
+        };
+        account_proof.verify(block.header().state_root).unwrap();
+        assert!(account_proof.verify(B256::random()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pending_account_diff() {
+        let tx = transfer();
+        let sender = tx.sender();
+        let eth_api = eth_api_with(PendingBlockConfig::default(), [tx]).await;
+
+        let diff = eth_api.pending_account_diff(sender).await.unwrap();
+        assert_eq!(diff.nonce, Some((0, 1)));
+        let (latest, pending) = diff.balance.unwrap();
+        assert_eq!(latest, U256::from(1_000_000_000u64));
+        assert!(pending < latest);
+        assert!(diff.storage.is_empty());
+
+        // accounts not touched by the pending block are unchanged
+        assert!(eth_api.pending_account_diff(Address::random()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pending_state_diff() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        // PUSH1 1 PUSH1 0 SSTORE STOP
+        let contract = Address::random();
+        provider.add_account(
+            contract,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(hex!("600160005500").into()),
+        );
+        let mut tx = transfer().with_gas_limit(100_000);
+        if let MockTransaction::Legacy { to, .. } = &mut tx {
+            *to = TxKind::Call(contract);
+        }
+        let sender = tx.sender();
+        add_funded_transaction(&provider, &pool, tx).await;
+
+        let eth_api = build_eth_api(provider.clone(), pool, PendingBlockConfig::default());
+        eth_api.local_pending_block().await.unwrap().unwrap();
+
+        // the diff is taken from the build of the cached pending block, replaying the block on
+        // top of the now empty parent state would fail
+        provider.accounts.lock().clear();
+
+        let diff = eth_api.pending_state_diff().await.unwrap();
+        let sender_diff = diff.account(&sender).unwrap();
+        assert_eq!(sender_diff.nonce, Some((0, 1)));
+        let (latest, pending) = sender_diff.balance.unwrap();
+        assert_eq!(latest, U256::from(1_000_000_000u64));
+        assert!(pending < latest);
+
+        let contract_diff = diff.account(&contract).unwrap();
+        assert_eq!(
+            contract_diff.storage,
+            BTreeMap::from([(U256::ZERO, (U256::ZERO, U256::from(1)))])
+        );
+        assert_eq!(eth_api.pending_account_diff(contract).await.unwrap(), *contract_diff);
+    }
+
+    /// Runtime code that self-destructs to the caller.
+    const SELFDESTRUCT_CODE: [u8; 2] = hex!("33ff");
+
+    #[tokio::test]
+    async fn test_pending_code_of_deployed_contract() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        // init code returning `SELFDESTRUCT_CODE` as runtime code
+        let mut tx = transfer()
+            .with_gas_limit(100_000)
+            .with_input(Bytes::from_static(&hex!("6133ff6000526002601ef3")));
+        if let MockTransaction::Legacy { to, .. } = &mut tx {
+            *to = TxKind::Create;
+        }
+        let contract = tx.sender().create(0);
+        add_funded_transaction(&provider, &pool, tx).await;
+
+        let eth_api = build_eth_api(provider, pool, PendingBlockConfig::default());
+
+        let latest = eth_api.get_code(contract, None).await.unwrap();
+        assert!(latest.is_empty());
+        let pending = eth_api.get_code(contract, Some(BlockId::pending())).await.unwrap();
+        assert_eq!(pending, Bytes::from_static(&SELFDESTRUCT_CODE));
+    }
+
+    #[tokio::test]
+    async fn test_pending_code_of_destroyed_contract() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        let (contract, untouched) = (Address::random(), Address::random());
+        for address in [contract, untouched] {
+            provider.add_account(
+                address,
+                ExtendedAccount::new(1, U256::ZERO)
+                    .with_bytecode(Bytes::from_static(&SELFDESTRUCT_CODE)),
+            );
+        }
+        let mut tx = transfer().with_gas_limit(100_000);
+        if let MockTransaction::Legacy { to, .. } = &mut tx {
+            *to = TxKind::Call(contract);
+        }
+        add_funded_transaction(&provider, &pool, tx).await;
+
+        let eth_api = build_eth_api(provider, pool, PendingBlockConfig::default());
+
+        let latest = eth_api.get_code(contract, Some(BlockId::latest())).await.unwrap();
+        assert_eq!(latest, Bytes::from_static(&SELFDESTRUCT_CODE));
+        let pending = eth_api.get_code(contract, Some(BlockId::pending())).await.unwrap();
+        assert!(pending.is_empty());
+
+        // contracts not touched by the pending block keep their code
+        let pending = eth_api.get_code(untouched, Some(BlockId::pending())).await.unwrap();
+        assert_eq!(pending, Bytes::from_static(&SELFDESTRUCT_CODE));
+    }
+
+    #[tokio::test]
+    async fn test_pending_balances() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        let untouched = Address::random();
+        provider.add_account(untouched, ExtendedAccount::new(0, U256::from(7)));
+
+        // the recipient only has a balance in the pending state
+        let recipient = Address::random();
+        let mut tx = transfer().with_value(U256::from(5));
+        if let MockTransaction::Legacy { to, .. } = &mut tx {
+            *to = TxKind::Call(recipient);
+        }
+        let sender = tx.sender();
+        add_funded_transaction(&provider, &pool, tx).await;
+
+        let eth_api = build_eth_api(provider, pool, PendingBlockConfig::default());
+        let missing = Address::random();
+        let balances =
+            eth_api.pending_balances(vec![recipient, untouched, missing, sender]).await.unwrap();
+
+        assert_eq!(balances[..3], [U256::from(5), U256::from(7), U256::ZERO]);
+        assert!(balances[3] < U256::from(1_000_000_000u64 - 5));
+    }
+
+    #[tokio::test]
+    async fn test_pending_state_provider() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        let untouched = Address::random();
+        provider.add_account(untouched, ExtendedAccount::new(3, U256::from(7)));
+
+        // init code storing 1 in slot 0 and returning `SELFDESTRUCT_CODE` as runtime code
+        let mut tx = transfer()
+            .with_gas_limit(100_000)
+            .with_input(Bytes::from_static(&hex!("60016000556133ff6000526002601ef3")));
+        if let MockTransaction::Legacy { to, .. } = &mut tx {
+            *to = TxKind::Create;
+        }
+        let sender = tx.sender();
+        let contract = sender.create(0);
+        add_funded_transaction(&provider, &pool, tx).await;
+
+        let eth_api = build_eth_api(provider, pool, PendingBlockConfig::default());
+        let state = eth_api.pending_state_provider().await.unwrap().unwrap();
+
+        assert_eq!(state.account_nonce(&sender).unwrap(), Some(1));
+        assert!(state.account_balance(&sender).unwrap().unwrap() < U256::from(1_000_000_000u64));
+        assert_eq!(state.storage(contract, B256::ZERO).unwrap(), Some(U256::from(1)));
+        assert_eq!(
+            state.account_code(&contract).unwrap().unwrap().original_bytes(),
+            Bytes::from_static(&SELFDESTRUCT_CODE)
+        );
+
+        // reads of accounts the pending block doesn't touch fall through to the latest state
+        assert_eq!(state.account_nonce(&untouched).unwrap(), Some(3));
+        assert_eq!(state.account_balance(&untouched).unwrap(), Some(U256::from(7)));
+        assert_eq!(state.account_code(&untouched).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_with_fee_recipient() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        add_funded_transaction(&provider, &pool, transfer()).await;
+
+        let eth_api = build_eth_api(provider.clone(), pool, PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        for fee_recipient in [Address::with_last_byte(1), Address::with_last_byte(2)] {
+            let (block, _) =
+                eth_api.build_block_with_fee_recipient(&parent, fee_recipient).unwrap();
+            assert_eq!(block.header().beneficiary, fee_recipient);
+            assert_eq!(block.body().transactions.len(), 1);
+        }
+
+        let (block, _) = eth_api.build_block(&parent).unwrap();
+        assert_eq!(block.header().beneficiary, parent.beneficiary);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_with_bundle_state() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        let tx = transfer().with_value(U256::from(1_000));
+        let (sender, recipient) = (tx.sender(), *tx.kind().to().unwrap());
+        add_funded_transaction(&provider, &pool, tx).await;
+
+        let eth_api = build_eth_api(provider.clone(), pool, PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.body().transactions.len(), 1);
+
+        // the bundle state is the one recorded when the cached pending block was built
+        let (parent_hash, bundle_state) =
+            eth_api.local_pending_bundle_state().await.unwrap().unwrap();
+        assert_eq!(parent_hash, parent.hash());
+        let cached = eth_api.pending_block().lock().await;
+        let cached = cached.as_ref().unwrap();
+        assert_eq!(cached.block.hash(), block.hash());
+        assert_eq!(bundle_state, cached.bundle_state);
+
+        let sender_info = bundle_state.account(&sender).and_then(|acc| acc.info.clone()).unwrap();
+        assert_eq!(sender_info.nonce, 1);
+        let base_fee = block.header().base_fee_per_gas.unwrap_or_default();
+        let fee = U256::from(21_000 * 10u64);
+        assert_eq!(sender_info.balance, U256::from(1_000_000_000u64) - fee - U256::from(1_000));
+
+        let recipient_info =
+            bundle_state.account(&recipient).and_then(|acc| acc.info.clone()).unwrap();
+        assert_eq!(recipient_info.balance, U256::from(1_000));
+
+        // the beneficiary is credited with the priority fee and the block reward
+        let beneficiary = block.header().beneficiary;
+        let beneficiary_info =
+            bundle_state.account(&beneficiary).and_then(|acc| acc.info.clone()).unwrap();
+        let reward =
+            base_block_reward(&*provider.chain_spec(), block.header().number).unwrap_or_default();
+        assert_eq!(
+            beneficiary_info.balance,
+            U256::from(reward) + U256::from(21_000 * (10 - base_fee))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_next_env_attributes_async() {
+        let provider = test_provider();
+        let eth_api =
+            build_eth_api(provider.clone(), testing_pool(), PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        let attributes = eth_api.next_env_attributes_async(&parent).await.unwrap();
+        let expected = eth_api.next_env_attributes(&parent).unwrap();
+        assert_eq!(attributes.timestamp, parent.timestamp + 12);
+        assert_eq!(attributes.timestamp, expected.timestamp);
+        assert_eq!(attributes.gas_limit, expected.gas_limit);
+        assert_eq!(attributes.suggested_fee_recipient, expected.suggested_fee_recipient);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_on_genesis() {
+        let provider = genesis_only_provider(ChainSpecBuilder::mainnet().build());
+        let genesis_hash = provider.chain_spec().genesis_hash();
+        let eth_api = build_eth_api(provider, testing_pool(), PendingBlockConfig::default());
+
+        let (block, receipts) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert!(receipts.is_empty());
+
+        let header = block.header();
+        assert_eq!(header.number, 1);
+        assert_eq!(header.parent_hash, genesis_hash);
+        assert_eq!(header.withdrawals_root, None);
+        assert_eq!(header.blob_gas_used, None);
+        assert_eq!(header.excess_blob_gas, None);
+        assert_eq!(header.parent_beacon_block_root, None);
+    }
+
+    #[tokio::test]
+    async fn test_pending_base_fee_at_london_activation() {
+        // the genesis block doesn't have a base fee, but the pending block does
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .berlin_activated()
+            .with_fork(EthereumHardfork::London, ForkCondition::Block(1))
+            .build();
+        let provider = genesis_only_provider(chain_spec);
+        assert_eq!(provider.chain_spec().genesis_header().base_fee_per_gas, None);
+
+        let eth_api =
+            build_eth_api(provider.clone(), testing_pool(), PendingBlockConfig::default());
+        let env = eth_api.pending_block_env_and_cfg().unwrap();
+        assert_eq!(env.evm_env.block_env.basefee, INITIAL_BASE_FEE);
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.header().base_fee_per_gas, Some(INITIAL_BASE_FEE));
+
+        let config = PendingBlockConfig::default().with_fallback_base_fee(Some(42));
+        let eth_api = build_eth_api(provider, testing_pool(), config);
+        let env = eth_api.pending_block_env_and_cfg().unwrap();
+        assert_eq!(env.evm_env.block_env.basefee, 42);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_on_pre_cancun_genesis() {
+        // the genesis block doesn't have any Cancun fields, but the pending block does
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .shanghai_activated()
+            .with_fork(EthereumHardfork::Cancun, ForkCondition::Timestamp(1))
+            .build();
+        let provider = genesis_only_provider(chain_spec);
+        let genesis_hash = provider.chain_spec().genesis_hash();
+        assert_eq!(provider.chain_spec().genesis_header().parent_beacon_block_root, None);
+
+        let eth_api = build_eth_api(provider, testing_pool(), PendingBlockConfig::default());
+
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        let header = block.header();
+        assert_eq!(header.number, 1);
+        assert_eq!(header.parent_hash, genesis_hash);
+        assert_eq!(header.withdrawals_root, Some(EMPTY_WITHDRAWALS));
+        assert_eq!(header.blob_gas_used, Some(0));
+        assert_eq!(header.excess_blob_gas, Some(0));
+        assert_eq!(header.parent_beacon_block_root, Some(B256::ZERO));
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_on_pruned_parent_state() {
+        let provider = test_provider();
+        let genesis = provider.sealed_header_by_id(BlockId::number(0)).unwrap().unwrap();
+        let header = Header {
+            number: 1,
+            parent_hash: genesis.hash(),
+            gas_limit: 30_000_000,
+            ..Default::default()
+        };
+        provider.add_block(header.hash_slow(), Block { header, body: Default::default() });
+
+        let eth_api =
+            build_eth_api(provider.clone(), testing_pool(), PendingBlockConfig::default());
+        assert!(eth_api.build_block(&genesis).is_ok());
+
+        // the history after the genesis block is pruned, so only the latest state is available
+        provider.add_prune_checkpoint(
+            PruneSegment::AccountHistory,
+            PruneCheckpoint { block_number: Some(1), tx_number: None, prune_mode: PruneMode::Full },
+        );
+        let err = eth_api.build_block(&genesis).unwrap_err();
+        assert!(matches!(err, EthApiError::PendingParentStatePruned(0)));
+
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.header().number, 2);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_fails_on_database_error() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        let failing = transfer().with_gas_price(20);
+        let failing_sender = failing.sender();
+        let succeeding = transfer();
+        let succeeding_sender = succeeding.sender();
+        add_funded_transaction(&provider, &pool, failing).await;
+        add_funded_transaction(&provider, &pool, succeeding).await;
+
+        let eth_api = build_eth_api(provider.clone(), pool.clone(), PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        // a database error isn't caused by the transaction, so the build is aborted instead of
+        // skipping the transaction
+        let result = eth_api.build_block_with_inspector(
+            &parent,
+            eth_api.next_env_attributes(&parent).unwrap(),
+            None,
+            None,
+            FailingCaller(failing_sender),
+            PendingBlockConfig::default(),
+        );
+        assert!(result.is_err());
+
+        // transactions that don't hit the error still build
+        let (block, receipts) = eth_api
+            .build_block_with_inspector(
+                &parent,
+                eth_api.next_env_attributes(&parent).unwrap(),
+                None,
+                None,
+                FailingCaller(Address::ZERO),
+                PendingBlockConfig::default(),
+            )
+            .unwrap();
+        assert_eq!(block.senders(), &[failing_sender, succeeding_sender]);
+        assert_eq!(receipts.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_includes_mid_build_replacement() {
+        for include_replacements in [false, true] {
+            let provider = test_provider();
+            let pool = testing_pool();
+
+            let original = transfer();
+            let replacement = original.inc_price_by(10).rng_hash();
+            let (original_hash, replacement_hash) = (*original.get_hash(), *replacement.get_hash());
+            add_funded_transaction(&provider, &pool, original).await;
+
+            let config =
+                PendingBlockConfig::default().with_include_replacements(include_replacements);
+            let eth_api = build_eth_api(provider.clone(), pool.clone(), config.clone());
+            let parent = provider.latest_header().unwrap().unwrap();
+
+            let inspector = ReplaceOnCall { pool, replacement: Some(replacement) };
+            let (block, _) = eth_api
+                .build_block_with_inspector(
+                    &parent,
+                    eth_api.next_env_attributes(&parent).unwrap(),
+                    None,
+                    None,
+                    inspector,
+                    config,
+                )
+                .unwrap();
+
+            let expected = if include_replacements { replacement_hash } else { original_hash };
+            assert_eq!(block.body().transactions.len(), 1);
+            assert_eq!(*block.body().transactions[0].tx_hash(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_by_hash() {
+        let eth_api = eth_api_with(PendingBlockConfig::default(), [transfer()]).await;
+        assert!(eth_api.rpc_block(B256::random().into(), false).await.unwrap().is_none());
+
+        let hash = eth_api.pending_block_hash().await.unwrap().unwrap();
+        let rpc_block = eth_api.rpc_block(hash.into(), false).await.unwrap().unwrap();
+        assert_eq!(rpc_block.header.hash, hash);
+        assert_eq!(rpc_block.header.number, 1);
+        assert_eq!(rpc_block.transactions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_rpc_hash_convention() {
+        let eth_api = eth_api_with(PendingBlockConfig::default(), [transfer()]).await;
+        let hash = eth_api.pending_block_hash().await.unwrap().unwrap();
+
+        // the header and the transactions of the pending response refer to the built block
+        let rpc_block = eth_api.rpc_block(BlockId::pending(), true).await.unwrap().unwrap();
+        let json = serde_json::to_value(&rpc_block).unwrap();
+        assert_eq!(json["hash"], serde_json::json!(hash));
+        assert_eq!(json["transactions"].as_array().unwrap().len(), 1);
+        assert_eq!(json["transactions"][0]["blockHash"], serde_json::json!(hash));
+        assert_eq!(json["transactions"][0]["blockNumber"], json["number"]);
+
+        let header = eth_api.rpc_block_header(BlockId::pending()).await.unwrap().unwrap();
+        assert_eq!(header.hash, hash);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_build_panic() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        add_funded_transaction(&provider, &pool, transfer()).await;
+
+        let eth_api = build_eth_api(provider.clone(), pool, PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+        let attributes = eth_api.next_env_attributes(&parent).unwrap();
+
+        let err = eth_api
+            .build_block_with_inspector(
+                &parent,
+                attributes,
+                None,
+                None,
+                PanickingInspector,
+                PendingBlockConfig::default(),
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, EthApiError::PendingBuildPanicked(ref message) if message == "inspector bug")
+        );
+
+        // the panic didn't take down the api, it still serves the pending block
+        let rpc_block = eth_api.rpc_block(BlockId::pending(), false).await.unwrap().unwrap();
+        assert_eq!(rpc_block.header.number, 1);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_verify_determinism() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        add_funded_transaction(&provider, &pool, transfer()).await;
+
+        let config = PendingBlockConfig::default().with_verify_determinism(true);
+        let eth_api = build_eth_api(provider.clone(), pool, config.clone());
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        // a deterministic build passes and is executed twice
+        let mut inspector = CallCounter::default();
+        let (block, _) = eth_api
+            .build_block_with_inspector(
+                &parent,
+                eth_api.next_env_attributes(&parent).unwrap(),
+                None,
+                None,
+                &mut inspector,
+                config.clone(),
+            )
+            .unwrap();
+        assert_eq!(block.body().transactions.len(), 1);
+        assert_eq!(inspector.calls, 2);
+
+        // the second build skips the transaction, so the builds diverge
+        let err = eth_api
+            .build_block_with_inspector(
+                &parent,
+                eth_api.next_env_attributes(&parent).unwrap(),
+                None,
+                None,
+                FlakyInspector::default(),
+                config,
+            )
+            .unwrap_err();
+        assert!(matches!(err, EthApiError::PendingBuildNondeterministic { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_with_inspector() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        for _ in 0..2 {
+            add_funded_transaction(&provider, &pool, transfer()).await;
+        }
+
+        let eth_api = build_eth_api(provider.clone(), pool, PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+        let attributes = eth_api.next_env_attributes(&parent).unwrap();
+
+        let mut inspector = CallCounter::default();
+        let (block, _) = eth_api
+            .build_block_with_inspector(
+                &parent,
+                attributes,
+                None,
+                None,
+                &mut inspector,
+                PendingBlockConfig::default(),
+            )
+            .unwrap();
+        assert_eq!(block.body().transactions.len(), 2);
+        assert_eq!(inspector.calls, 2);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_candidates() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        let first = transfer();
+        let second = transfer().with_gas_price(20);
+        let (first_hash, second_hash) = (*first.get_hash(), *second.get_hash());
+        add_funded_transaction(&provider, &pool, first).await;
+        add_funded_transaction(&provider, &pool, second).await;
+
+        let eth_api = build_eth_api(provider.clone(), pool, PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        // leaves room for a single transfer
+        let config = PendingBlockConfig::default().with_target_gas_fraction(Some(0.001)).unwrap();
+        let policies = [
+            PendingBlockPolicy {
+                ordering: PendingTransactionOrdering::Pool,
+                config: config.clone(),
+            },
+            PendingBlockPolicy { ordering: PendingTransactionOrdering::Submission, config },
+        ];
+
+        let candidates = eth_api.build_candidate_blocks(&parent, &policies).unwrap();
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].0, policies[0]);
+        assert_eq!(candidates[0].1.transactions, vec![second_hash]);
+        assert_eq!(candidates[1].0, policies[1]);
+        assert_eq!(candidates[1].1.transactions, vec![first_hash]);
+        assert_ne!(candidates[0].1.hash, candidates[1].1.hash);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_dependency_ordering() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        // init code returning `PUSH1 1 PUSH1 0 SSTORE STOP` as runtime code
+        let mut deploy = transfer()
+            .with_gas_limit(100_000)
+            .with_input(Bytes::from_static(&hex!("656001600055006000526006601af3")));
+        if let MockTransaction::Legacy { to, .. } = &mut deploy {
+            *to = TxKind::Create;
+        }
+        let contract = deploy.sender().create(0);
+
+        // the call pays more, so the pool yields it before the deployment
+        let mut call = transfer().with_gas_limit(100_000).with_gas_price(20);
+        if let MockTransaction::Legacy { to, .. } = &mut call {
+            *to = TxKind::Call(contract);
+        }
+        let (deploy_hash, call_hash) = (*deploy.get_hash(), *call.get_hash());
+        add_funded_transaction(&provider, &pool, deploy).await;
+        add_funded_transaction(&provider, &pool, call).await;
+
+        let eth_api = build_eth_api(provider.clone(), pool, PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        let policies = [
+            PendingBlockPolicy::default(),
+            PendingBlockPolicy {
+                ordering: PendingTransactionOrdering::Dependencies,
+                config: PendingBlockConfig::default(),
+            },
+        ];
+        let candidates = eth_api.build_candidate_blocks(&parent, &policies).unwrap();
+        assert_eq!(candidates[0].1.transactions, vec![call_hash, deploy_hash]);
+        assert_eq!(candidates[1].1.transactions, vec![deploy_hash, call_hash]);
+
+        // only the call after the deployment executes the contract and writes its slot
+        assert!(candidates[1].1.gas_used > candidates[0].1.gas_used);
+    }
+
+    #[tokio::test]
+    async fn test_pending_transactions_by_sender() {
+        // the other sender pays more, so its transaction is included first
+        let first = transfer();
+        let second = first.next();
+        let other = transfer().with_gas_price(20);
+        let sender = first.sender();
+        let hashes = [*first.get_hash(), *second.get_hash()];
+
+        let eth_api = eth_api_with(PendingBlockConfig::default(), [first, second, other]).await;
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.body().transactions.len(), 3);
+
+        let txs = eth_api.pending_transactions_by_sender(sender).await.unwrap();
+        assert_eq!(txs.len(), 2);
+        for ((tx, tx_info), (hash, index)) in txs.iter().zip(hashes.iter().zip([1, 2])) {
+            assert_eq!(tx.signer(), sender);
+            assert_eq!(tx.tx_hash(), hash);
+            assert_eq!(tx_info.hash, Some(*hash));
+            assert_eq!(tx_info.index, Some(index));
+            assert_eq!(tx_info.block_hash, Some(block.hash()));
+        }
+
+        assert!(eth_api
+            .pending_transactions_by_sender(Address::random())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_call_many_pending() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        // increments the counter in slot 0 and returns its new value:
+        // PUSH1 0 SLOAD PUSH1 1 ADD DUP1 PUSH1 0 SSTORE PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+        let counter = Address::random();
+        provider.add_account(
+            counter,
+            ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode(hex!("6000546001018060005560005260206000f3").into()),
+        );
+        // returns the block number and timestamp:
+        // NUMBER PUSH1 0 MSTORE TIMESTAMP PUSH1 32 MSTORE PUSH1 64 PUSH1 0 RETURN
+        let block_info = Address::random();
+        provider.add_account(
+            block_info,
+            ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode(hex!("4360005242602052604060006000f3").into()),
+        );
+        let call = |to: Address| {
+            let mut tx = transfer().with_gas_limit(100_000);
+            if let MockTransaction::Legacy { to: kind, .. } = &mut tx {
+                *kind = TxKind::Call(to);
+            }
+            tx
+        };
+
+        // the pending block increments the counter once
+        add_funded_transaction(&provider, &pool, call(counter)).await;
+
+        let first = call(counter);
+        let second = first.next();
+        let mut third = second.next();
+        if let MockTransaction::Legacy { to, .. } = &mut third {
+            *to = TxKind::Call(block_info);
+        }
+        provider.add_account(first.sender(), ExtendedAccount::new(0, U256::from(1_000_000_000u64)));
+
+        let eth_api = build_eth_api(provider, pool, PendingBlockConfig::default());
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        let results = eth_api
+            .call_many_pending(vec![
+                first.into_consensus(),
+                second.into_consensus(),
+                third.into_consensus(),
+            ])
+            .await
+            .unwrap();
+
+        // the calls see the state after the pending block and the second one reads the counter
+        // written by the first one
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].error, None);
+        assert_eq!(results[0].value, Some(B256::with_last_byte(2).into()));
+        assert_eq!(results[1].error, None);
+        assert_eq!(results[1].value, Some(B256::with_last_byte(3).into()));
+
+        // the calls are executed in the env of the block after the pending block
+        assert_eq!(results[2].error, None);
+        let output = results[2].value.clone().unwrap();
+        let number = U256::from_be_slice(&output[..32]);
+        let timestamp = U256::from_be_slice(&output[32..]);
+        assert_eq!(number, U256::from(block.header().number + 1));
+        assert!(timestamp > U256::from(block.header().timestamp));
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_digest() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        add_funded_transaction(&provider, &pool, transfer()).await;
+
+        let eth_api = build_eth_api(provider.clone(), pool.clone(), PendingBlockConfig::default());
+        let digest = eth_api.pending_block_digest().await.unwrap().unwrap();
+        assert_eq!(eth_api.pending_block_digest().await.unwrap(), Some(digest));
+
+        // rebuilding the block from the same transactions yields the same digest
+        expire_pending_block(&eth_api).await;
+        assert_eq!(eth_api.pending_block_digest().await.unwrap(), Some(digest));
+
+        // a new transaction changes the composition of the block
+        add_funded_transaction(&provider, &pool, transfer()).await;
+        expire_pending_block(&eth_api).await;
+        let changed = eth_api.pending_block_digest().await.unwrap().unwrap();
+        assert_ne!(changed, digest);
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.body().transactions.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pending_build_limits_ignore_bundle() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        for _ in 0..2 {
+            add_funded_transaction(&provider, &pool, transfer()).await;
+        }
+
+        let bundle = [transfer(), transfer()]
+            .map(|tx| {
+                provider.add_account(
+                    tx.sender(),
+                    ExtendedAccount::new(0, U256::from(1_000_000_000u64)),
+                );
+                tx.into_consensus()
+            })
+            .to_vec();
+
+        // the bundle alone exceeds the transaction limit, which still admits one pool transaction
+        let config = PendingBlockConfig::default().with_max_transactions(Some(1));
+        let eth_api = build_eth_api(provider.clone(), pool, config);
+        let parent = provider.latest_header().unwrap().unwrap();
+        let (block, ..) = eth_api.build_block_with_bundle(&parent, &bundle).unwrap();
+        assert_eq!(block.body().transactions.len(), 3);
+        assert_eq!(block.header().gas_used, 3 * 21_000);
+
+        // the gas target only covers the pool transactions as well
+        let pool = testing_pool();
+        for _ in 0..2 {
+            add_funded_transaction(&provider, &pool, transfer()).await;
+        }
+        let config = PendingBlockConfig::default().with_pending_gas_cap(Some(21_000));
+        let eth_api = build_eth_api(provider, pool, config);
+        let (block, ..) = eth_api.build_block_with_bundle(&parent, &bundle).unwrap();
+        assert_eq!(block.body().transactions.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_with_bundle() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        // PUSH1 0 PUSH1 0 REVERT
+        let contract = Address::random();
+        provider.add_account(
+            contract,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(hex!("60006000fd").into()),
+        );
+
+        let pool_tx = transfer();
+        let pool_hash = *pool_tx.get_hash();
+        add_funded_transaction(&provider, &pool, pool_tx).await;
+
+        // the bundle transactions are not in the pool, so their senders are funded directly
+        let succeeding = transfer();
+        let mut reverting = transfer().with_gas_limit(100_000);
+        if let MockTransaction::Legacy { to, .. } = &mut reverting {
+            *to = TxKind::Call(contract);
+        }
+        for tx in [&succeeding, &reverting] {
+            provider
+                .add_account(tx.sender(), ExtendedAccount::new(0, U256::from(1_000_000_000u64)));
+        }
+        let bundle = vec![succeeding.into_consensus(), reverting.into_consensus()];
+
+        let eth_api = build_eth_api(provider.clone(), pool, PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+        let (block, receipts, results) = eth_api.build_block_with_bundle(&parent, &bundle).unwrap();
+
+        // the bundle is executed in order at the top of the block, followed by the pool
+        assert_eq!(tx_hashes(&block), vec![*bundle[0].tx_hash(), *bundle[1].tx_hash(), pool_hash]);
+        assert_eq!(receipts.len(), 3);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].hash, *bundle[0].tx_hash());
+        assert!(results[0].success);
+        assert_eq!(results[0].gas_used, 21_000);
+        assert_eq!(results[1].hash, *bundle[1].tx_hash());
+        assert!(!results[1].success);
+        assert!(results[1].gas_used > 21_000);
+        assert_eq!(receipts[1].cumulative_gas_used, results[0].gas_used + results[1].gas_used);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_max_fee_ordering() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        // the cheaper transaction reserves more gas than it uses, so it doesn't fit after the
+        // pricier one, but the pricier one fits after it
+        let pricier = transfer().with_gas_price(30);
+        let reserving = transfer().with_gas_price(25).with_gas_limit(42_000);
+        let gas_prices =
+            HashMap::from([(*pricier.get_hash(), 30u128), (*reserving.get_hash(), 25u128)]);
+        add_funded_transaction(&provider, &pool, pricier).await;
+        add_funded_transaction(&provider, &pool, reserving).await;
+
+        let eth_api = build_eth_api(provider.clone(), pool, PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        let config = PendingBlockConfig::default().with_pending_gas_cap(Some(42_000));
+        let policies = [
+            PendingBlockPolicy {
+                ordering: PendingTransactionOrdering::Pool,
+                config: config.clone(),
+            },
+            PendingBlockPolicy { ordering: PendingTransactionOrdering::MaxFee, config },
+        ];
+        let candidates = eth_api.build_candidate_blocks(&parent, &policies).unwrap();
+
+        // the pending block has no base fee and every transfer uses 21000 gas
+        let fees: Vec<u128> = candidates
+            .iter()
+            .map(|(_, summary)| {
+                summary.transactions.iter().map(|hash| gas_prices[hash] * 21_000).sum()
+            })
+            .collect();
+        assert_eq!(candidates[0].1.transactions.len(), 1);
+        assert_eq!(candidates[1].1.transactions.len(), 2);
+        assert!(fees[1] >= fees[0]);
+        assert_eq!(fees, vec![630_000, 1_155_000]);
+    }
+
+    #[tokio::test]
+    async fn test_pending_receipt_by_index() {
+        let eth_api = eth_api_with(PendingBlockConfig::default(), (0..2).map(|_| transfer())).await;
+
+        let (_, receipts) = eth_api.local_pending_block().await.unwrap().unwrap();
+        let receipt = eth_api.pending_receipt_by_index(1).await.unwrap().unwrap();
+        assert_eq!(receipt, receipts[1]);
+        assert_eq!(receipt.cumulative_gas_used, 42_000);
+
+        assert_eq!(eth_api.pending_receipt_by_index(2).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_pending_tx_proof() {
+        let eth_api = eth_api_with(PendingBlockConfig::default(), (0..3).map(|_| transfer())).await;
+
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        let transactions_root = block.header().transactions_root;
+        for (index, tx) in block.body().transactions.iter().enumerate() {
+            let proof = eth_api.pending_tx_proof(*tx.tx_hash()).await.unwrap().unwrap();
+            assert_eq!(proof.index, index);
+            proof.verify(transactions_root).unwrap();
+            assert!(proof.verify(B256::random()).is_err());
+        }
+
+        assert_eq!(eth_api.pending_tx_proof(B256::random()).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_logs_pending() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        // PUSH1 1 PUSH1 0 PUSH1 0 LOG1 PUSH1 2 PUSH1 0 PUSH1 0 LOG1 STOP
+        let contract = Address::random();
+        provider.add_account(
+            contract,
+            ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode(hex!("600160006000a1600260006000a100").into()),
+        );
+        for _ in 0..2 {
+            let mut tx = transfer().with_gas_limit(100_000);
+            if let MockTransaction::Legacy { to, .. } = &mut tx {
+                *to = TxKind::Call(contract);
+            }
+            add_funded_transaction(&provider, &pool, tx).await;
+        }
+
+        let config = PendingBlockConfig::default().with_sort_pending_logs(true);
+        let eth_api = build_eth_api(provider, pool, config);
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        let eth_filter =
+            EthFilter::new(eth_api, Default::default(), TokioTaskExecutor::default().boxed());
+
+        let filter = Filter::new().from_block(BlockNumberOrTag::Pending);
+        let logs = EthFilterApiServer::logs(&eth_filter, filter).await.unwrap();
+        let positions = logs
+            .iter()
+            .map(|log| {
+                assert_eq!(log.block_hash, Some(block.hash()));
+                (log.transaction_index.unwrap(), log.log_index.unwrap())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(0, 0), (0, 1), (1, 2), (1, 3)]);
+
+        // a range up to the pending block also contains its logs
+        let filter = Filter::new()
+            .from_block(BlockNumberOrTag::Latest)
+            .to_block(BlockNumberOrTag::Pending)
+            .event_signature(B256::with_last_byte(2));
+        let logs = EthFilterApiServer::logs(&eth_filter, filter).await.unwrap();
+        let log_indices = logs.iter().map(|log| log.log_index.unwrap()).collect::<Vec<_>>();
+        assert_eq!(log_indices, vec![1, 3]);
+
+        // without the pending block, no logs are returned
+        let filter = Filter::new().to_block(BlockNumberOrTag::Latest);
+        assert!(EthFilterApiServer::logs(&eth_filter, filter).await.unwrap().is_empty());
+
+        // a range from the pending block must also end there
+        let filter =
+            Filter::new().from_block(BlockNumberOrTag::Pending).to_block(BlockNumberOrTag::Latest);
+        let err = EthFilterApiServer::logs(&eth_filter, filter).await.unwrap_err();
+        assert_eq!(err.code(), jsonrpsee::types::error::INVALID_PARAMS_CODE);
+        assert_eq!(err.message(), "invalid block range params");
+    }
+
+    #[tokio::test]
+    async fn test_pending_receipts_logs_bloom() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        // PUSH1 42 PUSH1 0 PUSH1 0 LOG1 STOP
+        let contract = Address::random();
+        provider.add_account(
+            contract,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(hex!("602a60006000a100").into()),
+        );
+        let mut logging = transfer().with_gas_limit(100_000).with_gas_price(20);
+        if let MockTransaction::Legacy { to, .. } = &mut logging {
+            *to = TxKind::Call(contract);
+        }
+        add_funded_transaction(&provider, &pool, logging).await;
+        add_funded_transaction(&provider, &pool, transfer()).await;
+
+        let eth_api = build_eth_api(provider, pool, PendingBlockConfig::default());
+
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        let receipts = eth_api.block_receipts(BlockId::pending()).await.unwrap().unwrap();
+        assert_eq!(receipts.len(), 2);
+
+        // each receipt carries the bloom of its own logs, not the one of the block
+        let topic = B256::with_last_byte(42);
+        let bloom = *receipts[0].inner.logs_bloom();
+        assert!(bloom.contains_input(BloomInput::Raw(contract.as_slice())));
+        assert!(bloom.contains_input(BloomInput::Raw(topic.as_slice())));
+        assert_eq!(*receipts[1].inner.logs_bloom(), Bloom::ZERO);
+        assert_eq!(block.header().logs_bloom, bloom);
+    }
+
+    #[tokio::test]
+    async fn test_pending_transaction_by_index() {
+        let eth_api = eth_api_with(PendingBlockConfig::default(), (0..2).map(|_| transfer())).await;
+
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        let expected = &block.body().transactions[1];
+        let (tx, tx_info) = eth_api.pending_transaction_by_index(1).await.unwrap().unwrap();
+        assert_eq!(tx.tx_hash(), expected.tx_hash());
+        assert_eq!(tx_info.block_hash, Some(block.hash()));
+        assert_eq!(tx_info.block_number, Some(1));
+        assert_eq!(tx_info.index, Some(1));
+        assert!(eth_api.pending_transaction_by_index(2).await.unwrap().is_none());
+
+        // served through the rpc with the pending tag
+        let rpc_tx = eth_api
+            .transaction_by_block_and_tx_index(BlockId::pending(), 1)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(rpc_tx.inner.tx_hash(), expected.tx_hash());
+        assert_eq!(rpc_tx.block_hash, Some(block.hash()));
+        assert_eq!(rpc_tx.transaction_index, Some(1));
+        assert!(eth_api
+            .transaction_by_block_and_tx_index(BlockId::pending(), 2)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pending_transactions_with_receipts() {
+        let with_calldata = transfer()
+            .with_gas_limit(100_000)
+            .with_gas_price(20)
+            .with_input(Bytes::from(vec![0x01; 100]));
+        let eth_api =
+            eth_api_with(PendingBlockConfig::default(), [transfer(), with_calldata]).await;
+
+        let (block, receipts) = eth_api.local_pending_block().await.unwrap().unwrap();
+        let pairs = eth_api
+            .pending_transactions_with_receipts()
+            .await
+            .unwrap()
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(pairs.len(), 2);
+
+        let mut cumulative_gas_used = 0;
+        for (index, (tx, receipt)) in pairs.iter().enumerate() {
+            assert_eq!(tx.tx_hash(), block.body().transactions[index].tx_hash());
+            assert_eq!(tx.signer(), block.senders()[index]);
+            assert_eq!(receipt, &receipts[index]);
+
+            // only the transfer without calldata uses the intrinsic gas of a plain transfer
+            let gas_used = receipt.cumulative_gas_used - cumulative_gas_used;
+            cumulative_gas_used = receipt.cumulative_gas_used;
+            assert_eq!(gas_used == 21_000, tx.input().is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sticky_pending_block() {
+        let provider = test_provider();
+        let pool = testing_pool();
+
+        let carried = transfer();
+        let carried_hash = *carried.get_hash();
+        add_funded_transaction(&provider, &pool, carried).await;
+
+        // leaves room for a single transfer
+        let config = PendingBlockConfig::default()
+            .with_target_gas_fraction(Some(0.001))
+            .unwrap()
+            .with_sticky_pending(true);
+        let eth_api = build_eth_api(provider.clone(), pool.clone(), config);
+
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(*block.body().transactions[0].tx_hash(), carried_hash);
+
+        // a better paying transaction doesn't replace the transaction of the previous build
+        let better = transfer().with_gas_price(20);
+        let better_hash = *better.get_hash();
+        add_funded_transaction(&provider, &pool, better).await;
+
+        expire_pending_block(&eth_api).await;
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.body().transactions.len(), 1);
+        assert_eq!(*block.body().transactions[0].tx_hash(), carried_hash);
+
+        // once the carried transaction left the pool, e.g. because it was mined, it's replaced
+        pool.remove_transactions(vec![carried_hash]);
+
+        expire_pending_block(&eth_api).await;
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.body().transactions.len(), 1);
+        assert_eq!(*block.body().transactions[0].tx_hash(), better_hash);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_status_stale_after_new_block() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        add_funded_transaction(&provider, &pool, transfer()).await;
+
+        let eth_api = build_eth_api(provider.clone(), pool, PendingBlockConfig::default());
+        assert_eq!(eth_api.pending_block_status().await.unwrap(), None);
+
+        eth_api.local_pending_block().await.unwrap().unwrap();
+        let status = eth_api.pending_block_status().await.unwrap().unwrap();
+        assert!(!status.is_stale);
+
+        // a new block on top of the parent of the pending block
+        provider.add_block(
+            B256::random(),
+            Block {
+                header: Header { number: 1, gas_limit: 30_000_000, ..Default::default() },
+                body: Default::default(),
+            },
+        );
+
+        let status = eth_api.pending_block_status().await.unwrap().unwrap();
+        assert!(status.is_stale);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_number_after_reorg() {
+        let provider = test_provider();
+        let mut parent_hash = provider.latest_header().unwrap().unwrap().hash();
+        for number in 1..=2 {
+            let header =
+                Header { number, parent_hash, gas_limit: 30_000_000, ..Default::default() };
+            parent_hash = header.hash_slow();
+            provider.add_block(parent_hash, Block { header, body: Default::default() });
+        }
+
+        let eth_api =
+            build_eth_api(provider.clone(), testing_pool(), PendingBlockConfig::default());
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.header().number, 3);
+
+        // reorg to a shorter chain with a different block 1
+        provider.headers.lock().retain(|_, header| header.number == 0);
+        provider.blocks.lock().retain(|_, block| block.header.number == 0);
+        let genesis_hash = provider.latest_header().unwrap().unwrap().hash();
+        let header = Header {
+            number: 1,
+            parent_hash: genesis_hash,
+            gas_limit: 30_000_000,
+            extra_data: Bytes::from_static(b"reorg"),
+            ..Default::default()
+        };
+        let reorg_hash = header.hash_slow();
+        provider.add_block(reorg_hash, Block { header, body: Default::default() });
+
+        let pending = eth_api.pending_block_env_and_cfg().unwrap();
+        assert_eq!(pending.evm_env.block_env.number, 2);
+
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.header().number, 2);
+        assert_eq!(block.header().parent_hash, reorg_hash);
+
+        let rpc_block = eth_api.rpc_block(BlockId::pending(), false).await.unwrap().unwrap();
+        assert_eq!(rpc_block.header.number, 2);
+        assert_eq!(rpc_block.header.parent_hash, reorg_hash);
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_build_spans() {
+        let provider = test_provider();
+        let pool = testing_pool();
+        add_funded_transaction(&provider, &pool, transfer()).await;
+
+        let eth_api = build_eth_api(provider.clone(), pool, PendingBlockConfig::default());
+        let parent = provider.latest_header().unwrap().unwrap();
+
+        let spans = PendingBuildSpans::default();
+        let subscriber = tracing_subscriber::registry().with(spans.clone());
+        tracing::subscriber::with_default(subscriber, || eth_api.build_block(&parent).unwrap());
+
+        assert_eq!(
+            *spans.0.lock().unwrap(),
+            [
+                "build_pending_block",
+                "select_transactions",
+                "execute_transaction",
+                "state_root",
+                "seal_block"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pending_block_without_pool_on_timeout() {
+        let provider = test_provider();
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel();
+        let pool = Pool::new(
+            MockTransactionValidator::default(),
+            MockOrdering::default(),
+            BlockingBlobStore {
+                inner: InMemoryBlobStore::default(),
+                entered: Mutex::new(entered_tx),
+                release: Mutex::new(release_rx),
+            },
+            Default::default(),
+        );
+
+        let tx = transfer();
+        let hash = *tx.get_hash();
+        provider.add_account(tx.sender(), ExtendedAccount::new(0, U256::from(1_000_000_000u64)));
+        pool.add_transaction(TransactionOrigin::External, tx).await.unwrap();
+
+        let eth_api = EthApiBuilder::new(
+            provider.clone(),
+            pool.clone(),
+            NoopNetwork::default(),
+            EthEvmConfig::new(provider.chain_spec()),
+        )
+        .pending_block_config(
+            PendingBlockConfig::default().with_pool_timeout(Some(Duration::from_millis(100))),
+        )
+        .build();
+
+        // the pool is available, so its transactions are included
+        let (block, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(block.body().transactions.len(), 1);
+        assert_eq!(*block.body().transactions[0].tx_hash(), hash);
+
+        // inserting a blob transaction locks the pool until its blob is stored
+        let blocked_pool = pool.clone();
+        let adder = std::thread::spawn(move || {
+            futures::executor::block_on(blocked_pool.add_transaction(
+                TransactionOrigin::External,
+                MockTransaction::eip4844_with_sidecar(BlobTransactionSidecar::default()),
+            ))
+        });
+        entered_rx.recv().unwrap();
+
+        eth_api.pending_block().lock().await.as_mut().unwrap().expires_at =
+            Instant::now() - Duration::from_secs(1);
+        let started = Instant::now();
+        let (block, receipts) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(block.body().transactions.is_empty());
+        assert!(receipts.is_empty());
+
+        release_tx.send(()).unwrap();
+        adder.join().unwrap().unwrap();
     }
 }
@@ -22,3 +22,70 @@ where
     Provider: BlockReader,
 {
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::EthApiBuilder;
+    use alloy_consensus::Header;
+    use alloy_eips::BlockId;
+    use alloy_primitives::{B256, U256};
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::Block;
+    use reth_provider::{
+        test_utils::{ExtendedAccount, MockEthProvider},
+        ChainSpecProvider,
+    };
+    use reth_rpc_eth_api::helpers::{LoadPendingBlock, Trace};
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        PoolTransaction, TransactionOrigin, TransactionPool,
+    };
+    use revm_inspectors::tracing::TracingInspectorConfig;
+
+    #[tokio::test]
+    async fn test_trace_pending_block() {
+        let provider = MockEthProvider::default();
+        provider.add_block(
+            B256::random(),
+            Block {
+                header: Header { gas_limit: 30_000_000, ..Default::default() },
+                body: Default::default(),
+            },
+        );
+
+        let pool = testing_pool();
+        for _ in 0..3 {
+            let tx = MockTransaction::legacy().with_gas_limit(21_000).with_gas_price(10);
+            provider
+                .add_account(tx.sender(), ExtendedAccount::new(0, U256::from(1_000_000_000u64)));
+            pool.add_transaction(TransactionOrigin::External, tx).await.unwrap();
+        }
+
+        let eth_api = EthApiBuilder::new(
+            provider.clone(),
+            pool,
+            NoopNetwork::default(),
+            EthEvmConfig::new(provider.chain_spec()),
+        )
+        .build();
+
+        let (pending, _) = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(pending.body().transactions.len(), 3);
+
+        let traces = eth_api
+            .trace_block_with(
+                BlockId::pending(),
+                None,
+                TracingInspectorConfig::default_parity(),
+                |tx_info, inspector, _, _, _| {
+                    Ok(inspector.into_parity_builder().into_localized_transaction_traces(tx_info))
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(traces.len(), pending.body().transactions.len());
+    }
+}
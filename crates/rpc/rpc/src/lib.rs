@@ -56,6 +56,6 @@ pub use otterscan::OtterscanApi;
 pub use reth::RethApi;
 pub use rpc::RPCApi;
 pub use trace::TraceApi;
-pub use txpool::TxPoolApi;
+pub use txpool::{PendingTransactionSubmission, TxPoolApi};
 pub use validation::{ValidationApi, ValidationApiConfig};
 pub use web3::Web3Api;
@@ -1,8 +1,11 @@
 use core::fmt;
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
 
 use alloy_consensus::Transaction;
-use alloy_primitives::Address;
+use alloy_primitives::{Address, TxHash};
 use alloy_rpc_types_txpool::{
     TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus,
 };
@@ -32,6 +35,49 @@ impl<Pool, Eth> TxPoolApi<Pool, Eth> {
     }
 }
 
+/// A pending transaction annotated with the time it entered the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingTransactionSubmission {
+    /// Hash of the transaction.
+    pub hash: TxHash,
+    /// Sender of the transaction.
+    pub sender: Address,
+    /// Nonce of the transaction.
+    pub nonce: u64,
+    /// Unix timestamp (in seconds) at which the transaction was added to the pool.
+    ///
+    /// `None` if the submission time can't be represented as a unix timestamp.
+    pub submitted_at: Option<u64>,
+}
+
+impl<Pool, Eth> TxPoolApi<Pool, Eth>
+where
+    Pool: TransactionPool + 'static,
+{
+    /// Returns all pending transactions together with the time they were submitted to the pool.
+    pub fn pending_transaction_submissions(&self) -> Vec<PendingTransactionSubmission> {
+        let now = Instant::now();
+        let system_now = SystemTime::now();
+        self.pool
+            .pending_transactions()
+            .into_iter()
+            .map(|tx| PendingTransactionSubmission {
+                hash: *tx.hash(),
+                sender: tx.sender(),
+                nonce: tx.nonce(),
+                submitted_at: unix_timestamp(tx.timestamp, now, system_now),
+            })
+            .collect()
+    }
+}
+
+/// Converts the monotonic `at` instant into a unix timestamp, relative to the `now` reference
+/// points.
+fn unix_timestamp(at: Instant, now: Instant, system_now: SystemTime) -> Option<u64> {
+    let elapsed = now.saturating_duration_since(at);
+    system_now.checked_sub(elapsed)?.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
 impl<Pool, Eth> TxPoolApi<Pool, Eth>
 where
     Pool: TransactionPool<Transaction: PoolTransaction<Consensus: Transaction>> + 'static,
@@ -149,3 +195,45 @@ impl<Pool, Eth> fmt::Debug for TxPoolApi<Pool, Eth> {
         f.debug_struct("TxpoolApi").finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        TransactionOrigin,
+    };
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_pending_transaction_submissions() {
+        let pool = testing_pool();
+        let tx = MockTransaction::eip1559();
+        let hash = *tx.get_hash();
+        let sender = tx.sender();
+
+        let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        pool.add_transaction(TransactionOrigin::External, tx).await.unwrap();
+        let after = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+        let api = TxPoolApi::new(pool, ());
+        let submissions = api.pending_transaction_submissions();
+        assert_eq!(submissions.len(), 1);
+
+        let submission = submissions[0];
+        assert_eq!(submission.hash, hash);
+        assert_eq!(submission.sender, sender);
+        let submitted_at = submission.submitted_at.unwrap();
+        assert!(before <= submitted_at && submitted_at <= after);
+    }
+
+    #[test]
+    fn unix_timestamp_before_epoch() {
+        let now = Instant::now();
+        let system_now = UNIX_EPOCH + Duration::from_secs(10);
+
+        assert_eq!(unix_timestamp(now, now, system_now), Some(10));
+        assert_eq!(unix_timestamp(now - Duration::from_secs(4), now, system_now), Some(6));
+        assert_eq!(unix_timestamp(now - Duration::from_secs(20), now, system_now), None);
+    }
+}
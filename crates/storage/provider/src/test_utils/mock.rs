@@ -5,9 +5,7 @@ use crate::{
     StateProvider, StateProviderBox, StateProviderFactory, StateReader, StateRootProvider,
     TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
-use alloy_consensus::{
-    constants::EMPTY_ROOT_HASH, transaction::TransactionMeta, Header, Transaction,
-};
+use alloy_consensus::{constants::EMPTY_ROOT_HASH, transaction::TransactionMeta, Header};
 use alloy_eips::{eip4895::Withdrawals, BlockHashOrNumber, BlockId, BlockNumberOrTag};
 use alloy_primitives::{
     keccak256,
@@ -24,21 +22,24 @@ use reth_db_api::{
 use reth_execution_types::ExecutionOutcome;
 use reth_node_types::NodeTypes;
 use reth_primitives::{
-    Account, Block, Bytecode, EthPrimitives, GotExpected, Receipt, RecoveredBlock, SealedBlock,
-    SealedHeader, TransactionSigned,
+    Account, Block, Bytecode, EthPrimitives, GotExpected, RecoveredBlock, SealedBlock, SealedHeader,
 };
-use reth_primitives_traits::SignedTransaction;
-use reth_prune_types::PruneModes;
+use reth_primitives_traits::{Block as _, NodePrimitives, SignedTransaction};
+use reth_prune_types::{PruneCheckpoint, PruneModes, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
 use reth_storage_api::{
     BlockBodyIndicesProvider, DBProvider, DatabaseProviderFactory, HashedPostStateProvider,
-    NodePrimitivesProvider, OmmersProvider, StageCheckpointReader, StateCommitmentProvider,
-    StateProofProvider, StorageRootProvider,
+    NodePrimitivesProvider, OmmersProvider, PruneCheckpointReader, StageCheckpointReader,
+    StateCommitmentProvider, StateProofProvider, StorageRootProvider,
 };
 use reth_storage_errors::provider::{ConsistentViewError, ProviderError, ProviderResult};
 use reth_trie::{
-    updates::TrieUpdates, AccountProof, HashedPostState, HashedStorage, MultiProof,
-    MultiProofTargets, StorageMultiProof, StorageProof, TrieInput,
+    hashed_cursor::{noop::NoopHashedCursorFactory, HashedPostStateCursorFactory},
+    proof::Proof,
+    trie_cursor::noop::NoopTrieCursorFactory,
+    updates::TrieUpdates,
+    AccountProof, HashedPostState, HashedStorage, MultiProof, MultiProofTargets, StateRoot,
+    StorageMultiProof, StorageProof, TrieInput,
 };
 use reth_trie_db::MerklePatriciaTrie;
 use std::{
@@ -49,60 +50,75 @@ use std::{
 use tokio::sync::broadcast;
 
 /// A mock implementation for Provider interfaces.
+///
+/// Blocks are stored with the transactions and receipts of the given [`NodePrimitives`].
 #[derive(Debug)]
-pub struct MockEthProvider<T = TransactionSigned, ChainSpec = reth_chainspec::ChainSpec> {
+pub struct MockEthProvider<T: NodePrimitives = EthPrimitives, ChainSpec = reth_chainspec::ChainSpec>
+{
     /// Local block store
-    pub blocks: Arc<Mutex<HashMap<B256, Block<T>>>>,
+    pub blocks: Arc<Mutex<HashMap<B256, Block<T::SignedTx>>>>,
     /// Local header store
     pub headers: Arc<Mutex<HashMap<B256, Header>>>,
+    /// Local receipt store
+    pub receipts: Arc<Mutex<HashMap<B256, Vec<T::Receipt>>>>,
     /// Local account store
     pub accounts: Arc<Mutex<HashMap<Address, ExtendedAccount>>>,
     /// Local chain spec
     pub chain_spec: Arc<ChainSpec>,
     /// Local state roots
     pub state_roots: Arc<Mutex<Vec<B256>>>,
+    /// Whether state roots and proofs are computed from the local account store
+    computes_trie: bool,
+    /// Local prune checkpoints
+    pub prune_checkpoints: Arc<Mutex<HashMap<PruneSegment, PruneCheckpoint>>>,
     tx: TxMock,
     prune_modes: Arc<PruneModes>,
 }
 
-impl<T, ChainSpec> Clone for MockEthProvider<T, ChainSpec> {
+impl<T: NodePrimitives, ChainSpec> Clone for MockEthProvider<T, ChainSpec> {
     fn clone(&self) -> Self {
         Self {
             blocks: self.blocks.clone(),
             headers: self.headers.clone(),
+            receipts: self.receipts.clone(),
             accounts: self.accounts.clone(),
             chain_spec: self.chain_spec.clone(),
             state_roots: self.state_roots.clone(),
+            computes_trie: self.computes_trie,
+            prune_checkpoints: self.prune_checkpoints.clone(),
             tx: self.tx.clone(),
             prune_modes: self.prune_modes.clone(),
         }
     }
 }
 
-impl<T> MockEthProvider<T> {
+impl<T: NodePrimitives> MockEthProvider<T> {
     /// Create a new, empty instance
     pub fn new() -> Self {
         Self {
             blocks: Default::default(),
             headers: Default::default(),
+            receipts: Default::default(),
             accounts: Default::default(),
             chain_spec: Arc::new(reth_chainspec::ChainSpecBuilder::mainnet().build()),
             state_roots: Default::default(),
+            computes_trie: false,
+            prune_checkpoints: Default::default(),
             tx: Default::default(),
             prune_modes: Default::default(),
         }
     }
 }
 
-impl<T, ChainSpec> MockEthProvider<T, ChainSpec> {
+impl<T: NodePrimitives, ChainSpec> MockEthProvider<T, ChainSpec> {
     /// Add block to local block store
-    pub fn add_block(&self, hash: B256, block: Block<T>) {
+    pub fn add_block(&self, hash: B256, block: Block<T::SignedTx>) {
         self.add_header(hash, block.header.clone());
         self.blocks.lock().insert(hash, block);
     }
 
     /// Add multiple blocks to local block store
-    pub fn extend_blocks(&self, iter: impl IntoIterator<Item = (B256, Block<T>)>) {
+    pub fn extend_blocks(&self, iter: impl IntoIterator<Item = (B256, Block<T::SignedTx>)>) {
         for (hash, block) in iter {
             self.add_header(hash, block.header.clone());
             self.add_block(hash, block)
@@ -121,6 +137,11 @@ impl<T, ChainSpec> MockEthProvider<T, ChainSpec> {
         }
     }
 
+    /// Add the receipts of a block to local receipt store
+    pub fn add_receipts(&self, hash: B256, receipts: Vec<T::Receipt>) {
+        self.receipts.lock().insert(hash, receipts);
+    }
+
     /// Add account to local account store
     pub fn add_account(&self, address: Address, account: ExtendedAccount) {
         self.accounts.lock().insert(address, account);
@@ -138,14 +159,62 @@ impl<T, ChainSpec> MockEthProvider<T, ChainSpec> {
         self.state_roots.lock().push(state_root);
     }
 
+    /// Add prune checkpoint to local prune checkpoint store
+    pub fn add_prune_checkpoint(&self, segment: PruneSegment, checkpoint: PruneCheckpoint) {
+        self.prune_checkpoints.lock().insert(segment, checkpoint);
+    }
+
+    /// Computes state roots and proofs from the local account store, instead of returning the
+    /// local state roots and empty proofs.
+    pub const fn with_computed_trie(mut self) -> Self {
+        self.computes_trie = true;
+        self
+    }
+
+    /// Returns the hashed state of the local account store, overlaid with the given state.
+    fn hashed_state_with(&self, state: HashedPostState) -> HashedPostState {
+        let mut hashed_state = HashedPostState::default();
+        for (address, account) in self.accounts.lock().iter() {
+            let hashed_address = keccak256(address);
+            hashed_state.accounts.insert(hashed_address, Some(account.account));
+            hashed_state.storages.insert(
+                hashed_address,
+                HashedStorage::from_iter(
+                    false,
+                    account.storage.iter().map(|(slot, value)| (keccak256(slot), *value)),
+                ),
+            );
+        }
+        hashed_state.extend(state);
+        hashed_state
+    }
+
+    /// Returns the state root for the given state, see [`Self::with_computed_trie`].
+    fn state_root_of(&self, state: HashedPostState) -> ProviderResult<B256> {
+        if !self.computes_trie {
+            return Ok(self.state_roots.lock().pop().unwrap_or_default())
+        }
+
+        let state = self.hashed_state_with(state).into_sorted();
+        StateRoot::new(
+            NoopTrieCursorFactory::default(),
+            HashedPostStateCursorFactory::new(NoopHashedCursorFactory::default(), &state),
+        )
+        .root()
+        .map_err(|err| ProviderError::Database(err.into()))
+    }
+
     /// Set chain spec.
     pub fn with_chain_spec<C>(self, chain_spec: C) -> MockEthProvider<T, C> {
         MockEthProvider {
             blocks: self.blocks,
             headers: self.headers,
+            receipts: self.receipts,
             accounts: self.accounts,
             chain_spec: Arc::new(chain_spec),
             state_roots: self.state_roots,
+            computes_trie: self.computes_trie,
+            prune_checkpoints: self.prune_checkpoints,
             tx: self.tx,
             prune_modes: self.prune_modes,
         }
@@ -206,13 +275,13 @@ impl NodeTypes for MockNode {
     type Storage = EthStorage;
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> StateCommitmentProvider
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> StateCommitmentProvider
     for MockEthProvider<T, ChainSpec>
 {
     type StateCommitment = <MockNode as NodeTypes>::StateCommitment;
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec + Clone + 'static> DatabaseProviderFactory
+impl<T: NodePrimitives, ChainSpec: EthChainSpec + Clone + 'static> DatabaseProviderFactory
     for MockEthProvider<T, ChainSpec>
 {
     type DB = DatabaseMock;
@@ -234,7 +303,7 @@ impl<T: Transaction, ChainSpec: EthChainSpec + Clone + 'static> DatabaseProvider
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec + 'static> DBProvider
+impl<T: NodePrimitives, ChainSpec: EthChainSpec + 'static> DBProvider
     for MockEthProvider<T, ChainSpec>
 {
     type Tx = TxMock;
@@ -256,7 +325,7 @@ impl<T: Transaction, ChainSpec: EthChainSpec + 'static> DBProvider
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> HeaderProvider for MockEthProvider<T, ChainSpec> {
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> HeaderProvider for MockEthProvider<T, ChainSpec> {
     type Header = Header;
 
     fn header(&self, block_hash: &BlockHash) -> ProviderResult<Option<Header>> {
@@ -315,7 +384,7 @@ impl<T: Transaction, ChainSpec: EthChainSpec> HeaderProvider for MockEthProvider
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec + 'static> ChainSpecProvider
+impl<T: NodePrimitives, ChainSpec: EthChainSpec + 'static> ChainSpecProvider
     for MockEthProvider<T, ChainSpec>
 {
     type ChainSpec = ChainSpec;
@@ -325,10 +394,10 @@ impl<T: Transaction, ChainSpec: EthChainSpec + 'static> ChainSpecProvider
     }
 }
 
-impl<T: SignedTransaction, ChainSpec: EthChainSpec> TransactionsProvider
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> TransactionsProvider
     for MockEthProvider<T, ChainSpec>
 {
-    type Transaction = T;
+    type Transaction = T::SignedTx;
 
     fn transaction_id(&self, tx_hash: TxHash) -> ProviderResult<Option<TxNumber>> {
         let lock = self.blocks.lock();
@@ -466,35 +535,42 @@ impl<T: SignedTransaction, ChainSpec: EthChainSpec> TransactionsProvider
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> ReceiptProvider for MockEthProvider<T, ChainSpec> {
-    type Receipt = Receipt;
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> ReceiptProvider for MockEthProvider<T, ChainSpec> {
+    type Receipt = T::Receipt;
 
-    fn receipt(&self, _id: TxNumber) -> ProviderResult<Option<Receipt>> {
+    fn receipt(&self, _id: TxNumber) -> ProviderResult<Option<Self::Receipt>> {
         Ok(None)
     }
 
-    fn receipt_by_hash(&self, _hash: TxHash) -> ProviderResult<Option<Receipt>> {
+    fn receipt_by_hash(&self, _hash: TxHash) -> ProviderResult<Option<Self::Receipt>> {
         Ok(None)
     }
 
-    fn receipts_by_block(&self, _block: BlockHashOrNumber) -> ProviderResult<Option<Vec<Receipt>>> {
-        Ok(None)
+    fn receipts_by_block(
+        &self,
+        block: BlockHashOrNumber,
+    ) -> ProviderResult<Option<Vec<Self::Receipt>>> {
+        let hash = match block {
+            BlockHashOrNumber::Hash(hash) => Some(hash),
+            BlockHashOrNumber::Number(num) => self.block_hash(num)?,
+        };
+        Ok(hash.and_then(|hash| self.receipts.lock().get(&hash).cloned()))
     }
 
     fn receipts_by_tx_range(
         &self,
         _range: impl RangeBounds<TxNumber>,
-    ) -> ProviderResult<Vec<Receipt>> {
+    ) -> ProviderResult<Vec<Self::Receipt>> {
         Ok(vec![])
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> ReceiptProviderIdExt
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> ReceiptProviderIdExt
     for MockEthProvider<T, ChainSpec>
 {
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> BlockHashReader for MockEthProvider<T, ChainSpec> {
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> BlockHashReader for MockEthProvider<T, ChainSpec> {
     fn block_hash(&self, number: u64) -> ProviderResult<Option<B256>> {
         let lock = self.blocks.lock();
 
@@ -518,7 +594,7 @@ impl<T: Transaction, ChainSpec: EthChainSpec> BlockHashReader for MockEthProvide
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> BlockNumReader for MockEthProvider<T, ChainSpec> {
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> BlockNumReader for MockEthProvider<T, ChainSpec> {
     fn chain_info(&self) -> ProviderResult<ChainInfo> {
         let best_block_number = self.best_block_number()?;
         let lock = self.headers.lock();
@@ -549,7 +625,7 @@ impl<T: Transaction, ChainSpec: EthChainSpec> BlockNumReader for MockEthProvider
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> BlockIdReader for MockEthProvider<T, ChainSpec> {
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> BlockIdReader for MockEthProvider<T, ChainSpec> {
     fn pending_block_num_hash(&self) -> ProviderResult<Option<alloy_eips::BlockNumHash>> {
         Ok(None)
     }
@@ -563,8 +639,8 @@ impl<T: Transaction, ChainSpec: EthChainSpec> BlockIdReader for MockEthProvider<
     }
 }
 
-impl<T: SignedTransaction, ChainSpec: EthChainSpec> BlockReader for MockEthProvider<T, ChainSpec> {
-    type Block = Block<T>;
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> BlockReader for MockEthProvider<T, ChainSpec> {
+    type Block = Block<T::SignedTx>;
 
     fn find_block_by_hash(
         &self,
@@ -592,24 +668,26 @@ impl<T: SignedTransaction, ChainSpec: EthChainSpec> BlockReader for MockEthProvi
 
     fn pending_block_and_receipts(
         &self,
-    ) -> ProviderResult<Option<(SealedBlock<Self::Block>, Vec<Receipt>)>> {
+    ) -> ProviderResult<Option<(SealedBlock<Self::Block>, Vec<T::Receipt>)>> {
         Ok(None)
     }
 
     fn block_with_senders(
         &self,
-        _id: BlockHashOrNumber,
+        id: BlockHashOrNumber,
         _transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<RecoveredBlock<Self::Block>>> {
-        Ok(None)
+        self.block(id)?
+            .map(|block| block.try_into_recovered().map_err(|_| ProviderError::SenderRecoveryError))
+            .transpose()
     }
 
     fn sealed_block_with_senders(
         &self,
-        _id: BlockHashOrNumber,
-        _transaction_kind: TransactionVariant,
+        id: BlockHashOrNumber,
+        transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<RecoveredBlock<Self::Block>>> {
-        Ok(None)
+        self.block_with_senders(id, transaction_kind)
     }
 
     fn block_range(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Vec<Self::Block>> {
@@ -637,10 +715,10 @@ impl<T: SignedTransaction, ChainSpec: EthChainSpec> BlockReader for MockEthProvi
     }
 }
 
-impl<T: SignedTransaction, ChainSpec: EthChainSpec> BlockReaderIdExt
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> BlockReaderIdExt
     for MockEthProvider<T, ChainSpec>
 {
-    fn block_by_id(&self, id: BlockId) -> ProviderResult<Option<Block<T>>> {
+    fn block_by_id(&self, id: BlockId) -> ProviderResult<Option<Block<T::SignedTx>>> {
         match id {
             BlockId::Number(num) => self.block_by_number_or_tag(num),
             BlockId::Hash(hash) => self.block_by_hash(hash.block_hash),
@@ -666,13 +744,13 @@ impl<T: SignedTransaction, ChainSpec: EthChainSpec> BlockReaderIdExt
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> AccountReader for MockEthProvider<T, ChainSpec> {
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> AccountReader for MockEthProvider<T, ChainSpec> {
     fn basic_account(&self, address: &Address) -> ProviderResult<Option<Account>> {
         Ok(self.accounts.lock().get(address).cloned().map(|a| a.account))
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> StageCheckpointReader
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> StageCheckpointReader
     for MockEthProvider<T, ChainSpec>
 {
     fn get_stage_checkpoint(&self, _id: StageId) -> ProviderResult<Option<StageCheckpoint>> {
@@ -688,33 +766,55 @@ impl<T: Transaction, ChainSpec: EthChainSpec> StageCheckpointReader
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> StateRootProvider for MockEthProvider<T, ChainSpec> {
-    fn state_root(&self, _state: HashedPostState) -> ProviderResult<B256> {
-        Ok(self.state_roots.lock().pop().unwrap_or_default())
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> PruneCheckpointReader
+    for MockEthProvider<T, ChainSpec>
+{
+    fn get_prune_checkpoint(
+        &self,
+        segment: PruneSegment,
+    ) -> ProviderResult<Option<PruneCheckpoint>> {
+        Ok(self.prune_checkpoints.lock().get(&segment).copied())
+    }
+
+    fn get_prune_checkpoints(&self) -> ProviderResult<Vec<(PruneSegment, PruneCheckpoint)>> {
+        Ok(self
+            .prune_checkpoints
+            .lock()
+            .iter()
+            .map(|(segment, checkpoint)| (*segment, *checkpoint))
+            .collect())
     }
+}
 
-    fn state_root_from_nodes(&self, _input: TrieInput) -> ProviderResult<B256> {
-        Ok(self.state_roots.lock().pop().unwrap_or_default())
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> StateRootProvider
+    for MockEthProvider<T, ChainSpec>
+{
+    fn state_root(&self, state: HashedPostState) -> ProviderResult<B256> {
+        self.state_root_of(state)
+    }
+
+    fn state_root_from_nodes(&self, input: TrieInput) -> ProviderResult<B256> {
+        self.state_root_of(input.state)
     }
 
     fn state_root_with_updates(
         &self,
-        _state: HashedPostState,
+        state: HashedPostState,
     ) -> ProviderResult<(B256, TrieUpdates)> {
-        let state_root = self.state_roots.lock().pop().unwrap_or_default();
+        let state_root = self.state_root_of(state)?;
         Ok((state_root, Default::default()))
     }
 
     fn state_root_from_nodes_with_updates(
         &self,
-        _input: TrieInput,
+        input: TrieInput,
     ) -> ProviderResult<(B256, TrieUpdates)> {
-        let state_root = self.state_roots.lock().pop().unwrap_or_default();
+        let state_root = self.state_root_of(input.state)?;
         Ok((state_root, Default::default()))
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> StorageRootProvider
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> StorageRootProvider
     for MockEthProvider<T, ChainSpec>
 {
     fn storage_root(
@@ -744,14 +844,26 @@ impl<T: Transaction, ChainSpec: EthChainSpec> StorageRootProvider
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> StateProofProvider for MockEthProvider<T, ChainSpec> {
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> StateProofProvider
+    for MockEthProvider<T, ChainSpec>
+{
     fn proof(
         &self,
-        _input: TrieInput,
+        input: TrieInput,
         address: Address,
-        _slots: &[B256],
+        slots: &[B256],
     ) -> ProviderResult<AccountProof> {
-        Ok(AccountProof::new(address))
+        if !self.computes_trie {
+            return Ok(AccountProof::new(address))
+        }
+
+        let state = self.hashed_state_with(input.state).into_sorted();
+        Proof::new(
+            NoopTrieCursorFactory::default(),
+            HashedPostStateCursorFactory::new(NoopHashedCursorFactory::default(), &state),
+        )
+        .account_proof(address, slots)
+        .map_err(ProviderError::from)
     }
 
     fn multiproof(
@@ -771,7 +883,7 @@ impl<T: Transaction, ChainSpec: EthChainSpec> StateProofProvider for MockEthProv
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec + 'static> HashedPostStateProvider
+impl<T: NodePrimitives, ChainSpec: EthChainSpec + 'static> HashedPostStateProvider
     for MockEthProvider<T, ChainSpec>
 {
     fn hashed_post_state(&self, _state: &revm_database::BundleState) -> HashedPostState {
@@ -779,7 +891,7 @@ impl<T: Transaction, ChainSpec: EthChainSpec + 'static> HashedPostStateProvider
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec + 'static> StateProvider
+impl<T: NodePrimitives, ChainSpec: EthChainSpec + 'static> StateProvider
     for MockEthProvider<T, ChainSpec>
 {
     fn storage(
@@ -804,7 +916,7 @@ impl<T: Transaction, ChainSpec: EthChainSpec + 'static> StateProvider
     }
 }
 
-impl<T: SignedTransaction, ChainSpec: EthChainSpec + 'static> StateProviderFactory
+impl<T: NodePrimitives, ChainSpec: EthChainSpec + 'static> StateProviderFactory
     for MockEthProvider<T, ChainSpec>
 {
     fn latest(&self) -> ProviderResult<StateProviderBox> {
@@ -858,7 +970,7 @@ impl<T: SignedTransaction, ChainSpec: EthChainSpec + 'static> StateProviderFacto
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> WithdrawalsProvider
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> WithdrawalsProvider
     for MockEthProvider<T, ChainSpec>
 {
     fn withdrawals_by_block(
@@ -870,13 +982,13 @@ impl<T: Transaction, ChainSpec: EthChainSpec> WithdrawalsProvider
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> OmmersProvider for MockEthProvider<T, ChainSpec> {
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> OmmersProvider for MockEthProvider<T, ChainSpec> {
     fn ommers(&self, _id: BlockHashOrNumber) -> ProviderResult<Option<Vec<Header>>> {
         Ok(None)
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> BlockBodyIndicesProvider
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> BlockBodyIndicesProvider
     for MockEthProvider<T, ChainSpec>
 {
     fn block_body_indices(&self, _num: u64) -> ProviderResult<Option<StoredBlockBodyIndices>> {
@@ -890,7 +1002,7 @@ impl<T: Transaction, ChainSpec: EthChainSpec> BlockBodyIndicesProvider
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> ChangeSetReader for MockEthProvider<T, ChainSpec> {
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> ChangeSetReader for MockEthProvider<T, ChainSpec> {
     fn account_block_changeset(
         &self,
         _block_number: BlockNumber,
@@ -899,24 +1011,27 @@ impl<T: Transaction, ChainSpec: EthChainSpec> ChangeSetReader for MockEthProvide
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> StateReader for MockEthProvider<T, ChainSpec> {
-    type Receipt = Receipt;
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> StateReader for MockEthProvider<T, ChainSpec> {
+    type Receipt = T::Receipt;
 
-    fn get_state(&self, _block: BlockNumber) -> ProviderResult<Option<ExecutionOutcome>> {
+    fn get_state(
+        &self,
+        _block: BlockNumber,
+    ) -> ProviderResult<Option<ExecutionOutcome<Self::Receipt>>> {
         Ok(None)
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> CanonStateSubscriptions
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> CanonStateSubscriptions
     for MockEthProvider<T, ChainSpec>
 {
-    fn subscribe_to_canonical_state(&self) -> CanonStateNotifications<EthPrimitives> {
+    fn subscribe_to_canonical_state(&self) -> CanonStateNotifications<T> {
         broadcast::channel(1).1
     }
 }
 
-impl<T: Transaction, ChainSpec: EthChainSpec> NodePrimitivesProvider
+impl<T: NodePrimitives, ChainSpec: EthChainSpec> NodePrimitivesProvider
     for MockEthProvider<T, ChainSpec>
 {
-    type Primitives = EthPrimitives;
+    type Primitives = T;
 }